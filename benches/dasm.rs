@@ -1,10 +1,10 @@
-use criterion::{criterion_group, criterion_main, BatchSize, Criterion, Throughput};
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion, Throughput};
 use std::fs;
 use std::io::{self, BufReader, Cursor};
 use std::path::PathBuf;
 
 use spike_dasm_rs::disasm::Disassembler;
-use spike_dasm_rs::instruction;
+use spike_dasm_rs::instruction::{self, InstructionBits};
 use spike_dasm_rs::{Extensions, Xlen};
 
 const INPUT_DIR: &str = "inputs/sim-logs-no-dasm";
@@ -46,5 +46,44 @@ pub fn criterion_benchmark(c: &mut Criterion) {
     group.finish();
 }
 
-criterion_group!(benches, criterion_benchmark);
+pub fn lookup_benchmark(c: &mut Criterion) {
+    // A pseudo-random mix of compressed and 32-bit instructions.
+    let mut x: u32 = 1;
+    let inst_bits: Vec<InstructionBits> = (0..10_000)
+        .filter_map(|_| {
+            x = x.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+            InstructionBits::new(u64::from(x >> 2)).ok()
+        })
+        .collect();
+    let extensions = Extensions::IMAFDC | Extensions::V | Extensions::ZBA | Extensions::ZBB;
+
+    let mut group = c.benchmark_group("lookup-benchmark");
+    group.throughput(Throughput::Elements(inst_bits.len() as u64));
+    group.bench_function("linear-search", |b| {
+        // The decode strategy `Disassembler` used before its decode table: the first matching
+        // filter in priority order.
+        let instructions = instruction::gen_instructions(Xlen::Rv64, extensions, true);
+        b.iter(|| {
+            for &x in &inst_bits {
+                black_box(
+                    instructions
+                        .iter()
+                        .find(|inst| inst.is_eq(x))
+                        .map(|inst| inst.decode(x).to_string()),
+                );
+            }
+        })
+    });
+    group.bench_function("decode-table", |b| {
+        let disasm = Disassembler::new(instruction::gen_instructions(Xlen::Rv64, extensions, true));
+        b.iter(|| {
+            for &x in &inst_bits {
+                black_box(disasm.fmt_inst(x).ok());
+            }
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, criterion_benchmark, lookup_benchmark);
 criterion_main!(benches);
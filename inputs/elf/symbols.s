@@ -0,0 +1,39 @@
+# Small RV64IMAC program used by the ELF tests.
+#
+# Regenerate `symbols.o` with:
+#   llvm-mc -triple=riscv64 -mattr=+m,+a,+c -filetype=obj symbols.s -o symbols.o
+
+    .attribute arch, "rv64i2p0_m2p0_a2p0_c2p0"
+
+    .text
+    .globl _start
+    .type _start, @function
+_start:
+    li      sp, 0x1000
+    call    main
+1:
+    j       1b
+    .size _start, .-_start
+
+    .globl main
+    .type main, @function
+main:
+    addi    sp, sp, -16
+    sd      ra, 8(sp)
+    li      a0, 3
+    jal     ra, square
+    ld      ra, 8(sp)
+    addi    sp, sp, 16
+    ret
+    .size main, .-main
+
+square:
+    mul     a0, a0, a0
+    ret
+
+    .data
+    .globl counter
+    .type counter, @object
+counter:
+    .dword  0
+    .size counter, 8
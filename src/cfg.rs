@@ -0,0 +1,515 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::io::{self, BufRead, Write};
+
+use super::decode::{DecodedInstruction, InstructionClass};
+use super::disasm::Disassembler;
+use super::instruction::{self, InstructionBits, InstructionLen};
+use super::io::for_each_trace_inst;
+use super::trace::TraceFormat;
+
+/// How control passes from one basic block to another.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum EdgeKind {
+    /// Running on into the next block, including past a branch that isn't taken.
+    FallThrough,
+    /// A taken branch.
+    Taken,
+    /// A jump that's neither a call nor a return.
+    Jump,
+    Call,
+    Return,
+    /// From a call to its return address, where execution continues once the callee returns.
+    CallReturn,
+    /// Any other change of flow, such as a trap, a trap return or an interrupt.
+    Trap,
+}
+
+impl EdgeKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::FallThrough => "fallthrough",
+            Self::Taken => "taken",
+            Self::Jump => "jump",
+            Self::Call => "call",
+            Self::Return => "return",
+            Self::CallReturn => "callreturn",
+            Self::Trap => "trap",
+        }
+    }
+
+    /// Returns how control passes from the last instruction of a block, `last`, to `to`.
+    fn from_transfer(last: &DecodedInstruction, to: u64) -> Self {
+        if Some(to) == next_pc(last) {
+            return Self::FallThrough;
+        }
+        match last.class() {
+            InstructionClass::Branch => Self::Taken,
+            InstructionClass::Jump if last.is_call() => Self::Call,
+            InstructionClass::Jump if last.is_return() => Self::Return,
+            InstructionClass::Jump => Self::Jump,
+            _ => Self::Trap,
+        }
+    }
+}
+
+/// A run of instructions that's only entered at its first instruction and only left after its
+/// last one.
+#[derive(Clone, Debug)]
+pub struct BasicBlock {
+    /// The address of the first instruction.
+    pub start: u64,
+    /// The address just past the last instruction.
+    pub end: u64,
+    pub instructions: Vec<DecodedInstruction>,
+    /// The number of times the block was entered in the trace.
+    pub count: u64,
+}
+
+/// An edge of a control-flow graph, between the blocks starting at `from` and `to`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Edge {
+    pub from: u64,
+    pub to: u64,
+    pub kind: EdgeKind,
+    /// The number of times the edge was taken in the trace.
+    pub count: u64,
+}
+
+/// A control-flow graph of basic blocks, built by a `CfgBuilder`.
+#[derive(Clone, Debug, Default)]
+pub struct ControlFlowGraph {
+    blocks: BTreeMap<u64, BasicBlock>,
+    edges: Vec<Edge>,
+    /// Whether any instruction was executed, in which case blocks and edges have counts.
+    executed: bool,
+}
+
+/// Returns the address of the instruction following `decoded`.
+fn next_pc(decoded: &DecodedInstruction) -> Option<u64> {
+    let len = decoded.bits.length.bytes() as u64;
+    decoded.pc.map(|pc| pc.wrapping_add(len))
+}
+
+/// Returns `true` if `decoded` ends a basic block: branches, jumps, and system instructions
+/// other than fences (including `sfence.vma` and `hfence.*`), which may trap or return from a
+/// trap.
+fn ends_block(decoded: &DecodedInstruction) -> bool {
+    match decoded.class() {
+        InstructionClass::Branch | InstructionClass::Jump => true,
+        InstructionClass::System => {
+            let bits = decoded.bits.bits as u32;
+            let is_fence = decoded.bits.length != InstructionLen::TwoByte
+                && match bits & 0x7f {
+                    // MISC-MEM: `fence`, `fence.tso` and `fence.i`.
+                    0b000_1111 => true,
+                    // SYSTEM with `funct3` 0: `sfence.vma`, `hfence.vvma` and `hfence.gvma`.
+                    0b111_0011 => {
+                        (bits >> 12) & 0b111 == 0
+                            && matches!(bits >> 25, 0b000_1001 | 0b001_0001 | 0b011_0001)
+                    }
+                    _ => false,
+                };
+            !is_fence
+        }
+        _ => false,
+    }
+}
+
+/// Collects instructions, from a binary or in the order a trace executes them, to build a
+/// `ControlFlowGraph`.
+///
+/// Blocks are split after every branch, jump and system instruction (see
+/// `DecodedInstruction::class`), at the targets of branches and direct jumps (see
+/// `DecodedInstruction::static_target`), and wherever the trace enters or leaves an instruction
+/// other than by running on to the next one, such as at the targets of indirect jumps and at
+/// traps. Instructions without a PC are ignored.
+#[derive(Clone, Debug, Default)]
+pub struct CfgBuilder {
+    instructions: BTreeMap<u64, DecodedInstruction>,
+    /// The number of times each PC was executed.
+    counts: HashMap<u64, u64>,
+    /// The number of times each pair of PCs was executed one after the other.
+    transitions: HashMap<(u64, u64), u64>,
+    /// The PCs where runs of executed instructions start.
+    entries: BTreeSet<u64>,
+    /// The PC of the last instruction executed, unless there's a gap since.
+    last: Option<u64>,
+}
+
+impl CfgBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an instruction from a binary, without executing it.
+    pub fn add(&mut self, decoded: DecodedInstruction) {
+        if let Some(pc) = decoded.pc {
+            self.instructions.insert(pc, decoded);
+        }
+    }
+
+    /// Adds an instruction executed right after the last one added by `add_executed`, unless
+    /// `add_gap` was called in between.
+    pub fn add_executed(&mut self, decoded: DecodedInstruction) {
+        let pc = match decoded.pc {
+            Some(pc) => pc,
+            None => return self.add_gap(),
+        };
+        *self.counts.entry(pc).or_default() += 1;
+        match self.last {
+            Some(last) => *self.transitions.entry((last, pc)).or_default() += 1,
+            None => {
+                self.entries.insert(pc);
+            }
+        }
+        self.last = Some(pc);
+        self.add(decoded);
+    }
+
+    /// Marks a gap in the executed instructions, such as one that couldn't be decoded, so the
+    /// next one isn't taken to follow the last one.
+    pub fn add_gap(&mut self) {
+        self.last = None;
+    }
+
+    /// Adds the instructions in `data`, which is located at `address`, as a linear listing would
+    /// find them (see `listing::write_listing`). Instructions that can't be decoded are skipped.
+    pub fn add_code(&mut self, address: u64, data: &[u8], disasm: &Disassembler) {
+        let mut offset = 0;
+        while offset < data.len() {
+            let remaining = &data[offset..];
+            let parcel = match remaining {
+                [lo, hi, ..] => u16::from_le_bytes([*lo, *hi]),
+                _ => break,
+            };
+            let len = match instruction::encoded_len(parcel) {
+                Some(len) if len <= remaining.len() => len,
+                Some(_) => break,
+                None => 2,
+            };
+
+            let mut bytes = [0; 8];
+            let available = len.min(8);
+            bytes[..available].copy_from_slice(&remaining[..available]);
+            let pc = address + offset as u64;
+            let decoded = InstructionBits::new(u64::from_le_bytes(bytes))
+                .and_then(|x| disasm.decode_at(x, Some(pc)));
+            if let Ok(decoded) = decoded {
+                self.add(decoded);
+            }
+            offset += len;
+        }
+    }
+
+    /// Adds the instructions that `format` finds in `istream` (or that the detected format finds;
+    /// see `io::process_trace`) as executed, leaving gaps at instructions without a PC or that
+    /// `disasm` can't decode.
+    pub fn add_trace<I: BufRead>(
+        &mut self,
+        istream: I,
+        disasm: &Disassembler,
+        format: Option<&dyn TraceFormat>,
+    ) -> io::Result<()> {
+        for_each_trace_inst(istream, format, |inst| {
+            let decoded = match inst.pc {
+                Some((pc, _)) => {
+                    InstructionBits::new(inst.bits).and_then(|x| disasm.decode_at(x, Some(pc)))
+                }
+                None => return self.add_gap(),
+            };
+            match decoded {
+                Ok(decoded) => self.add_executed(decoded),
+                Err(_) => self.add_gap(),
+            }
+        })
+    }
+
+    /// Splits the instructions into basic blocks and connects them.
+    ///
+    /// Edges are added for the targets of branches and direct jumps, for not-taken branches, for
+    /// running on into the next block, and from calls to their return addresses, as long as the
+    /// block they lead to is known. Every pair of blocks executed one after the other adds an
+    /// edge too, which is counted along with those.
+    pub fn build(&self) -> ControlFlowGraph {
+        // Blocks start wherever execution starts or jumps to, and end wherever it jumps from.
+        let mut leaders: HashSet<u64> = self.entries.iter().copied().collect();
+        let mut exits = HashSet::new();
+        for &(from, to) in self.transitions.keys() {
+            if next_pc(&self.instructions[&from]) != Some(to) {
+                exits.insert(from);
+                leaders.insert(to);
+            }
+        }
+        leaders.extend(
+            self.instructions
+                .values()
+                .filter_map(DecodedInstruction::static_target),
+        );
+
+        let mut blocks: BTreeMap<u64, BasicBlock> = BTreeMap::new();
+        let mut current: Option<BasicBlock> = None;
+        for (&pc, decoded) in self.instructions.iter() {
+            let block = match current.as_mut() {
+                Some(block) if block.end == pc && !leaders.contains(&pc) => block,
+                _ => {
+                    if let Some(block) = current.take() {
+                        blocks.insert(block.start, block);
+                    }
+                    current.insert(BasicBlock {
+                        start: pc,
+                        end: pc,
+                        instructions: Vec::new(),
+                        count: self.counts.get(&pc).copied().unwrap_or(0),
+                    })
+                }
+            };
+            block.end = next_pc(decoded).unwrap_or(pc);
+            block.instructions.push(*decoded);
+            if ends_block(decoded) || exits.contains(&pc) {
+                blocks.insert(block.start, current.take().unwrap());
+            }
+        }
+        if let Some(block) = current {
+            blocks.insert(block.start, block);
+        }
+
+        let mut edges: BTreeMap<(u64, u64), Edge> = BTreeMap::new();
+        let mut add_edge = |from: u64, to: u64, kind: EdgeKind, count: u64| {
+            edges
+                .entry((from, to))
+                .or_insert(Edge {
+                    from,
+                    to,
+                    kind,
+                    count: 0,
+                })
+                .count += count;
+        };
+        for block in blocks.values() {
+            let last = block.instructions.last().unwrap();
+            if let Some(target) = last.static_target().filter(|to| blocks.contains_key(to)) {
+                add_edge(
+                    block.start,
+                    target,
+                    EdgeKind::from_transfer(last, target),
+                    0,
+                );
+            }
+            let falls_through = !ends_block(last) || last.class() == InstructionClass::Branch;
+            if falls_through && blocks.contains_key(&block.end) {
+                add_edge(block.start, block.end, EdgeKind::FallThrough, 0);
+            }
+            if last.is_call() && blocks.contains_key(&block.end) {
+                add_edge(block.start, block.end, EdgeKind::CallReturn, 0);
+            }
+        }
+        for (&(from, to), &count) in self.transitions.iter() {
+            // Other transitions are within a block.
+            if !blocks.contains_key(&to) {
+                continue;
+            }
+            if let Some((&start, block)) = blocks.range(..=from).next_back() {
+                let last = block.instructions.last().unwrap();
+                if last.pc == Some(from) {
+                    add_edge(start, to, EdgeKind::from_transfer(last, to), count);
+                }
+            }
+        }
+
+        ControlFlowGraph {
+            blocks,
+            edges: edges.into_values().collect(),
+            executed: !self.counts.is_empty(),
+        }
+    }
+}
+
+/// Escapes `s` for a double-quoted Graphviz string.
+fn escape_dot(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+impl ControlFlowGraph {
+    /// Builds the graph of the instructions executed in a trace; see `CfgBuilder::add_trace`.
+    pub fn from_trace<I: BufRead>(
+        istream: I,
+        disasm: &Disassembler,
+        format: Option<&dyn TraceFormat>,
+    ) -> io::Result<Self> {
+        let mut builder = CfgBuilder::new();
+        builder.add_trace(istream, disasm, format)?;
+        Ok(builder.build())
+    }
+
+    /// Returns the blocks in address order.
+    pub fn blocks(&self) -> impl Iterator<Item = &BasicBlock> {
+        self.blocks.values()
+    }
+
+    /// Returns the block starting at `start`.
+    pub fn block(&self, start: u64) -> Option<&BasicBlock> {
+        self.blocks.get(&start)
+    }
+
+    /// Returns the edges, ordered by the blocks they leave and then the blocks they enter.
+    pub fn edges(&self) -> &[Edge] {
+        &self.edges
+    }
+
+    /// Writes the graph in Graphviz DOT, with a node for each block listing its instructions as
+    /// `disasm` shows them, and an edge labelled with its kind. Calls, returns and traps are
+    /// dashed, and edges from calls to their return addresses dotted. If the graph was built from a
+    /// trace, blocks and edges are labelled with their execution counts too.
+    pub fn write_dot<W: Write>(&self, w: &mut W, disasm: &Disassembler) -> io::Result<()> {
+        writeln!(w, "digraph cfg {{")?;
+        writeln!(w, "    node [shape=box, fontname=\"monospace\"];")?;
+        for block in self.blocks.values() {
+            let mut label = format!("{:x}", block.start);
+            if let Some(symbol) = disasm.lookup_symbol(block.start) {
+                label += &format!(" {}", escape_dot(&symbol.to_string()));
+            }
+            label += ":";
+            if self.executed {
+                label += &format!(" executed: {}", block.count);
+            }
+            label += "\\l";
+            for decoded in block.instructions.iter() {
+                label += &format!("  {}\\l", escape_dot(&disasm.display(decoded).to_string()));
+            }
+            writeln!(w, "    \"{:#x}\" [label=\"{}\"];", block.start, label)?;
+        }
+        for edge in self.edges.iter() {
+            write!(
+                w,
+                "    \"{:#x}\" -> \"{:#x}\" [label=\"{}",
+                edge.from,
+                edge.to,
+                edge.kind.name()
+            )?;
+            if self.executed {
+                write!(w, " {}", edge.count)?;
+            }
+            w.write_all(b"\"")?;
+            if matches!(
+                edge.kind,
+                EdgeKind::Call | EdgeKind::Return | EdgeKind::Trap
+            ) {
+                w.write_all(b", style=dashed")?;
+            } else if edge.kind == EdgeKind::CallReturn {
+                w.write_all(b", style=dotted")?;
+            }
+            writeln!(w, "];")?;
+        }
+        writeln!(w, "}}")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::decode::TargetStyle;
+    use crate::test_util::{disassembler, rocket_trace};
+
+    // A loop that counts `a0` down from 3, then an `ecall` whose handler returns past it.
+    const TRACE: &[(u64, &str)] = &[
+        (0x80000000, "00300513"),
+        (0x80000004, "fff50513"),
+        (0x80000008, "fe051ee3"),
+        (0x80000004, "fff50513"),
+        (0x80000008, "fe051ee3"),
+        (0x80000004, "fff50513"),
+        (0x80000008, "fe051ee3"),
+        (0x8000000c, "00000073"),
+        (0x80000100, "30200073"),
+        (0x80000010, "0000006f"),
+    ];
+
+    fn trace_cfg() -> (ControlFlowGraph, Disassembler) {
+        let disasm = disassembler("rv64gc").with_target_style(TargetStyle::Absolute);
+        let trace = rocket_trace(TRACE);
+        let cfg = ControlFlowGraph::from_trace(trace.as_bytes(), &disasm, None).unwrap();
+        (cfg, disasm)
+    }
+
+    #[test]
+    fn blocks_and_edges() {
+        let (cfg, _) = trace_cfg();
+        let blocks: Vec<_> = cfg
+            .blocks()
+            .map(|block| {
+                (
+                    block.start,
+                    block.end,
+                    block.instructions.len(),
+                    block.count,
+                )
+            })
+            .collect();
+        assert_eq!(
+            blocks,
+            [
+                (0x80000000, 0x80000004, 1, 1),
+                (0x80000004, 0x8000000c, 2, 3),
+                (0x8000000c, 0x80000010, 1, 1),
+                (0x80000010, 0x80000014, 1, 1),
+                (0x80000100, 0x80000104, 1, 1),
+            ]
+        );
+        assert_eq!(cfg.block(0x80000008).map(|block| block.start), None);
+
+        let edges: Vec<_> = cfg
+            .edges()
+            .iter()
+            .map(|edge| (edge.from, edge.to, edge.kind, edge.count))
+            .collect();
+        assert_eq!(
+            edges,
+            [
+                (0x80000000, 0x80000004, EdgeKind::FallThrough, 1),
+                (0x80000004, 0x80000004, EdgeKind::Taken, 2),
+                (0x80000004, 0x8000000c, EdgeKind::FallThrough, 1),
+                (0x8000000c, 0x80000100, EdgeKind::Trap, 1),
+                (0x80000010, 0x80000010, EdgeKind::Jump, 0),
+                (0x80000100, 0x80000010, EdgeKind::Trap, 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn fences_dont_end_blocks() {
+        let disasm = disassembler("rv64gch");
+        let ends = |bits| ends_block(&disasm.decode(InstructionBits::new(bits).unwrap()).unwrap());
+        // `fence`, `fence.i`, `sfence.vma zero, zero`, `hfence.vvma zero, zero` and
+        // `hfence.gvma zero, zero`.
+        for bits in [0x0ff0000f, 0x0000100f, 0x12000073, 0x22000073, 0x62000073] {
+            assert!(!ends(bits), "{:x}", bits);
+        }
+        // `ecall`, `mret`, `wfi` and `c.ebreak`.
+        for bits in [0x00000073, 0x30200073, 0x10500073, 0x9002] {
+            assert!(ends(bits), "{:x}", bits);
+        }
+    }
+
+    #[test]
+    fn dot() {
+        let (cfg, disasm) = trace_cfg();
+        let mut output = Vec::new();
+        cfg.write_dot(&mut output, &disasm).unwrap();
+        let expected = r#"digraph cfg {
+    node [shape=box, fontname="monospace"];
+    "0x80000000" [label="80000000: executed: 1\l  li      a0, 3\l"];
+    "0x80000004" [label="80000004: executed: 3\l  addi    a0, a0, -1\l  bnez    a0, 0x80000004\l"];
+    "0x8000000c" [label="8000000c: executed: 1\l  ecall\l"];
+    "0x80000010" [label="80000010: executed: 1\l  j       0x80000010\l"];
+    "0x80000100" [label="80000100: executed: 1\l  mret\l"];
+    "0x80000000" -> "0x80000004" [label="fallthrough 1"];
+    "0x80000004" -> "0x80000004" [label="taken 2"];
+    "0x80000004" -> "0x8000000c" [label="fallthrough 1"];
+    "0x8000000c" -> "0x80000100" [label="trap 1", style=dashed];
+    "0x80000010" -> "0x80000010" [label="jump 0"];
+    "0x80000100" -> "0x80000010" [label="trap 1", style=dashed];
+}
+"#;
+        assert_eq!(String::from_utf8(output).unwrap(), expected);
+    }
+}
@@ -70,6 +70,7 @@ pub fn lookup_csr(csr_id: u32) -> Option<&'static str> {
         0x242 => Some("vscause"),
         0x243 => Some("vstval"),
         0x244 => Some("vsip"),
+        0x24d => Some("vstimecmp"),
         0x280 => Some("vsatp"),
         0x600 => Some("hstatus"),
         0x602 => Some("hedeleg"),
@@ -78,11 +79,13 @@ pub fn lookup_csr(csr_id: u32) -> Option<&'static str> {
         0x605 => Some("htimedelta"),
         0x606 => Some("hcounteren"),
         0x607 => Some("hgeie"),
+        0x60a => Some("henvcfg"),
         0x643 => Some("htval"),
         0x644 => Some("hip"),
         0x645 => Some("hvip"),
         0x64a => Some("htinst"),
         0x680 => Some("hgatp"),
+        0x6a8 => Some("hcontext"),
         0xe12 => Some("hgeip"),
         0x7 => Some("utvt"),
         0x45 => Some("unxti"),
@@ -207,6 +210,8 @@ pub fn lookup_csr(csr_id: u32) -> Option<&'static str> {
         0xf13 => Some("mimpid"),
         0xf14 => Some("mhartid"),
         0x615 => Some("htimedeltah"),
+        0x61a => Some("henvcfgh"),
+        0x25d => Some("vstimecmph"),
         0xc80 => Some("cycleh"),
         0xc81 => Some("timeh"),
         0xc82 => Some("instreth"),
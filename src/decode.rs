@@ -0,0 +1,556 @@
+use std::fmt;
+
+use super::csrs;
+use super::instruction::{InstructionBits, InstructionFilter, InstructionLen};
+use super::registers::{RegisterNames, VEC_REGISTER_NAMES};
+use super::symbols::SymbolTable;
+use super::{Extensions, Xlen};
+
+const INSTRUCTION_NAME_WIDTH: usize = 7;
+
+/// The maximum number of operands of any instruction (e.g. `vmacc.vv vd, vs1, vs2, v0.t`).
+pub const MAX_OPERANDS: usize = 4;
+
+/// The encoding format of an instruction, as named in the RISC-V specifications.
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum InstructionFormat {
+    R,
+    R4,
+    I,
+    S,
+    B,
+    U,
+    J,
+    CR,
+    CI,
+    CSS,
+    CIW,
+    CL,
+    CS,
+    CA,
+    CB,
+    CJ,
+    /// Vector configuration-setting instructions (`vsetvli`, `vsetivli`, `vsetvl`).
+    VSet,
+    /// Vector loads, using the LOAD-FP major opcode.
+    VL,
+    /// Vector stores, using the STORE-FP major opcode.
+    VS,
+    /// Vector arithmetic instructions, using the OP-V major opcode.
+    V,
+}
+
+/// What kind of operation an instruction performs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum InstructionClass {
+    /// Conditional branches.
+    Branch,
+    /// Unconditional jumps, including calls and returns.
+    Jump,
+    /// Loads, from integer, floating-point and vector loads to hypervisor loads.
+    Load,
+    /// Stores, from integer, floating-point and vector stores to hypervisor stores.
+    Store,
+    /// Atomic memory operations, including load-reserved and store-conditional.
+    Atomic,
+    /// Integer, floating-point and vector computation.
+    Alu,
+    /// CSR reads and writes.
+    Csr,
+    /// Fences, environment calls, trap returns and other privileged instructions.
+    System,
+}
+
+impl InstructionClass {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Branch => "branch",
+            Self::Jump => "jump",
+            Self::Load => "load",
+            Self::Store => "store",
+            Self::Atomic => "atomic",
+            Self::Alu => "alu",
+            Self::Csr => "csr",
+            Self::System => "system",
+        }
+    }
+}
+
+impl fmt::Display for InstructionClass {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad(self.name())
+    }
+}
+
+/// Why an instruction couldn't be decoded.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DecodeError {
+    /// The low bits of the instruction encode a length that isn't supported.
+    BadLength(u64),
+    /// The instruction isn't defined by any supported extension.
+    IllegalInstruction(InstructionBits),
+    /// The instruction is only defined on the other XLEN, and is reserved on the given one (e.g.
+    /// `addiw` on RV32).
+    ReservedEncoding(InstructionBits, Xlen),
+    /// The instruction belongs to the given extensions, which aren't enabled.
+    ExtensionNotEnabled(InstructionBits, Extensions),
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::BadLength(x) => write!(f, "unsupported instruction length for {:x}", x),
+            Self::IllegalInstruction(inst_bits) => {
+                write!(f, "illegal instruction {:x}", inst_bits.bits)
+            }
+            Self::ReservedEncoding(inst_bits, xlen) => {
+                let xlen = match xlen {
+                    Xlen::Rv32 => "RV32",
+                    Xlen::Rv64 => "RV64",
+                };
+                write!(
+                    f,
+                    "instruction {:x} is reserved on {}",
+                    inst_bits.bits, xlen
+                )
+            }
+            Self::ExtensionNotEnabled(inst_bits, extensions) => {
+                write!(f, "instruction {:x} requires extension ", inst_bits.bits)?;
+                for (idx, name) in extensions.names().enumerate() {
+                    if idx != 0 {
+                        f.write_str(", ")?;
+                    }
+                    f.write_str(name)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+/// How branch and jump targets are rendered.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum TargetStyle {
+    /// Relative to the instruction's PC, e.g. `pc + 16`, as spike prints them.
+    #[default]
+    Relative,
+    /// As an absolute address, e.g. `0x80000010`, when the instruction's PC is known.
+    Absolute,
+    /// As an absolute address followed by the relative form, e.g. `0x80000010 (pc + 16)`, when
+    /// the instruction's PC is known.
+    Both,
+}
+
+impl std::str::FromStr for TargetStyle {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "relative" => Ok(Self::Relative),
+            "absolute" => Ok(Self::Absolute),
+            "both" => Ok(Self::Both),
+            _ => Err(format!(
+                "unknown target style '{}' (expected 'relative', 'absolute' or 'both')",
+                s
+            )),
+        }
+    }
+}
+
+/// Options controlling how a `DecodedInstruction` is rendered.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct DisplayOptions {
+    pub register_names: RegisterNames,
+    pub targets: TargetStyle,
+}
+
+/// A single operand of a decoded instruction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Operand {
+    /// Integer register `x0`-`x31`.
+    IntReg(u8),
+    /// Floating-point register `f0`-`f31`.
+    FpReg(u8),
+    /// Vector register `v0`-`v31`.
+    VecReg(u8),
+    /// The `v0.t` mask operand of a masked vector instruction.
+    VecMask,
+    /// An immediate, shown in decimal.
+    Imm(i64),
+    /// The upper 20-bit immediate of `lui`, `auipc` and `c.lui`, shown in hex.
+    UpperImm(u32),
+    /// A CSR, by its address.
+    Csr(u16),
+    /// A memory operand: a base register, plus an offset for instructions that have one.
+    Mem { base: u8, offset: Option<i64> },
+    /// A branch or jump target, relative to the instruction's own PC.
+    PcRel(i64),
+    /// The `vtype` setting of a vector configuration-setting instruction.
+    VType(u32),
+}
+
+/// The operands of a decoded instruction, stored inline so that decoding doesn't allocate.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct Operands {
+    operands: [Operand; MAX_OPERANDS],
+    len: usize,
+}
+
+impl Operands {
+    pub fn new(operands: &[Operand]) -> Self {
+        let mut ops = Self {
+            operands: [Operand::Imm(0); MAX_OPERANDS],
+            len: operands.len(),
+        };
+        ops.operands[..operands.len()].copy_from_slice(operands);
+        ops
+    }
+
+    /// Appends `operand`, which must fit within `MAX_OPERANDS`.
+    pub fn push(&mut self, operand: Operand) {
+        self.operands[self.len] = operand;
+        self.len += 1;
+    }
+}
+
+impl std::ops::Deref for Operands {
+    type Target = [Operand];
+
+    fn deref(&self) -> &[Operand] {
+        &self.operands[..self.len]
+    }
+}
+
+impl fmt::Debug for Operands {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+/// An instruction mnemonic.
+///
+/// Vector segment loads/stores share encodings with the plain unit-stride, strided and indexed
+/// loads/stores, so their mnemonic is built from the base name and the number of fields per
+/// segment (e.g. `vle8.v` with 2 fields is `vlseg2e8.v`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Mnemonic {
+    pub name: &'static str,
+    pub segment_fields: u8,
+}
+
+impl fmt::Display for Mnemonic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match (self.segment_fields, self.name.find('e')) {
+            (0..=1, _) | (_, None) => f.pad(self.name),
+            // Segment mnemonics are always longer than `INSTRUCTION_NAME_WIDTH`, so there's no
+            // padding to apply.
+            (fields, Some(idx)) => {
+                let (prefix, suffix) = self.name.split_at(idx);
+                write!(f, "{}seg{}{}", prefix, fields, suffix)
+            }
+        }
+    }
+}
+
+/// A fully-decoded instruction: what it is, which extension it belongs to, and its operands.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DecodedInstruction {
+    pub bits: InstructionBits,
+    pub mnemonic: Mnemonic,
+    pub extension: Extensions,
+    pub format: InstructionFormat,
+    pub operands: Operands,
+    /// The address of the instruction, if known, used to render absolute branch/jump targets.
+    pub pc: Option<u64>,
+}
+
+impl DecodedInstruction {
+    pub(crate) fn new(
+        inst_filter: &InstructionFilter,
+        inst_bits: InstructionBits,
+        format: InstructionFormat,
+        operands: &[Operand],
+    ) -> Self {
+        Self {
+            bits: inst_bits,
+            mnemonic: Mnemonic {
+                name: inst_filter.name,
+                segment_fields: 0,
+            },
+            extension: inst_filter.extension(),
+            format,
+            operands: Operands::new(operands),
+            pc: None,
+        }
+    }
+
+    /// Returns a `Display` adapter that renders the instruction with the given options.
+    pub fn display(&self, options: DisplayOptions) -> DisplayInstruction<'_> {
+        DisplayInstruction {
+            inst: self,
+            options,
+            symbols: None,
+        }
+    }
+
+    /// Returns whether the destination of the instruction is a floating-point register: its first
+    /// operand is one, and it isn't a store (whose first operand is the data to store).
+    pub fn writes_fp_reg(&self) -> bool {
+        let is_store = matches!(
+            self.format,
+            InstructionFormat::S | InstructionFormat::CSS | InstructionFormat::CS
+        );
+        !is_store && matches!(self.operands.first(), Some(Operand::FpReg(_)))
+    }
+
+    /// Returns what kind of operation the instruction performs, based on its major opcode (and
+    /// for compressed instructions, its quadrant and `funct3`).
+    pub fn class(&self) -> InstructionClass {
+        let bits = self.bits.bits as u32;
+        let funct3 = |offset: u32| (bits >> offset) & 0b111;
+        match self.bits.length {
+            InstructionLen::TwoByte => match (bits & 0b11, funct3(13)) {
+                (0b00, 0b001..=0b011) | (0b10, 0b001..=0b011) => InstructionClass::Load,
+                (0b00, 0b101..=0b111) | (0b10, 0b101..=0b111) => InstructionClass::Store,
+                (0b01, 0b101) => InstructionClass::Jump,
+                // `c.jal` on RV32, and `c.addiw` on RV64.
+                (0b01, 0b001) => {
+                    let is_jump = self
+                        .operands
+                        .iter()
+                        .any(|op| matches!(op, Operand::PcRel(_)));
+                    if is_jump {
+                        InstructionClass::Jump
+                    } else {
+                        InstructionClass::Alu
+                    }
+                }
+                (0b01, 0b110..=0b111) => InstructionClass::Branch,
+                // `c.jr`, `c.jalr` and `c.ebreak` have no `rs2`, unlike `c.mv` and `c.add`.
+                (0b10, 0b100) if (bits >> 2) & 0x1f == 0 => {
+                    if (bits >> 7) & 0x1f != 0 {
+                        InstructionClass::Jump
+                    } else {
+                        InstructionClass::System
+                    }
+                }
+                _ => InstructionClass::Alu,
+            },
+            _ => match bits & 0x7f {
+                0b110_0011 => InstructionClass::Branch,
+                0b110_1111 | 0b110_0111 => InstructionClass::Jump,
+                0b000_0011 | 0b000_0111 => InstructionClass::Load,
+                0b010_0011 | 0b010_0111 => InstructionClass::Store,
+                0b010_1111 => InstructionClass::Atomic,
+                0b000_1111 => InstructionClass::System,
+                0b111_0011 => match funct3(12) {
+                    0b000 => InstructionClass::System,
+                    // Hypervisor virtual-machine loads and stores, where the low bit of `funct7`
+                    // is set for `hsv.*`.
+                    0b100 if (bits >> 25) & 1 == 1 => InstructionClass::Store,
+                    0b100 => InstructionClass::Load,
+                    _ => InstructionClass::Csr,
+                },
+                _ => InstructionClass::Alu,
+            },
+        }
+    }
+
+    /// Returns `true` for a jump that links into `ra` or `t0`, which the RISC-V spec hints is a
+    /// call (such as `jal ra` or `c.jalr`).
+    pub fn is_call(&self) -> bool {
+        if self.class() != InstructionClass::Jump {
+            return false;
+        }
+        let bits = self.bits.bits;
+        match self.bits.length {
+            // `c.jal` links into `ra`, like `c.jalr`, which unlike `c.jr` sets bit 12.
+            InstructionLen::TwoByte => match bits & 0b11 {
+                0b01 => (bits >> 13) & 0b111 == 0b001,
+                _ => (bits >> 12) & 1 == 1,
+            },
+            _ => is_link_reg(self.bits.get_idx_rd()),
+        }
+    }
+
+    /// Returns `true` for an indirect jump through `ra` or `t0` that doesn't link, which the
+    /// RISC-V spec hints is a return (such as `ret`).
+    pub fn is_return(&self) -> bool {
+        if self.class() != InstructionClass::Jump {
+            return false;
+        }
+        let bits = self.bits.bits;
+        match self.bits.length {
+            InstructionLen::TwoByte => {
+                bits & 0b11 == 0b10
+                    && (bits >> 12) & 1 == 0
+                    && is_link_reg(self.bits.get_idx_c_rs1())
+            }
+            _ => {
+                bits & 0x7f == 0b110_0111
+                    && !is_link_reg(self.bits.get_idx_rd())
+                    && is_link_reg(self.bits.get_idx_rs1())
+            }
+        }
+    }
+
+    /// Returns the base register and offset of the memory operand of a load, store or AMO, with
+    /// an offset of 0 for instructions that don't have one.
+    pub fn memory_operand(&self) -> Option<(u8, i64)> {
+        self.operands.iter().find_map(|operand| match *operand {
+            Operand::Mem { base, offset } => Some((base, offset.unwrap_or(0))),
+            _ => None,
+        })
+    }
+
+    /// Returns the target of a branch or direct jump, from its immediate, if the PC is known.
+    /// Indirect jumps (`jalr`, `c.jr` and `c.jalr`) have none.
+    pub fn static_target(&self) -> Option<u64> {
+        let bits = self.bits;
+        let offset = match (self.class(), bits.length) {
+            (InstructionClass::Branch, InstructionLen::TwoByte) => bits.get_cb_imm(),
+            (InstructionClass::Branch, _) => bits.get_b_imm(),
+            // `c.j` and `c.jal`, in quadrant 1.
+            (InstructionClass::Jump, InstructionLen::TwoByte) if bits.bits & 0b11 == 0b01 => {
+                bits.get_cj_imm()
+            }
+            (InstructionClass::Jump, _) if bits.bits & 0x7f == 0b110_1111 => bits.get_j_imm(),
+            _ => return None,
+        };
+        self.target(offset.into())
+    }
+
+    /// Returns the absolute target address of a branch/jump operand, if the PC is known.
+    pub fn target(&self, offset: i64) -> Option<u64> {
+        self.pc.map(|pc| pc.wrapping_add(offset as u64))
+    }
+
+    fn fmt_operand(
+        &self,
+        f: &mut fmt::Formatter,
+        operand: Operand,
+        options: DisplayOptions,
+        symbols: Option<&SymbolTable>,
+    ) -> fmt::Result {
+        let names = options.register_names;
+        match operand {
+            Operand::IntReg(idx) => f.write_str(names.int(idx)),
+            Operand::FpReg(idx) => f.write_str(names.fp(idx)),
+            Operand::VecReg(idx) => f.write_str(VEC_REGISTER_NAMES[idx as usize]),
+            Operand::VecMask => f.write_str("v0.t"),
+            Operand::Imm(imm) => write!(f, "{}", imm),
+            Operand::UpperImm(imm) => write!(f, "{:#x}", imm),
+            Operand::Csr(csr) => f.write_str(csrs::lookup_csr(csr as u32).unwrap_or("unknown")),
+            Operand::Mem { base, offset } => {
+                if let Some(offset) = offset {
+                    write!(f, "{}", offset)?;
+                }
+                write!(f, "({})", names.int(base))
+            }
+            Operand::PcRel(offset) => {
+                match (options.targets, self.target(offset)) {
+                    (TargetStyle::Relative, _) | (_, None) => self.fmt_pc_rel(f, offset)?,
+                    (TargetStyle::Absolute, Some(target)) => write!(f, "{:#x}", target)?,
+                    (TargetStyle::Both, Some(target)) => {
+                        write!(f, "{:#x} (", target)?;
+                        self.fmt_pc_rel(f, offset)?;
+                        f.write_str(")")?;
+                    }
+                }
+                let symbol = symbols.zip(self.target(offset));
+                match symbol.and_then(|(symbols, target)| symbols.lookup(target)) {
+                    Some(symbol) => write!(f, " {}", symbol),
+                    None => Ok(()),
+                }
+            }
+            Operand::VType(vtype) => fmt_vtype(f, vtype),
+        }
+    }
+
+    fn fmt_pc_rel(&self, f: &mut fmt::Formatter, offset: i64) -> fmt::Result {
+        let operator = if offset.is_negative() { '-' } else { '+' };
+        // Like spike, `jal` offsets are shown in hex, and all others in decimal.
+        if self.format == InstructionFormat::J {
+            write!(f, "pc {} {:#x}", operator, offset.abs())
+        } else {
+            write!(f, "pc {} {}", operator, offset.abs())
+        }
+    }
+}
+
+/// Returns `true` for the registers that the RISC-V spec reserves as link registers, `ra` and `t0`.
+fn is_link_reg(idx: u8) -> bool {
+    idx == 1 || idx == 5
+}
+
+/// Formats a `vtype` value as `e<sew>, m<lmul>, t<a|u>, m<a|u>`.
+///
+/// Like spike, values with any reserved bits set are shown as a hex number instead.
+fn fmt_vtype(f: &mut fmt::Formatter, vtype: u32) -> fmt::Result {
+    if vtype >> 8 != 0 {
+        return write!(f, "{:#x}", vtype);
+    }
+
+    let sew = 8 << ((vtype >> 3) & 0x7);
+    let vlmul = vtype & 0x7;
+    write!(f, "e{}, ", sew)?;
+    if vlmul & 0x4 != 0 {
+        write!(f, "mf{}", 1 << (8 - vlmul))?;
+    } else {
+        write!(f, "m{}", 1 << vlmul)?;
+    }
+    let tail_policy = if vtype & 0x40 != 0 { "ta" } else { "tu" };
+    let mask_policy = if vtype & 0x80 != 0 { "ma" } else { "mu" };
+    write!(f, ", {}, {}", tail_policy, mask_policy)
+}
+
+/// Renders an instruction with chosen `DisplayOptions`; see `DecodedInstruction::display`.
+pub struct DisplayInstruction<'a> {
+    inst: &'a DecodedInstruction,
+    options: DisplayOptions,
+    symbols: Option<&'a SymbolTable>,
+}
+
+impl<'a> DisplayInstruction<'a> {
+    /// Annotates branch and jump targets with the nearest symbol in `symbols`, as `<main+0x24>`,
+    /// when the instruction's PC is known.
+    pub fn with_symbols(mut self, symbols: &'a SymbolTable) -> Self {
+        self.symbols = Some(symbols);
+        self
+    }
+}
+
+/// Renders the instruction the way spike does: the mnemonic padded to a fixed width, followed by
+/// a comma-separated list of operands.
+impl fmt::Display for DisplayInstruction<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let inst = self.inst;
+        if inst.operands.is_empty() {
+            return write!(f, "{}", inst.mnemonic);
+        }
+
+        write!(
+            f,
+            "{:<width$} ",
+            inst.mnemonic,
+            width = INSTRUCTION_NAME_WIDTH
+        )?;
+        for (idx, &operand) in inst.operands.iter().enumerate() {
+            if idx != 0 {
+                f.write_str(", ")?;
+            }
+            inst.fmt_operand(f, operand, self.options, self.symbols)?;
+        }
+        Ok(())
+    }
+}
+
+/// Renders the instruction with the default `DisplayOptions`, like spike.
+impl fmt::Display for DecodedInstruction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.display(DisplayOptions::default()).fmt(f)
+    }
+}
@@ -1,33 +1,255 @@
-use super::instruction::{InstructionBits, InstructionFilter};
+use std::io;
+use std::sync::OnceLock;
+
+use super::decode::{
+    DecodeError, DecodedInstruction, DisplayInstruction, DisplayOptions, Operand, TargetStyle,
+};
+use super::instruction::{self, InstructionBits, InstructionFilter};
+use super::registers::RegisterNames;
+use super::symbols::{SymbolRef, SymbolTable};
+use super::{Extensions, Isa, Xlen};
+
+/// Number of instruction bits used to index the decode table: the major opcode in bits [6:0],
+/// and bits [15:12]. For 32-bit instructions, those are funct3 in bits [14:12] plus the low bit
+/// of rs1 in bit 15, which filters that don't fix rs1 ignore. For compressed instructions, they're
+/// funct3 in bits [15:13] plus bit 12, which completes funct4 where there is one.
+const DECODE_KEY_BITS: u32 = 11;
+
+fn decode_key(bits: u32) -> usize {
+    (((bits >> 5) & 0x780) | (bits & 0x7f)) as usize
+}
+
+/// Inverse of `decode_key`, spreading a key back out into instruction bit positions.
+fn decode_key_bits(key: usize) -> u32 {
+    let key = key as u32;
+    ((key & 0x780) << 5) | (key & 0x7f)
+}
+
+/// Returns a disassembler for every extension we can decode on `xlen`, used to explain why an
+/// instruction didn't decode.
+fn reference_disassembler(xlen: Xlen) -> &'static Disassembler {
+    static RV32: OnceLock<Disassembler> = OnceLock::new();
+    static RV64: OnceLock<Disassembler> = OnceLock::new();
+    let cell = match xlen {
+        Xlen::Rv32 => &RV32,
+        Xlen::Rv64 => &RV64,
+    };
+    cell.get_or_init(|| {
+        let extensions = Extensions::ALL
+            .iter()
+            .fold(Extensions::empty(), |all, &(ext, _)| all | ext)
+            - Extensions::E;
+        Disassembler::new(instruction::gen_instructions(xlen, extensions, false))
+    })
+}
 
 pub struct Disassembler {
     instructions: Vec<InstructionFilter>,
+    /// The ISA the filters were generated for, if known, to explain decoding errors.
+    isa: Option<Isa>,
+    /// For each decode key, the indices of all filters that can match an instruction with that
+    /// key, in the same priority order as `instructions`.
+    decode_table: Vec<Vec<usize>>,
+    display_options: DisplayOptions,
+    symbols: Option<SymbolTable>,
 }
 
 impl Disassembler {
     pub fn new(instructions: Vec<InstructionFilter>) -> Self {
-        Self { instructions }
+        let key_mask = decode_key_bits((1 << DECODE_KEY_BITS) - 1);
+        let decode_table = (0..1 << DECODE_KEY_BITS)
+            .map(|key| {
+                // A filter belongs to every bucket whose key bits agree with the filter's match
+                // value, on the key bits the filter actually checks.
+                let key_bits = decode_key_bits(key);
+                instructions
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, inst)| {
+                        let mask = inst.mask() & key_mask;
+                        key_bits & mask == inst.r#match() & mask
+                    })
+                    .map(|(idx, _)| idx)
+                    .collect()
+            })
+            .collect();
+
+        Self {
+            instructions,
+            isa: None,
+            decode_table,
+            display_options: DisplayOptions::default(),
+            symbols: None,
+        }
+    }
+
+    /// Returns a disassembler for `isa`, decoding pseudo-instructions if `aliases` is set.
+    ///
+    /// Unlike `new`, this knows the ISA, so instructions that fail to decode are reported as
+    /// reserved on this XLEN or needing a disabled extension where that's the case, rather than
+    /// always as illegal.
+    pub fn for_isa(isa: Isa, aliases: bool) -> Self {
+        let instructions = instruction::gen_instructions(isa.xlen, isa.extensions, aliases);
+        Self {
+            isa: Some(isa),
+            ..Self::new(instructions)
+        }
+    }
+
+    /// Selects how register operands are named in the disassembly (ABI names by default).
+    pub fn with_register_names(mut self, register_names: RegisterNames) -> Self {
+        self.display_options.register_names = register_names;
+        self
+    }
+
+    /// Selects how branch and jump targets are rendered (relative to the PC by default).
+    ///
+    /// Absolute targets are only shown for instructions whose PC is known; see `decode_at`.
+    pub fn with_target_style(mut self, targets: TargetStyle) -> Self {
+        self.display_options.targets = targets;
+        self
+    }
+
+    /// Annotates branch and jump targets with the nearest symbol in `symbols`, when the PC of the
+    /// instruction is known; see `decode_at`.
+    pub fn with_symbols(mut self, symbols: SymbolTable) -> Self {
+        self.symbols = Some(symbols);
+        self
+    }
+
+    /// Returns the ISA this disassembler was built for by `for_isa`.
+    pub fn isa(&self) -> Option<Isa> {
+        self.isa
+    }
+
+    /// Returns how register operands are named.
+    pub fn register_names(&self) -> RegisterNames {
+        self.display_options.register_names
+    }
+
+    /// Returns `true` if the rendering depends on the PC of each instruction, in which case it
+    /// should be passed to `decode_at` and `write_inst_at`.
+    pub fn needs_pc(&self) -> bool {
+        self.display_options.targets != TargetStyle::Relative || self.symbols.is_some()
+    }
+
+    /// Returns the nearest symbol at or before `address`, if symbols were loaded.
+    pub fn lookup_symbol(&self, address: u64) -> Option<SymbolRef<'_>> {
+        self.symbols.as_ref()?.lookup(address)
     }
 
     fn get_inst(&self, x: InstructionBits) -> Option<&InstructionFilter> {
-        self.instructions.iter().find(|inst| inst.is_eq(x))
+        self.decode_table[decode_key(x.bits as u32)]
+            .iter()
+            .map(|&idx| &self.instructions[idx])
+            .find(|inst| inst.is_eq(x))
+    }
+
+    /// Explains why `x` doesn't match any of our filters.
+    fn decode_error(&self, x: InstructionBits) -> DecodeError {
+        let isa = match self.isa {
+            Some(isa) => isa,
+            None => return DecodeError::IllegalInstruction(x),
+        };
+
+        if let Some(inst_filter) = reference_disassembler(isa.xlen).get_inst(x) {
+            let missing = inst_filter.extension() - isa.extensions;
+            if !missing.is_empty() {
+                return DecodeError::ExtensionNotEnabled(x, missing);
+            }
+        }
+        let other_xlen = match isa.xlen {
+            Xlen::Rv32 => Xlen::Rv64,
+            Xlen::Rv64 => Xlen::Rv32,
+        };
+        if reference_disassembler(other_xlen).get_inst(x).is_some() {
+            return DecodeError::ReservedEncoding(x, isa.xlen);
+        }
+        DecodeError::IllegalInstruction(x)
+    }
+
+    /// Decodes `x` into its mnemonic and operands.
+    ///
+    /// On RV32E and RV64E, instructions that use integer registers `x16` to `x31` are illegal.
+    pub fn decode(&self, x: InstructionBits) -> Result<DecodedInstruction, DecodeError> {
+        let decoded = match self.get_inst(x) {
+            Some(inst_filter) => inst_filter.decode(x),
+            None => return Err(self.decode_error(x)),
+        };
+        let has_e = self.isa.is_some_and(|isa| isa.extensions.has_e());
+        let uses_upper_reg = || {
+            decoded.operands.iter().any(|operand| {
+                matches!(*operand, Operand::IntReg(idx) | Operand::Mem { base: idx, .. } if idx >= 16)
+            })
+        };
+        if has_e && uses_upper_reg() {
+            return Err(DecodeError::IllegalInstruction(x));
+        }
+        Ok(decoded)
+    }
+
+    /// Like `decode`, for an instruction located at address `pc`.
+    pub fn decode_at(
+        &self,
+        x: InstructionBits,
+        pc: Option<u64>,
+    ) -> Result<DecodedInstruction, DecodeError> {
+        self.decode(x)
+            .map(|decoded| DecodedInstruction { pc, ..decoded })
     }
 
-    pub fn fmt_inst(&self, x: InstructionBits) -> Option<String> {
-        self.get_inst(x)
-            .map(|inst_filter| (inst_filter.formatter)(inst_filter, x))
+    pub fn fmt_inst(&self, x: InstructionBits) -> Result<String, DecodeError> {
+        self.decode(x)
+            .map(|decoded| self.display(&decoded).to_string())
+    }
+
+    /// Returns a `Display` adapter that renders `decoded` with this disassembler's options and
+    /// symbols.
+    pub fn display<'a>(&'a self, decoded: &'a DecodedInstruction) -> DisplayInstruction<'a> {
+        let display = decoded.display(self.display_options);
+        match &self.symbols {
+            Some(symbols) => display.with_symbols(symbols),
+            None => display,
+        }
+    }
+
+    /// Writes the disassembly of `x` straight into `w`, without any intermediate allocation.
+    ///
+    /// If `x` can't be decoded, nothing is written and the inner result holds the reason. To
+    /// write into a `fmt::Write` instead, format the result of `decode` through `display` with
+    /// `write!`.
+    pub fn write_inst<W: io::Write>(
+        &self,
+        w: &mut W,
+        x: InstructionBits,
+    ) -> io::Result<Result<(), DecodeError>> {
+        self.write_inst_at(w, x, None)
+    }
+
+    /// Like `write_inst`, for an instruction located at address `pc`.
+    pub fn write_inst_at<W: io::Write>(
+        &self,
+        w: &mut W,
+        x: InstructionBits,
+        pc: Option<u64>,
+    ) -> io::Result<Result<(), DecodeError>> {
+        match self.decode_at(x, pc) {
+            Ok(decoded) => write!(w, "{}", self.display(&decoded)).map(Ok),
+            Err(e) => Ok(Err(e)),
+        }
     }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::decode::{InstructionClass, InstructionFormat, Mnemonic};
     use crate::instruction;
     use crate::{Extensions, Xlen};
 
     fn test_disasm(disasm: Disassembler, test_pairs: Vec<(u32, &str)>) {
         for (inst_u32, inst_str) in test_pairs.into_iter() {
-            let inst_bits = InstructionBits::new(inst_u32).unwrap_or_else(|e| {
+            let inst_bits = InstructionBits::new(inst_u32.into()).unwrap_or_else(|e| {
                 panic!(
                     "Failed to construct InstructionBits from {:0>8x} ({}): {}",
                     inst_u32, inst_str, e
@@ -35,9 +257,9 @@ mod test {
             });
 
             assert_eq!(
-                disasm.fmt_inst(inst_bits).unwrap_or_else(|| panic!(
-                    "{:?} didn't match any known instruction, from {:0>8x}, '{}'",
-                    inst_bits, inst_u32, inst_str
+                disasm.fmt_inst(inst_bits).unwrap_or_else(|e| panic!(
+                    "{:?} didn't match any known instruction ({}), from {:0>8x}, '{}'",
+                    inst_bits, e, inst_u32, inst_str
                 )),
                 inst_str
             );
@@ -315,6 +537,50 @@ mod test {
         test_disasm(disasm, test_pairs);
     }
 
+    #[test]
+    fn disasm_rv64_q() {
+        let instructions =
+            instruction::gen_instructions(Xlen::Rv64, Extensions::IMAFDC | Extensions::Q, true);
+        let disasm = Disassembler::new(instructions);
+
+        let test_pairs = vec![
+            (0x01054107, "flq     ft2, 16(a0)"),
+            (0xfef14027, "fsq     fa5, -32(sp)"),
+            (0x061071d3, "fadd.q  ft3, ft0, ft1"),
+            (0x0e1071d3, "fsub.q  ft3, ft0, ft1"),
+            (0x161071d3, "fmul.q  ft3, ft0, ft1"),
+            (0x1e1071d3, "fdiv.q  ft3, ft0, ft1"),
+            (0x5e0071d3, "fsqrt.q ft3, ft0"),
+            (0x2e1001d3, "fmin.q  ft3, ft0, ft1"),
+            (0x2e1011d3, "fmax.q  ft3, ft0, ft1"),
+            (0x261001d3, "fsgnj.q ft3, ft0, ft1"),
+            (0x261011d3, "fsgnjn.q ft3, ft0, ft1"),
+            (0x261021d3, "fsgnjx.q ft3, ft0, ft1"),
+            (0x161071c3, "fmadd.q ft3, ft0, ft1, ft2"),
+            (0x161071c7, "fmsub.q ft3, ft0, ft1, ft2"),
+            (0x161071cb, "fnmsub.q ft3, ft0, ft1, ft2"),
+            (0x161071cf, "fnmadd.q ft3, ft0, ft1, ft2"),
+            (0x4030f1d3, "fcvt.s.q ft3, ft1"),
+            (0x4600f1d3, "fcvt.q.s ft3, ft1"),
+            (0x4230f1d3, "fcvt.d.q ft3, ft1"),
+            (0x4610f1d3, "fcvt.q.d ft3, ft1"),
+            (0xc600f553, "fcvt.w.q a0, ft1"),
+            (0xc610f553, "fcvt.wu.q a0, ft1"),
+            (0xc620f553, "fcvt.l.q a0, ft1"),
+            (0xc630f553, "fcvt.lu.q a0, ft1"),
+            (0xd60570d3, "fcvt.q.w ft1, a0"),
+            (0xd61570d3, "fcvt.q.wu ft1, a0"),
+            (0xd62570d3, "fcvt.q.l ft1, a0"),
+            (0xd63570d3, "fcvt.q.lu ft1, a0"),
+            (0xa6102553, "feq.q   a0, ft0, ft1"),
+            (0xa6101553, "flt.q   a0, ft0, ft1"),
+            (0xa6100553, "fle.q   a0, ft0, ft1"),
+            (0xe6051553, "fclass.q a0, fa0"),
+        ];
+
+        test_disasm(disasm, test_pairs);
+    }
+
     #[test]
     fn disasm_rv64_c() {
         let instructions = instruction::gen_instructions(Xlen::Rv64, Extensions::IMAFDC, true);
@@ -463,4 +729,686 @@ mod test {
 
         test_disasm(disasm, test_pairs);
     }
+
+    #[test]
+    fn disasm_rv64_v() {
+        let instructions =
+            instruction::gen_instructions(Xlen::Rv64, Extensions::IMAFDC | Extensions::V, true);
+        let disasm = Disassembler::new(instructions);
+
+        let test_pairs = vec![
+            (0x0405f557, "vsetvli a0, a1, e8, m1, ta, mu"),
+            (0x097072d7, "vsetvli t0, zero, e32, mf2, tu, ma"),
+            (0xcdb27557, "vsetivli a0, 4, e64, m8, ta, ma"),
+            (0x80c5f557, "vsetvl  a0, a1, a2"),
+            (0x02050087, "vle8.v  v1, (a0)"),
+            (0x00016207, "vle32.v v4, (sp), v0.t"),
+            (0x0205f427, "vse64.v v8, (a1)"),
+            (0x03055107, "vle16ff.v v2, (a0)"),
+            (0x42050087, "vlseg3e8.v v1, (a0)"),
+            (0xe005e427, "vsseg8e32.v v8, (a1), v0.t"),
+            (0x0ab56087, "vlse32.v v1, (a0), a1"),
+            (0x2a555127, "vssseg2e16.v v2, (a0), t0"),
+            (0x06256087, "vluxei32.v v1, (a0), v2"),
+            (0x0c250087, "vloxei8.v v1, (a0), v2, v0.t"),
+            (0x0645f1a7, "vsuxei64.v v3, (a1), v4"),
+            (0x6ec65427, "vsoxseg4ei16.v v8, (a2), v12"),
+            (0x02b50007, "vlm.v   v0, (a0)"),
+            (0x02b500a7, "vsm.v   v1, (a0)"),
+            (0x02850087, "vl1re8.v v1, (a0)"),
+            (0xe2857407, "vl8re64.v v8, (a0)"),
+            (0x62850227, "vs4r.v  v4, (a0)"),
+            (0x022180d7, "vadd.vv v1, v2, v3"),
+            (0x002180d7, "vadd.vv v1, v2, v3, v0.t"),
+            (0x022540d7, "vadd.vx v1, v2, a0"),
+            (0x022830d7, "vadd.vi v1, v2, -16"),
+            (0x0e27b0d7, "vrsub.vi v1, v2, 15"),
+            (0x962fb0d7, "vsll.vi v1, v2, 31"),
+            (0xd2456157, "vwaddu.wx v2, v4, a0"),
+            (0x4a432157, "vzext.vf2 v2, v4"),
+            (0x4811a457, "vsext.vf8 v8, v1, v0.t"),
+            (0x402180d7, "vadc.vvm v1, v2, v3, v0"),
+            (0x442180d7, "vmadc.vvm v1, v2, v3, v0"),
+            (0x462180d7, "vmadc.vv v1, v2, v3"),
+            (0x462eb0d7, "vmadc.vi v1, v2, -3"),
+            (0x482540d7, "vsbc.vxm v1, v2, a0, v0"),
+            (0x4e2540d7, "vmsbc.vx v1, v2, a0"),
+            (0xb22230d7, "vnsrl.wi v1, v2, 4"),
+            (0x7a23b0d7, "vmsgtu.vi v1, v2, 7"),
+            (0xb63120d7, "vmacc.vv v1, v2, v3"),
+            (0xac2560d7, "vnmsub.vx v1, a0, v2, v0.t"),
+            (0xfa456157, "vwmaccus.vx v2, a0, v4"),
+            (0x5c22b0d7, "vmerge.vim v1, v2, 5, v0"),
+            (0x5e0100d7, "vmv.v.v v1, v2"),
+            (0x5e0540d7, "vmv.v.x v1, a0"),
+            (0x5e0fb0d7, "vmv.v.i v1, -1"),
+            (0x9e2180d7, "vsmul.vv v1, v2, v3"),
+            (0xbe21b0d7, "vnclip.wi v1, v2, 3"),
+            (0x022550d7, "vfadd.vf v1, v2, fa0"),
+            (0x9c2050d7, "vfrsub.vf v1, v2, ft0, v0.t"),
+            (0xd245d157, "vfwadd.wf v2, v4, fa1"),
+            (0xb22550d7, "vfmacc.vf v1, fa0, v2"),
+            (0xae3110d7, "vfnmsub.vv v1, v2, v3"),
+            (0x4e2010d7, "vfsqrt.v v1, v2"),
+            (0x4e2210d7, "vfrsqrt7.v v1, v2"),
+            (0x4c2290d7, "vfrec7.v v1, v2, v0.t"),
+            (0x7e2550d7, "vmfge.vf v1, v2, fa0"),
+            (0x4e2810d7, "vfclass.v v1, v2"),
+            (0x5c2550d7, "vfmerge.vfm v1, v2, fa0, v0"),
+            (0x5e0550d7, "vfmv.v.f v1, fa0"),
+            (0x4a2390d7, "vfcvt.rtz.x.f.v v1, v2"),
+            (0x4a461157, "vfwcvt.f.f.v v2, v4"),
+            (0x4a2a90d7, "vfncvt.rod.f.f.w v1, v2"),
+            (0x0221a0d7, "vredsum.vs v1, v2, v3"),
+            (0xc02180d7, "vwredsumu.vs v1, v2, v3, v0.t"),
+            (0x062190d7, "vfredusum.vs v1, v2, v3"),
+            (0x0e2190d7, "vfredosum.vs v1, v2, v3"),
+            (0xc62190d7, "vfwredusum.vs v1, v2, v3"),
+            (0x6221a0d7, "vmandn.mm v1, v2, v3"),
+            (0x7221a0d7, "vmorn.mm v1, v2, v3"),
+            (0x7e21a0d7, "vmxnor.mm v1, v2, v3"),
+            (0x42282557, "vcpop.m a0, v2"),
+            (0x4028a557, "vfirst.m a0, v2, v0.t"),
+            (0x5220a0d7, "vmsbf.m v1, v2"),
+            (0x502820d7, "viota.m v1, v2, v0.t"),
+            (0x5208a0d7, "vid.v   v1"),
+            (0x5008a0d7, "vid.v   v1, v0.t"),
+            (0x42202557, "vmv.x.s a0, v2"),
+            (0x420560d7, "vmv.s.x v1, a0"),
+            (0x42201557, "vfmv.f.s fa0, v2"),
+            (0x420550d7, "vfmv.s.f v1, fa0"),
+            (0x3a2fb0d7, "vslideup.vi v1, v2, 31"),
+            (0x3e2540d7, "vslidedown.vx v1, v2, a0"),
+            (0x3a2560d7, "vslide1up.vx v1, v2, a0"),
+            (0x3e2550d7, "vfslide1down.vf v1, v2, fa0"),
+            (0x322fb0d7, "vrgather.vi v1, v2, 31"),
+            (0x3a2180d7, "vrgatherei16.vv v1, v2, v3"),
+            (0x5e21a0d7, "vcompress.vm v1, v2, v3"),
+            (0x9e2030d7, "vmv1r.v v1, v2"),
+            (0x9f03b457, "vmv8r.v v8, v16"),
+        ];
+
+        test_disasm(disasm, test_pairs);
+    }
+
+    #[test]
+    fn disasm_rv64_h() {
+        let instructions =
+            instruction::gen_instructions(Xlen::Rv64, Extensions::IMAFDC | Extensions::H, true);
+        let disasm = Disassembler::new(instructions);
+
+        let test_pairs = vec![
+            (0x22b50073, "hfence.vvma a0, a1"),
+            (0x62000073, "hfence.gvma zero, zero"),
+            (0x6005c573, "hlv.b   a0, (a1)"),
+            (0x6015c573, "hlv.bu  a0, (a1)"),
+            (0x640142f3, "hlv.h   t0, (sp)"),
+            (0x641142f3, "hlv.hu  t0, (sp)"),
+            (0x643142f3, "hlvx.hu t0, (sp)"),
+            (0x68054573, "hlv.w   a0, (a0)"),
+            (0x68354573, "hlvx.wu a0, (a0)"),
+            (0x68154573, "hlv.wu  a0, (a0)"),
+            (0x6c054573, "hlv.d   a0, (a0)"),
+            (0x62b54073, "hsv.b   a1, (a0)"),
+            (0x66b54073, "hsv.h   a1, (a0)"),
+            (0x6ab54073, "hsv.w   a1, (a0)"),
+            (0x6eb54073, "hsv.d   a1, (a0)"),
+            (0x60002573, "csrr    a0, hstatus"),
+            (0x68002573, "csrr    a0, hgatp"),
+            (0x60a51073, "csrw    henvcfg, a0"),
+            (0x20051073, "csrw    vsstatus, a0"),
+            (0x28002573, "csrr    a0, vsatp"),
+        ];
+
+        test_disasm(disasm, test_pairs);
+    }
+
+    #[test]
+    fn disasm_rv64_zb() {
+        let instructions = instruction::gen_instructions(
+            Xlen::Rv64,
+            Extensions::IMAFDC
+                | Extensions::ZBA
+                | Extensions::ZBB
+                | Extensions::ZBC
+                | Extensions::ZBS,
+            true,
+        );
+        let disasm = Disassembler::new(instructions);
+
+        let test_pairs = vec![
+            (0x20c5a533, "sh1add  a0, a1, a2"),
+            (0x20c5c533, "sh2add  a0, a1, a2"),
+            (0x207362b3, "sh3add  t0, t1, t2"),
+            (0x08c5853b, "add.uw  a0, a1, a2"),
+            (0x20c5a53b, "sh1add.uw a0, a1, a2"),
+            (0x20c5c53b, "sh2add.uw a0, a1, a2"),
+            (0x20c5e53b, "sh3add.uw a0, a1, a2"),
+            (0x0a85951b, "slli.uw a0, a1, 40"),
+            (0x0805853b, "zext.w  a0, a1"),
+            (0x40c5f533, "andn    a0, a1, a2"),
+            (0x40c5e533, "orn     a0, a1, a2"),
+            (0x40c5c533, "xnor    a0, a1, a2"),
+            (0x60059513, "clz     a0, a1"),
+            (0x60159513, "ctz     a0, a1"),
+            (0x60259513, "cpop    a0, a1"),
+            (0x6005951b, "clzw    a0, a1"),
+            (0x6015951b, "ctzw    a0, a1"),
+            (0x6025951b, "cpopw   a0, a1"),
+            (0x0ac5e533, "max     a0, a1, a2"),
+            (0x0ac5f533, "maxu    a0, a1, a2"),
+            (0x0ac5c533, "min     a0, a1, a2"),
+            (0x0ac5d533, "minu    a0, a1, a2"),
+            (0x60459513, "sext.b  a0, a1"),
+            (0x60559513, "sext.h  a0, a1"),
+            (0x0805c53b, "zext.h  a0, a1"),
+            (0x60c59533, "rol     a0, a1, a2"),
+            (0x60c5d533, "ror     a0, a1, a2"),
+            (0x60c5953b, "rolw    a0, a1, a2"),
+            (0x60c5d53b, "rorw    a0, a1, a2"),
+            (0x63f5d513, "rori    a0, a1, 63"),
+            (0x61f5d51b, "roriw   a0, a1, 31"),
+            (0x2875d513, "orc.b   a0, a1"),
+            (0x6b85d513, "rev8    a0, a1"),
+            (0x0ac59533, "clmul   a0, a1, a2"),
+            (0x0ac5b533, "clmulh  a0, a1, a2"),
+            (0x0ac5a533, "clmulr  a0, a1, a2"),
+            (0x48c59533, "bclr    a0, a1, a2"),
+            (0x4bf59513, "bclri   a0, a1, 63"),
+            (0x48c5d533, "bext    a0, a1, a2"),
+            (0x4855d513, "bexti   a0, a1, 5"),
+            (0x68c59533, "binv    a0, a1, a2"),
+            (0x6a059513, "binvi   a0, a1, 32"),
+            (0x28c59533, "bset    a0, a1, a2"),
+            (0x28059513, "bseti   a0, a1, 0"),
+        ];
+
+        test_disasm(disasm, test_pairs);
+    }
+
+    #[test]
+    fn disasm_rv32_zb() {
+        let instructions = instruction::gen_instructions(
+            Xlen::Rv32,
+            Extensions::IMAFDC
+                | Extensions::ZBA
+                | Extensions::ZBB
+                | Extensions::ZBC
+                | Extensions::ZBS,
+            true,
+        );
+        let disasm = Disassembler::new(instructions);
+
+        let test_pairs = vec![
+            (0x0805c533, "zext.h  a0, a1"),
+            (0x6985d513, "rev8    a0, a1"),
+            (0x61f5d513, "rori    a0, a1, 31"),
+            (0x29f59513, "bseti   a0, a1, 31"),
+            (0x20c5a533, "sh1add  a0, a1, a2"),
+        ];
+
+        test_disasm(disasm, test_pairs);
+    }
+
+    #[test]
+    fn disasm_rv64_extension_subset() {
+        let isa = Isa {
+            xlen: Xlen::Rv64,
+            extensions: Extensions::I | Extensions::M | Extensions::C,
+        };
+        let disasm = Disassembler::for_isa(isa, true);
+
+        // Zicsr, Zifencei, D and A instructions aren't decoded without their extensions.
+        let test_cases = [
+            (0x30002573, Extensions::ZICSR),
+            (0x0000100f, Extensions::ZIFENCEI),
+            (0x0005b507, Extensions::D),
+            (0x100525af, Extensions::A),
+        ];
+        for &(inst_u32, extension) in test_cases.iter() {
+            let inst_bits = InstructionBits::new(inst_u32).unwrap();
+            assert_eq!(
+                disasm.fmt_inst(inst_bits),
+                Err(DecodeError::ExtensionNotEnabled(inst_bits, extension))
+            );
+        }
+
+        let test_pairs = vec![
+            (0xfc050513, "addi    a0, a0, -64"),
+            (0x02208733, "mul     a4, ra, sp"),
+            (0x00000785, "c.addi  a5, 1"),
+        ];
+
+        test_disasm(disasm, test_pairs);
+    }
+
+    #[test]
+    fn decode_table_matches_linear_search() {
+        let extensions = Extensions::IMAFDC
+            | Extensions::Q
+            | Extensions::V
+            | Extensions::H
+            | Extensions::ZBA
+            | Extensions::ZBB
+            | Extensions::ZBC
+            | Extensions::ZBS;
+        let linear = instruction::gen_instructions(Xlen::Rv64, extensions, true);
+        let disasm = Disassembler::new(instruction::gen_instructions(Xlen::Rv64, extensions, true));
+
+        let check = |inst_u32: u32| {
+            if let Ok(inst_bits) = InstructionBits::new(inst_u32.into()) {
+                assert_eq!(
+                    disasm.get_inst(inst_bits).map(|inst| inst.name),
+                    linear
+                        .iter()
+                        .find(|inst| inst.is_eq(inst_bits))
+                        .map(|inst| inst.name),
+                    "mismatch for {:0>8x}",
+                    inst_u32
+                );
+            }
+        };
+
+        // Every compressed instruction, and a pseudo-random sample of 32-bit instructions.
+        (0..=0xffff).for_each(check);
+        let mut x: u32 = 1;
+        for _ in 0..200_000 {
+            x = x.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+            check(x | 0b11);
+        }
+    }
+
+    #[test]
+    fn decode_rv64() {
+        let instructions =
+            instruction::gen_instructions(Xlen::Rv64, Extensions::IMAFDC | Extensions::V, true);
+        let disasm = Disassembler::new(instructions);
+        let decode = |inst_u32| {
+            disasm
+                .decode(InstructionBits::new(inst_u32).unwrap())
+                .unwrap()
+        };
+
+        let lw = decode(0xffc62683);
+        assert_eq!(lw.mnemonic.name, "lw");
+        assert_eq!(lw.extension, Extensions::I);
+        assert_eq!(lw.format, InstructionFormat::I);
+        assert_eq!(
+            *lw.operands,
+            [
+                Operand::IntReg(13),
+                Operand::Mem {
+                    base: 12,
+                    offset: Some(-4)
+                }
+            ]
+        );
+
+        let beq = decode(0x03ff0a63);
+        assert_eq!(beq.format, InstructionFormat::B);
+        assert_eq!(
+            *beq.operands,
+            [Operand::IntReg(30), Operand::IntReg(31), Operand::PcRel(52)]
+        );
+
+        let csrr = decode(0x300027f3);
+        assert_eq!(csrr.mnemonic.name, "csrr");
+        assert_eq!(csrr.extension, Extensions::ZICSR);
+        assert_eq!(*csrr.operands, [Operand::IntReg(15), Operand::Csr(0x300)]);
+
+        let c_fld = decode(0x000025f8);
+        assert_eq!(c_fld.extension, Extensions::C | Extensions::D);
+        assert_eq!(c_fld.format, InstructionFormat::CL);
+        assert_eq!(
+            *c_fld.operands,
+            [
+                Operand::FpReg(14),
+                Operand::Mem {
+                    base: 11,
+                    offset: Some(200)
+                }
+            ]
+        );
+
+        let vlseg = decode(0x42050087);
+        assert_eq!(
+            vlseg.mnemonic,
+            Mnemonic {
+                name: "vle8.v",
+                segment_fields: 3
+            }
+        );
+        assert_eq!(vlseg.mnemonic.to_string(), "vlseg3e8.v");
+        assert_eq!(vlseg.extension, Extensions::V);
+        assert_eq!(vlseg.format, InstructionFormat::VL);
+        assert_eq!(
+            *vlseg.operands,
+            [
+                Operand::VecReg(1),
+                Operand::Mem {
+                    base: 10,
+                    offset: None
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn write_inst() {
+        let instructions = instruction::gen_instructions(Xlen::Rv64, Extensions::IMAFDC, true);
+        let disasm = Disassembler::new(instructions);
+
+        let mut out = Vec::new();
+        for &inst_u32 in [0xfc050513, 0x300027f3, 0x00002198].iter() {
+            let inst_bits = InstructionBits::new(inst_u32).unwrap();
+            assert_eq!(disasm.write_inst(&mut out, inst_bits).unwrap(), Ok(()));
+            out.push(b'\n');
+        }
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "addi    a0, a0, -64\ncsrr    a5, mstatus\nc.fld   fa4, 0(a1)\n"
+        );
+
+        let mut out = Vec::new();
+        let unknown = InstructionBits::new(0x0000000b).unwrap();
+        assert_eq!(
+            disasm.write_inst(&mut out, unknown).unwrap(),
+            Err(DecodeError::IllegalInstruction(unknown))
+        );
+        assert!(out.is_empty());
+    }
+
+    #[test]
+    fn numeric_register_names() {
+        let instructions = instruction::gen_instructions(Xlen::Rv64, Extensions::IMAFDC, true);
+        let disasm = Disassembler::new(instructions).with_register_names(RegisterNames::Numeric);
+
+        let test_cases = [
+            (0xfc050513, "addi    x10, x10, -64"),
+            (0x300027f3, "csrr    x15, mstatus"),
+            (0x00002198, "c.fld   f14, 0(x11)"),
+            (0x0007b787, "fld     f15, 0(x15)"),
+            (0xd22577d3, "fcvt.d.l f15, x10"),
+            (0x00008082, "ret"),
+        ];
+        for &(inst_u32, expected) in test_cases.iter() {
+            let inst_bits = InstructionBits::new(inst_u32).unwrap();
+            assert_eq!(disasm.fmt_inst(inst_bits), Ok(expected.to_string()));
+        }
+    }
+
+    #[test]
+    fn absolute_targets() {
+        let instructions = instruction::gen_instructions(Xlen::Rv64, Extensions::IMAFDC, true);
+        let relative = Disassembler::new(instructions);
+        let instructions = instruction::gen_instructions(Xlen::Rv64, Extensions::IMAFDC, true);
+        let absolute = Disassembler::new(instructions).with_target_style(TargetStyle::Absolute);
+        let instructions = instruction::gen_instructions(Xlen::Rv64, Extensions::IMAFDC, true);
+        let both = Disassembler::new(instructions).with_target_style(TargetStyle::Both);
+        assert!(!relative.needs_pc());
+        assert!(absolute.needs_pc());
+
+        let pc = Some(0x80000100);
+        let test_cases = [
+            (0x0100006f, "j       0x80000110", "0x80000110 (pc + 0x10)"),
+            (
+                0xfe0008e3,
+                "beqz    zero, 0x800000f0",
+                "0x800000f0 (pc - 16)",
+            ),
+            (0x0000bfe5, "c.j     0x800000f8", "0x800000f8 (pc - 8)"),
+            (0x0000c111, "c.beqz  a0, 0x80000104", "0x80000104 (pc + 4)"),
+        ];
+        for &(inst_u32, expected_absolute, expected_target) in test_cases.iter() {
+            let inst_bits = InstructionBits::new(inst_u32).unwrap();
+
+            let mut out = Vec::new();
+            absolute
+                .write_inst_at(&mut out, inst_bits, pc)
+                .unwrap()
+                .unwrap();
+            assert_eq!(String::from_utf8(out).unwrap(), expected_absolute);
+
+            let decoded = both.decode_at(inst_bits, pc).unwrap();
+            let rendered = decoded
+                .display(DisplayOptions {
+                    targets: TargetStyle::Both,
+                    ..DisplayOptions::default()
+                })
+                .to_string();
+            assert!(rendered.ends_with(expected_target), "{}", rendered);
+
+            // Without a PC, or in the default style, targets stay relative.
+            assert_eq!(absolute.fmt_inst(inst_bits), relative.fmt_inst(inst_bits));
+            let mut out = Vec::new();
+            relative
+                .write_inst_at(&mut out, inst_bits, pc)
+                .unwrap()
+                .unwrap();
+            assert_eq!(
+                String::from_utf8(out).unwrap(),
+                relative.fmt_inst(inst_bits).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn decode_errors() {
+        let rv32 = Disassembler::for_isa("rv32imac".parse().unwrap(), true);
+        let rv64 = Disassembler::for_isa("rv64imac".parse().unwrap(), true);
+        let decode_err = |disasm: &Disassembler, inst_u32| {
+            let inst_bits = InstructionBits::new(inst_u32).unwrap();
+            (inst_bits, disasm.decode(inst_bits).unwrap_err())
+        };
+
+        // `addiw` (and its `sext.w` alias), `sd`, `mulw`, `divw`, `remuw` and `lr.d` only exist on
+        // RV64, and so do `fcvt.l.s` and `fmv.x.d` when F and D are enabled.
+        let rv32gc = Disassembler::for_isa("rv32gc".parse().unwrap(), true);
+        let rv64_only = [
+            (&rv32, 0x0015051b),
+            (&rv32, 0x0005051b),
+            (&rv32, 0x00a13423),
+            (&rv32, 0x02a5053b),
+            (&rv32, 0x02b5453b),
+            (&rv32, 0x02b5753b),
+            (&rv32, 0x1005352f),
+            (&rv32gc, 0xc0250553),
+            (&rv32gc, 0xe2050553),
+        ];
+        for &(disasm, inst_u32) in rv64_only.iter() {
+            let (inst_bits, e) = decode_err(disasm, inst_u32);
+            assert_eq!(e, DecodeError::ReservedEncoding(inst_bits, Xlen::Rv32));
+            assert_eq!(
+                e.to_string(),
+                format!("instruction {:x} is reserved on RV32", inst_u32)
+            );
+        }
+
+        // `c.flw` shares its encoding with `c.ld`, and needs F on RV32.
+        let (inst_bits, e) = decode_err(&rv32, 0x00006188);
+        assert_eq!(
+            e,
+            DecodeError::ExtensionNotEnabled(inst_bits, Extensions::F)
+        );
+        assert_eq!(e.to_string(), "instruction 6188 requires extension f");
+
+        // Custom-0 isn't defined by any extension.
+        let (inst_bits, e) = decode_err(&rv64, 0x0000000b);
+        assert_eq!(e, DecodeError::IllegalInstruction(inst_bits));
+        assert_eq!(e.to_string(), "illegal instruction b");
+
+        // A disassembler built from bare filters doesn't know the ISA, so it can't tell the
+        // difference.
+        let disasm = Disassembler::new(instruction::gen_instructions(
+            Xlen::Rv32,
+            Extensions::IMAC,
+            true,
+        ));
+        let (inst_bits, e) = decode_err(&disasm, 0x0015051b);
+        assert_eq!(e, DecodeError::IllegalInstruction(inst_bits));
+
+        // Zmmul has `mul` but not `div`.
+        let zmmul = Disassembler::for_isa("rv32i_zmmul".parse().unwrap(), true);
+        let mul = InstructionBits::new(0x02b50533).unwrap();
+        assert_eq!(zmmul.fmt_inst(mul).unwrap(), "mul     a0, a0, a1");
+        // Multiplications belong to Zmmul unless M is enabled.
+        assert_eq!(zmmul.decode(mul).unwrap().extension, Extensions::ZMMUL);
+        assert_eq!(rv32.decode(mul).unwrap().extension, Extensions::M);
+
+        // Zbkb and Zbkc decode what they share with Zbb and Zbc, but not the rest of those.
+        let zbk = Disassembler::for_isa("rv64gc_zbkb_zbkc".parse().unwrap(), true);
+        for &(inst_u32, name, extension) in [
+            (0x40b57533, "andn", Extensions::ZBKB),
+            (0x60b5153b, "rolw", Extensions::ZBKB),
+            (0x0ab53533, "clmulh", Extensions::ZBKC),
+        ]
+        .iter()
+        {
+            let decoded = zbk.decode(InstructionBits::new(inst_u32).unwrap()).unwrap();
+            assert_eq!(
+                (decoded.mnemonic.name, decoded.extension),
+                (name, extension)
+            );
+        }
+        // `clz` and `clmulr`.
+        for &(inst_u32, extension) in
+            [(0x60051513, Extensions::ZBB), (0x0ab52533, Extensions::ZBC)].iter()
+        {
+            let (inst_bits, e) = decode_err(&zbk, inst_u32);
+            assert_eq!(e, DecodeError::ExtensionNotEnabled(inst_bits, extension));
+        }
+        let (inst_bits, e) = decode_err(&zmmul, 0x02b54533);
+        assert_eq!(
+            e,
+            DecodeError::ExtensionNotEnabled(inst_bits, Extensions::M)
+        );
+
+        // RV32E has no `x16` to `x31`.
+        let rv32e = Disassembler::for_isa("rv32e".parse().unwrap(), true);
+        assert_eq!(
+            rv32e
+                .fmt_inst(InstructionBits::new(0x00b50533).unwrap())
+                .unwrap(),
+            "add     a0, a0, a1"
+        );
+        let (inst_bits, e) = decode_err(&rv32e, 0x01080833);
+        assert_eq!(e, DecodeError::IllegalInstruction(inst_bits));
+    }
+
+    #[test]
+    fn instruction_classes() {
+        let instructions =
+            instruction::gen_instructions(Xlen::Rv64, Extensions::IMAFDC | Extensions::H, true);
+        let disasm = Disassembler::new(instructions);
+        let class = |inst_u32: u32| {
+            disasm
+                .decode(InstructionBits::new(inst_u32.into()).unwrap())
+                .unwrap()
+                .class()
+        };
+
+        let classes = [
+            // beq, jal, ret, lw, sd, hlv.w, hsv.w
+            (0x03ff0a63, InstructionClass::Branch),
+            (0x008000ef, InstructionClass::Jump),
+            (0x00008067, InstructionClass::Jump),
+            (0xffc62683, InstructionClass::Load),
+            (0x00a13423, InstructionClass::Store),
+            (0x6805c573, InstructionClass::Load),
+            (0x6aa5c073, InstructionClass::Store),
+            // amoadd.w, add, csrr, ecall, fence
+            (0x00a5202f, InstructionClass::Atomic),
+            (0x00b50533, InstructionClass::Alu),
+            (0x300027f3, InstructionClass::Csr),
+            (0x00000073, InstructionClass::System),
+            (0x0ff0000f, InstructionClass::System),
+            // c.beqz, c.j, c.jr, c.lw, c.sdsp, c.addiw, c.mv, c.ebreak
+            (0xc101, InstructionClass::Branch),
+            (0xa001, InstructionClass::Jump),
+            (0x8082, InstructionClass::Jump),
+            (0x4188, InstructionClass::Load),
+            (0xe406, InstructionClass::Store),
+            (0x2505, InstructionClass::Alu),
+            (0x852e, InstructionClass::Alu),
+            (0x9002, InstructionClass::System),
+        ];
+        for &(inst_u32, expected) in classes.iter() {
+            assert_eq!(class(inst_u32), expected, "{:x}", inst_u32);
+        }
+
+        // `c.jal` on RV32 shares its encoding with `c.addiw`.
+        let rv32 = Disassembler::for_isa("rv32imac".parse().unwrap(), true);
+        let c_jal = rv32.decode(InstructionBits::new(0x2001).unwrap()).unwrap();
+        assert_eq!(c_jal.class(), InstructionClass::Jump);
+    }
+
+    #[test]
+    fn calls_and_returns() {
+        let rv64 = Disassembler::for_isa("rv64gc".parse().unwrap(), true);
+        let rv32 = Disassembler::for_isa("rv32imac".parse().unwrap(), true);
+        let call_or_return = |disasm: &Disassembler, inst_u32: u32| {
+            let decoded = disasm
+                .decode(InstructionBits::new(inst_u32.into()).unwrap())
+                .unwrap();
+            (decoded.is_call(), decoded.is_return())
+        };
+
+        // jal ra, jal t0, jalr ra, c.jalr a0
+        for &inst_u32 in [0x008000ef, 0x008002ef, 0x000080e7, 0x9502].iter() {
+            assert_eq!(
+                call_or_return(&rv64, inst_u32),
+                (true, false),
+                "{:x}",
+                inst_u32
+            );
+        }
+        assert_eq!(call_or_return(&rv32, 0x2001), (true, false));
+        // ret, jr t0, c.jr ra
+        for &inst_u32 in [0x00008067, 0x00028067, 0x8082].iter() {
+            assert_eq!(
+                call_or_return(&rv64, inst_u32),
+                (false, true),
+                "{:x}",
+                inst_u32
+            );
+        }
+        // j, jr t1, c.j, beq
+        for &inst_u32 in [0x0080006f, 0x00030067, 0xa001, 0x03ff0a63].iter() {
+            assert_eq!(
+                call_or_return(&rv64, inst_u32),
+                (false, false),
+                "{:x}",
+                inst_u32
+            );
+        }
+    }
+
+    #[test]
+    fn static_targets() {
+        let disasm = Disassembler::for_isa("rv64gc".parse().unwrap(), true);
+        let target = |inst_u32: u32| {
+            disasm
+                .decode_at(InstructionBits::new(inst_u32.into()).unwrap(), Some(0x1000))
+                .unwrap()
+                .static_target()
+        };
+
+        // beq, jal, c.beqz, c.j
+        assert_eq!(target(0x03ff0a63), Some(0x1034));
+        assert_eq!(target(0xfe051ee3), Some(0xffc));
+        assert_eq!(target(0x008000ef), Some(0x1008));
+        assert_eq!(target(0xff5ff06f), Some(0xff4));
+        assert_eq!(target(0xc101), Some(0x1000));
+        assert_eq!(target(0xa001), Some(0x1000));
+        assert_eq!(target(0xbfe5), Some(0xff8));
+        // ret, c.jr, add
+        for &inst_u32 in [0x00008067, 0x8082, 0x00b50533].iter() {
+            assert_eq!(target(inst_u32), None, "{:x}", inst_u32);
+        }
+        // Without a PC, there's no target.
+        let decoded = disasm
+            .decode(InstructionBits::new(0xa001).unwrap())
+            .unwrap();
+        assert_eq!(decoded.static_target(), None);
+    }
 }
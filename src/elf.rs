@@ -0,0 +1,353 @@
+use std::convert::TryInto;
+use std::error::Error;
+use std::fmt;
+use std::io::{self, Write};
+
+use object::elf::{
+    EF_RISCV_FLOAT_ABI, EF_RISCV_FLOAT_ABI_DOUBLE, EF_RISCV_FLOAT_ABI_QUAD,
+    EF_RISCV_FLOAT_ABI_SINGLE, EF_RISCV_RVC,
+};
+use object::{Architecture, FileFlags, Object, ObjectSection, SectionKind};
+
+use super::cfg::{CfgBuilder, ControlFlowGraph};
+use super::disasm::Disassembler;
+use super::listing;
+use super::{Extensions, Isa, Xlen};
+
+/// The `e_flags` bit marking an RV32E/RV64E binary.
+const EF_RISCV_RVE: u32 = 0x0008;
+
+/// The attribute tag holding the ISA string in `.riscv.attributes`.
+const TAG_RISCV_ARCH: u64 = 5;
+
+#[derive(Debug)]
+pub enum ElfError {
+    Object(object::Error),
+    UnsupportedArchitecture(Architecture),
+    Io(io::Error),
+}
+
+impl fmt::Display for ElfError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Object(e) => write!(f, "{}", e),
+            Self::UnsupportedArchitecture(arch) => {
+                write!(f, "not a RISC-V ELF file (architecture {:?})", arch)
+            }
+            Self::Io(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl Error for ElfError {}
+
+impl From<object::Error> for ElfError {
+    fn from(e: object::Error) -> Self {
+        Self::Object(e)
+    }
+}
+
+impl From<io::Error> for ElfError {
+    fn from(e: io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+/// Parses `data` as a RISC-V ELF file.
+pub fn parse(data: &[u8]) -> Result<object::File<'_>, ElfError> {
+    let file = object::File::parse(data)?;
+    match file.architecture() {
+        Architecture::Riscv32 | Architecture::Riscv64 => Ok(file),
+        arch => Err(ElfError::UnsupportedArchitecture(arch)),
+    }
+}
+
+/// Returns the ISA a RISC-V ELF file was built for.
+///
+/// This is the `Tag_RISCV_arch` ISA string from the `.riscv.attributes` section, if there is one
+/// that we can parse, skipping any extensions we don't know (see `Isa::parse_lenient`).
+/// Otherwise it's derived from the ELF header: the file class gives the XLEN,
+/// and `e_flags` gives the base (I or E), the C extension and the floating-point extension of the
+/// float ABI, on top of a default of IMA + Zicsr + Zifencei.
+pub fn isa(file: &object::File) -> Isa {
+    let from_attributes = file
+        .section_by_name(".riscv.attributes")
+        .and_then(|section| section.data().ok())
+        .and_then(riscv_arch_attribute)
+        .and_then(|arch| Isa::parse_lenient(arch).ok());
+    if let Some(isa) = from_attributes {
+        return isa;
+    }
+
+    let xlen = if file.is_64() { Xlen::Rv64 } else { Xlen::Rv32 };
+    let e_flags = match file.flags() {
+        FileFlags::Elf { e_flags, .. } => e_flags,
+        _ => 0,
+    };
+
+    let mut extensions = Extensions::IMAC - Extensions::C;
+    if e_flags & EF_RISCV_RVE != 0 {
+        extensions = (extensions - Extensions::I) | Extensions::E;
+    }
+    if e_flags & EF_RISCV_RVC != 0 {
+        extensions |= Extensions::C;
+    }
+    extensions |= match e_flags & EF_RISCV_FLOAT_ABI {
+        EF_RISCV_FLOAT_ABI_SINGLE => Extensions::F,
+        EF_RISCV_FLOAT_ABI_DOUBLE => Extensions::D,
+        EF_RISCV_FLOAT_ABI_QUAD => Extensions::Q,
+        _ => Extensions::empty(),
+    };
+
+    Isa {
+        xlen,
+        extensions: extensions.with_implied(),
+    }
+}
+
+/// Reads an unsigned LEB128 number from the start of `data`, advancing past it.
+fn read_uleb128(data: &mut &[u8]) -> Option<u64> {
+    let mut value = 0;
+    for shift in (0..64).step_by(7) {
+        let (&byte, rest) = data.split_first()?;
+        *data = rest;
+        value |= u64::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return Some(value);
+        }
+    }
+    None
+}
+
+/// Reads a NUL-terminated string from the start of `data`, advancing past it.
+fn read_ntbs<'a>(data: &mut &'a [u8]) -> Option<&'a str> {
+    let len = data.iter().position(|&b| b == 0)?;
+    let s = std::str::from_utf8(&data[..len]).ok()?;
+    *data = &data[len + 1..];
+    Some(s)
+}
+
+/// Finds the `Tag_RISCV_arch` ISA string in the contents of a `.riscv.attributes` section.
+///
+/// The section holds a format version (`'A'`), then vendor subsections, each made of a length, a
+/// vendor name and a list of sub-subsections. Only the file-scope attributes of the `riscv`
+/// vendor are searched. In those, even-numbered tags have a ULEB128 value and odd-numbered tags a
+/// NUL-terminated string.
+fn riscv_arch_attribute(data: &[u8]) -> Option<&str> {
+    let mut data = data.strip_prefix(b"A")?;
+    while data.len() >= 4 {
+        let len = u32::from_le_bytes(data[..4].try_into().ok()?) as usize;
+        let mut subsection = data.get(4..len)?;
+        data = &data[len..];
+        if read_ntbs(&mut subsection)? != "riscv" {
+            continue;
+        }
+
+        while let Some((&tag, rest)) = subsection.split_first() {
+            let len = u32::from_le_bytes(rest.get(..4)?.try_into().ok()?) as usize;
+            let mut attributes = subsection.get(5..len)?;
+            subsection = &subsection[len..];
+            // Tag_File; the other scopes (sections and symbols) don't describe the whole file.
+            if tag != 1 {
+                continue;
+            }
+
+            while !attributes.is_empty() {
+                let tag = read_uleb128(&mut attributes)?;
+                if tag % 2 == 0 {
+                    read_uleb128(&mut attributes)?;
+                } else {
+                    let value = read_ntbs(&mut attributes)?;
+                    if tag == TAG_RISCV_ARCH {
+                        return Some(value);
+                    }
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Writes an objdump-style listing of every executable section of `file`.
+///
+/// Each section and each symbol (if `disasm` has symbols) starts a new block, and every
+/// instruction is shown with its address and raw bits. `disasm` should be built for the ISA the
+/// file was built for; see `isa`.
+pub fn write_disassembly<W: Write>(
+    ostream: &mut W,
+    file: &object::File,
+    disasm: &Disassembler,
+) -> Result<(), ElfError> {
+    let address_width = if file.is_64() { 16 } else { 8 };
+
+    for section in file.sections() {
+        if section.kind() != SectionKind::Text {
+            continue;
+        }
+        let data = section.data()?;
+        writeln!(
+            ostream,
+            "\nDisassembly of section {}:",
+            section.name().unwrap_or("<unknown>")
+        )?;
+
+        listing::write_listing(ostream, section.address(), data, disasm, address_width)?;
+    }
+
+    Ok(())
+}
+
+/// Builds the control-flow graph of each executable section of `file`, without execution counts,
+/// and returns it with the section's name. Each section gets its own graph because the sections
+/// of relocatable objects (such as `.text.*` from `-ffunction-sections`) all start at address 0.
+/// `disasm` should be built for the ISA the file was built for; see `isa`.
+pub fn control_flow_graphs(
+    file: &object::File,
+    disasm: &Disassembler,
+) -> Result<Vec<(String, ControlFlowGraph)>, ElfError> {
+    let mut graphs = Vec::new();
+    for section in file.sections() {
+        if section.kind() != SectionKind::Text {
+            continue;
+        }
+        let mut builder = CfgBuilder::new();
+        builder.add_code(section.address(), section.data()?, disasm);
+        let name = section.name().unwrap_or("<unknown>").to_string();
+        graphs.push((name, builder.build()));
+    }
+    Ok(graphs)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::cfg::EdgeKind;
+    use crate::decode::TargetStyle;
+    use crate::instruction;
+    use crate::symbols::SymbolTable;
+
+    const SYMBOLS_O: &[u8] = include_bytes!("../inputs/elf/symbols.o");
+
+    #[test]
+    fn isa_from_elf() {
+        let file = parse(SYMBOLS_O).unwrap();
+        assert_eq!(isa(&file), "rv64imac".parse().unwrap());
+
+        assert!(parse(b"not an ELF file").is_err());
+    }
+
+    #[test]
+    fn isa_from_toolchain_arch() {
+        // Written by GCC 14 for `-march=rv64gcv_zba_zbb_zicond`, which names more extensions
+        // than we know.
+        let arch = "rv64i2p1_m2p0_a2p1_f2p2_d2p2_c2p0_v1p0_zicond1p0_zicsr2p0_zifencei2p0_\
+                    zmmul1p0_zaamo1p0_zalrsc1p0_zba1p0_zbb1p0_zve32f1p0_zve32x1p0_zve64d1p0_\
+                    zve64f1p0_zve64x1p0_zvl128b1p0_zvl32b1p0_zvl64b1p0";
+        assert!(arch.parse::<Isa>().is_err());
+        let isa = Isa::parse_lenient(arch).unwrap();
+        assert_eq!(isa.xlen, Xlen::Rv64);
+        assert_eq!(
+            isa.extensions,
+            Extensions::IMAFDC
+                | Extensions::V
+                | Extensions::ZMMUL
+                | Extensions::ZBA
+                | Extensions::ZBB
+        );
+    }
+
+    #[test]
+    fn parse_attributes() {
+        // Generated by GCC, with an integer attribute (Tag_RISCV_stack_align) before the ISA.
+        let mut section = b"A".to_vec();
+        let isa = b"rv32i2p1_m2p0_zicsr2p0\0";
+        let file_attrs_len = 1 + 4 + 2 + 1 + isa.len();
+        let subsection_len = 4 + 6 + file_attrs_len;
+        section.extend_from_slice(&(subsection_len as u32).to_le_bytes());
+        section.extend_from_slice(b"riscv\0");
+        section.push(1);
+        section.extend_from_slice(&(file_attrs_len as u32).to_le_bytes());
+        section.extend_from_slice(&[4, 16, 5]);
+        section.extend_from_slice(isa);
+        assert_eq!(
+            riscv_arch_attribute(&section),
+            Some("rv32i2p1_m2p0_zicsr2p0")
+        );
+
+        assert_eq!(riscv_arch_attribute(b"A"), None);
+        assert_eq!(riscv_arch_attribute(&section[..section.len() - 4]), None);
+        assert_eq!(riscv_arch_attribute(b"B"), None);
+    }
+
+    #[test]
+    fn disassemble_elf() {
+        let file = parse(SYMBOLS_O).unwrap();
+        let isa = isa(&file);
+        let instructions = instruction::gen_instructions(isa.xlen, isa.extensions, true);
+        let disasm = Disassembler::new(instructions)
+            .with_target_style(TargetStyle::Absolute)
+            .with_symbols(SymbolTable::from_file(&file));
+
+        let mut output = Vec::new();
+        write_disassembly(&mut output, &file, &disasm).unwrap();
+        let expected = "
+Disassembly of section .text:
+
+0000000000000000 <_start>:
+       0:\t00001137\tlui     sp, 0x1
+       4:\t00000097\tauipc   ra, 0x0
+       8:\t000080e7\tjalr    ra
+       c:\ta001    \tc.j     0xc <_start+0xc>
+
+000000000000000e <main>:
+       e:\t1141    \tc.addi  sp, -16
+      10:\te406    \tc.sdsp  ra, 8(sp)
+      12:\t450d    \tc.li    a0, 3
+      14:\t00a000ef\tjal     0x1e <square>
+      18:\t60a2    \tc.ldsp  ra, 8(sp)
+      1a:\t0141    \tc.addi  sp, 16
+      1c:\t8082    \tret
+
+000000000000001e <square>:
+      1e:\t02a50533\tmul     a0, a0, a0
+      22:\t8082    \tret
+";
+        assert_eq!(String::from_utf8(output).unwrap(), expected);
+    }
+
+    #[test]
+    fn elf_control_flow_graph() {
+        let file = parse(SYMBOLS_O).unwrap();
+        let disasm = Disassembler::for_isa(isa(&file), true);
+        let graphs = control_flow_graphs(&file, &disasm).unwrap();
+        let names: Vec<_> = graphs.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, [".text"]);
+        let cfg = &graphs[0].1;
+
+        let blocks: Vec<_> = cfg.blocks().map(|block| (block.start, block.end)).collect();
+        assert_eq!(
+            blocks,
+            [
+                (0x0, 0xc),
+                (0xc, 0xe),
+                (0xe, 0x18),
+                (0x18, 0x1e),
+                (0x1e, 0x24)
+            ]
+        );
+        let edges: Vec<_> = cfg
+            .edges()
+            .iter()
+            .map(|edge| (edge.from, edge.to, edge.kind, edge.count))
+            .collect();
+        assert_eq!(
+            edges,
+            [
+                (0x0, 0xc, EdgeKind::CallReturn, 0),
+                (0xc, 0xc, EdgeKind::Jump, 0),
+                (0xe, 0x18, EdgeKind::CallReturn, 0),
+                (0xe, 0x1e, EdgeKind::Call, 0)
+            ]
+        );
+    }
+}
@@ -0,0 +1,312 @@
+use std::error::Error;
+use std::fmt;
+use std::io::{self, Write};
+
+use super::disasm::Disassembler;
+use super::listing;
+
+/// A memory image: runs of contiguous bytes, each at its own address.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MemoryImage {
+    regions: Vec<(u64, Vec<u8>)>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum HexParseError {
+    /// A word that isn't a hex number, with its line number.
+    InvalidWord(usize, String),
+    /// An `@` directive that isn't a hex address, with its line number.
+    InvalidAddress(usize, String),
+    /// A word with more digits than the word width, with its line number.
+    WordTooWide(usize, String),
+    /// A `/*` comment without a closing `*/`.
+    UnterminatedComment,
+}
+
+impl fmt::Display for HexParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::InvalidWord(line, word) => {
+                write!(f, "line {}: invalid hex word '{}'", line, word)
+            }
+            Self::InvalidAddress(line, addr) => {
+                write!(f, "line {}: invalid address '{}'", line, addr)
+            }
+            Self::WordTooWide(line, word) => {
+                write!(
+                    f,
+                    "line {}: word '{}' is wider than the first word",
+                    line, word
+                )
+            }
+            Self::UnterminatedComment => f.write_str("unterminated /* comment"),
+        }
+    }
+}
+
+impl Error for HexParseError {}
+
+impl MemoryImage {
+    /// Returns an image of `data` loaded at `base`, as from a raw `.bin` file.
+    pub fn from_bin(data: Vec<u8>, base: u64) -> Self {
+        Self {
+            regions: vec![(base, data)],
+        }
+    }
+
+    /// Parses a Verilog `$readmemh` memory image, loaded at `base`.
+    ///
+    /// The file holds whitespace-separated hex words, which may contain `_` separators, plus `//`
+    /// and `/* */` comments. Words are stored little-endian, and `@<hex>` directives move to the
+    /// given word index. The word width is `word_bytes`, or otherwise the width of the first word,
+    /// rounded up to whole bytes; narrower words are zero-extended.
+    pub fn from_readmemh(
+        input: &str,
+        base: u64,
+        word_bytes: Option<usize>,
+    ) -> Result<Self, HexParseError> {
+        let mut image = Self::default();
+        let mut word_bytes = word_bytes;
+        let mut address = base;
+        // An `@` index seen before the word width is known.
+        let mut pending_word_idx = None;
+
+        let mut in_comment = false;
+        for (line_idx, line) in input.lines().enumerate() {
+            let line_num = line_idx + 1;
+            let mut line = line;
+            loop {
+                if in_comment {
+                    match line.find("*/") {
+                        Some(end) => {
+                            line = &line[end + 2..];
+                            in_comment = false;
+                        }
+                        None => break,
+                    }
+                }
+
+                let (code, rest) = match (line.find("//"), line.find("/*")) {
+                    (Some(line_comment), Some(block)) if line_comment < block => {
+                        (&line[..line_comment], None)
+                    }
+                    (_, Some(block)) => (&line[..block], Some(&line[block + 2..])),
+                    (Some(line_comment), None) => (&line[..line_comment], None),
+                    (None, None) => (line, None),
+                };
+
+                for token in code.split_whitespace() {
+                    if let Some(addr) = token.strip_prefix('@') {
+                        let word_idx = parse_hex(addr)
+                            .ok_or_else(|| HexParseError::InvalidAddress(line_num, token.into()))?;
+                        match word_bytes {
+                            Some(word_bytes) => address = word_address(base, word_idx, word_bytes),
+                            None => pending_word_idx = Some(word_idx),
+                        }
+                        continue;
+                    }
+
+                    let word = parse_hex(token)
+                        .ok_or_else(|| HexParseError::InvalidWord(line_num, token.into()))?;
+                    let digits = token.bytes().filter(|&b| b != b'_').count();
+                    let word_bytes = *word_bytes.get_or_insert_with(|| {
+                        let word_bytes = digits.div_ceil(2);
+                        if let Some(word_idx) = pending_word_idx {
+                            address = word_address(base, word_idx, word_bytes);
+                        }
+                        word_bytes
+                    });
+                    if digits > 2 * word_bytes || word_bytes > 8 {
+                        return Err(HexParseError::WordTooWide(line_num, token.into()));
+                    }
+                    image.push(address, &word.to_le_bytes()[..word_bytes]);
+                    address = address.wrapping_add(word_bytes as u64);
+                }
+
+                match rest {
+                    Some(rest) => {
+                        line = rest;
+                        in_comment = true;
+                    }
+                    None => break,
+                }
+            }
+        }
+
+        if in_comment {
+            return Err(HexParseError::UnterminatedComment);
+        }
+        Ok(image)
+    }
+
+    /// Appends `bytes` at `address`, extending the last region if they follow on from it.
+    fn push(&mut self, address: u64, bytes: &[u8]) {
+        match self.regions.last_mut() {
+            Some((start, data)) if *start + data.len() as u64 == address => {
+                data.extend_from_slice(bytes)
+            }
+            _ => self.regions.push((address, bytes.to_vec())),
+        }
+    }
+
+    /// Returns the regions of the image, as `(address, bytes)` pairs in the order they were loaded.
+    pub fn regions(&self) -> impl Iterator<Item = (u64, &[u8])> {
+        self.regions
+            .iter()
+            .map(|(address, data)| (*address, data.as_slice()))
+    }
+
+    /// Writes an objdump-style linear listing of the whole image, one region at a time.
+    pub fn write_disassembly<W: Write>(
+        &self,
+        ostream: &mut W,
+        disasm: &Disassembler,
+    ) -> io::Result<()> {
+        let max_address = self
+            .regions()
+            .map(|(address, data)| address + data.len() as u64)
+            .max()
+            .unwrap_or(0);
+        let address_width = if max_address > u32::MAX as u64 { 16 } else { 8 };
+
+        for (address, data) in self.regions() {
+            writeln!(
+                ostream,
+                "\nDisassembly of {:#0width$x}:",
+                address,
+                width = address_width + 2
+            )?;
+            listing::write_listing(ostream, address, data, disasm, address_width)?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns the address of word `word_idx` of an image loaded at `base`.
+fn word_address(base: u64, word_idx: u64, word_bytes: usize) -> u64 {
+    base.wrapping_add(word_idx.wrapping_mul(word_bytes as u64))
+}
+
+/// Parses a hex number of up to 64 bits, which may contain `_` separators.
+fn parse_hex(s: &str) -> Option<u64> {
+    let mut value: u64 = 0;
+    let mut digits = 0;
+    for c in s.chars().filter(|&c| c != '_') {
+        value = value.checked_mul(16)? + u64::from(c.to_digit(16)?);
+        digits += 1;
+    }
+    if digits == 0 {
+        return None;
+    }
+    Some(value)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::instruction;
+    use crate::{Extensions, Xlen};
+
+    #[test]
+    fn parse_readmemh() {
+        let input = "\
+// Boot ROM
+@0
+00000517 fc050513
+@4 /* skip ahead */ 8082_4505
+/* a comment
+   across lines */ 0001
+";
+        let image = MemoryImage::from_readmemh(input, 0x10000, None).unwrap();
+        let regions: Vec<_> = image.regions().collect();
+        assert_eq!(
+            regions,
+            vec![
+                (
+                    0x10000,
+                    &[0x17, 0x05, 0x00, 0x00, 0x13, 0x05, 0x05, 0xfc][..]
+                ),
+                (
+                    0x10010,
+                    &[0x05, 0x45, 0x82, 0x80, 0x01, 0x00, 0x00, 0x00][..]
+                ),
+            ]
+        );
+
+        // Byte-wide words, where `@` addresses are byte offsets.
+        let image = MemoryImage::from_readmemh("@10 05 45\n82 80", 0, None).unwrap();
+        let regions: Vec<_> = image.regions().collect();
+        assert_eq!(regions, vec![(0x10, &[0x05, 0x45, 0x82, 0x80][..])]);
+
+        // 16-bit words, given explicitly.
+        let image = MemoryImage::from_readmemh("@1 4505 8082", 0x100, Some(2)).unwrap();
+        let regions: Vec<_> = image.regions().collect();
+        assert_eq!(regions, vec![(0x102, &[0x05, 0x45, 0x82, 0x80][..])]);
+    }
+
+    #[test]
+    fn parse_readmemh_errors() {
+        assert_eq!(
+            MemoryImage::from_readmemh("0000\n12x4", 0, None),
+            Err(HexParseError::InvalidWord(2, "12x4".to_string()))
+        );
+        assert_eq!(
+            MemoryImage::from_readmemh("@zz", 0, None),
+            Err(HexParseError::InvalidAddress(1, "@zz".to_string()))
+        );
+        assert_eq!(
+            MemoryImage::from_readmemh("0000 00000000", 0, None),
+            Err(HexParseError::WordTooWide(1, "00000000".to_string()))
+        );
+        assert_eq!(
+            MemoryImage::from_readmemh("0000 /* 0000", 0, None),
+            Err(HexParseError::UnterminatedComment)
+        );
+    }
+
+    #[test]
+    fn disassemble_image() {
+        let instructions = instruction::gen_instructions(Xlen::Rv64, Extensions::IMAFDC, true);
+        let disasm = crate::disasm::Disassembler::new(instructions);
+
+        // A compressed instruction, a 32-bit one, and half of another 32-bit one at the end.
+        let image = MemoryImage::from_bin(
+            vec![0x05, 0x45, 0x13, 0x05, 0x05, 0xfc, 0x17, 0x05],
+            0x80000000,
+        );
+        let mut output = Vec::new();
+        image.write_disassembly(&mut output, &disasm).unwrap();
+        let expected = "
+Disassembly of 0x80000000:
+80000000:\t4505    \tc.li    a0, 1
+80000002:\tfc050513\taddi    a0, a0, -64
+80000006:\t0517    \t(truncated instruction)
+";
+        assert_eq!(String::from_utf8(output).unwrap(), expected);
+
+        // Without the C extension, the same halfword isn't an instruction.
+        let instructions = instruction::gen_instructions(Xlen::Rv64, Extensions::I, true);
+        let disasm = crate::disasm::Disassembler::new(instructions);
+        let image = MemoryImage::from_bin(vec![0x05, 0x45], 0);
+        let mut output = Vec::new();
+        image.write_disassembly(&mut output, &disasm).unwrap();
+        assert!(String::from_utf8(output)
+            .unwrap()
+            .ends_with("4505    \tunknown\n"));
+
+        // A 48-bit and an 80-bit instruction, which are skipped over as a whole.
+        let mut data = vec![0x1f, 0x00, 0x01, 0x02, 0x03, 0x04];
+        data.extend_from_slice(&[0x7f, 0x00, 1, 2, 3, 4, 5, 6, 7, 8]);
+        data.extend_from_slice(&[0x05, 0x45]);
+        let image = MemoryImage::from_bin(data, 0);
+        let mut output = Vec::new();
+        image.write_disassembly(&mut output, &disasm).unwrap();
+        let expected = "
+Disassembly of 0x00000000:
+       0:\t04030201001f\tunknown
+       6:\t0807060504030201007f\tunknown
+      10:\t4505    \tunknown
+";
+        assert_eq!(String::from_utf8(output).unwrap(), expected);
+    }
+}
@@ -1493,6 +1493,110 @@ pub(crate) const MATCH_VL4R_V: u32 = 0xe806007;
 pub(crate) const MASK_VL4R_V: u32 = 0xfff0707f;
 pub(crate) const MATCH_VL8R_V: u32 = 0x1e807007;
 pub(crate) const MASK_VL8R_V: u32 = 0xfff0707f;
+pub(crate) const MATCH_VSETIVLI: u32 = 0xc0007057;
+pub(crate) const MASK_VSETIVLI: u32 = 0xc000707f;
+pub(crate) const MATCH_VLM_V: u32 = 0x2b00007;
+pub(crate) const MASK_VLM_V: u32 = 0xfff0707f;
+pub(crate) const MATCH_VSM_V: u32 = 0x2b00027;
+pub(crate) const MASK_VSM_V: u32 = 0xfff0707f;
+pub(crate) const MATCH_VLUXEI8_V: u32 = 0x4000007;
+pub(crate) const MASK_VLUXEI8_V: u32 = 0x1c00707f;
+pub(crate) const MATCH_VLUXEI16_V: u32 = 0x4005007;
+pub(crate) const MASK_VLUXEI16_V: u32 = 0x1c00707f;
+pub(crate) const MATCH_VLUXEI32_V: u32 = 0x4006007;
+pub(crate) const MASK_VLUXEI32_V: u32 = 0x1c00707f;
+pub(crate) const MATCH_VLUXEI64_V: u32 = 0x4007007;
+pub(crate) const MASK_VLUXEI64_V: u32 = 0x1c00707f;
+pub(crate) const MATCH_SH1ADD: u32 = 0x20002033;
+pub(crate) const MASK_SH1ADD: u32 = 0xfe00707f;
+pub(crate) const MATCH_SH2ADD: u32 = 0x20004033;
+pub(crate) const MASK_SH2ADD: u32 = 0xfe00707f;
+pub(crate) const MATCH_SH3ADD: u32 = 0x20006033;
+pub(crate) const MASK_SH3ADD: u32 = 0xfe00707f;
+pub(crate) const MATCH_ADD_UW: u32 = 0x800003b;
+pub(crate) const MASK_ADD_UW: u32 = 0xfe00707f;
+pub(crate) const MATCH_SH1ADD_UW: u32 = 0x2000203b;
+pub(crate) const MASK_SH1ADD_UW: u32 = 0xfe00707f;
+pub(crate) const MATCH_SH2ADD_UW: u32 = 0x2000403b;
+pub(crate) const MASK_SH2ADD_UW: u32 = 0xfe00707f;
+pub(crate) const MATCH_SH3ADD_UW: u32 = 0x2000603b;
+pub(crate) const MASK_SH3ADD_UW: u32 = 0xfe00707f;
+pub(crate) const MATCH_SLLI_UW: u32 = 0x800101b;
+pub(crate) const MASK_SLLI_UW: u32 = 0xfc00707f;
+pub(crate) const MATCH_ANDN: u32 = 0x40007033;
+pub(crate) const MASK_ANDN: u32 = 0xfe00707f;
+pub(crate) const MATCH_ORN: u32 = 0x40006033;
+pub(crate) const MASK_ORN: u32 = 0xfe00707f;
+pub(crate) const MATCH_XNOR: u32 = 0x40004033;
+pub(crate) const MASK_XNOR: u32 = 0xfe00707f;
+pub(crate) const MATCH_CLZ: u32 = 0x60001013;
+pub(crate) const MASK_CLZ: u32 = 0xfff0707f;
+pub(crate) const MATCH_CTZ: u32 = 0x60101013;
+pub(crate) const MASK_CTZ: u32 = 0xfff0707f;
+pub(crate) const MATCH_CPOP: u32 = 0x60201013;
+pub(crate) const MASK_CPOP: u32 = 0xfff0707f;
+pub(crate) const MATCH_CLZW: u32 = 0x6000101b;
+pub(crate) const MASK_CLZW: u32 = 0xfff0707f;
+pub(crate) const MATCH_CTZW: u32 = 0x6010101b;
+pub(crate) const MASK_CTZW: u32 = 0xfff0707f;
+pub(crate) const MATCH_CPOPW: u32 = 0x6020101b;
+pub(crate) const MASK_CPOPW: u32 = 0xfff0707f;
+pub(crate) const MATCH_MAX: u32 = 0xa006033;
+pub(crate) const MASK_MAX: u32 = 0xfe00707f;
+pub(crate) const MATCH_MAXU: u32 = 0xa007033;
+pub(crate) const MASK_MAXU: u32 = 0xfe00707f;
+pub(crate) const MATCH_MIN: u32 = 0xa004033;
+pub(crate) const MASK_MIN: u32 = 0xfe00707f;
+pub(crate) const MATCH_MINU: u32 = 0xa005033;
+pub(crate) const MASK_MINU: u32 = 0xfe00707f;
+pub(crate) const MATCH_SEXT_B: u32 = 0x60401013;
+pub(crate) const MASK_SEXT_B: u32 = 0xfff0707f;
+pub(crate) const MATCH_SEXT_H: u32 = 0x60501013;
+pub(crate) const MASK_SEXT_H: u32 = 0xfff0707f;
+pub(crate) const MATCH_ZEXT_H_RV32: u32 = 0x8004033;
+pub(crate) const MASK_ZEXT_H_RV32: u32 = 0xfff0707f;
+pub(crate) const MATCH_ZEXT_H: u32 = 0x800403b;
+pub(crate) const MASK_ZEXT_H: u32 = 0xfff0707f;
+pub(crate) const MATCH_ROL: u32 = 0x60001033;
+pub(crate) const MASK_ROL: u32 = 0xfe00707f;
+pub(crate) const MATCH_ROR: u32 = 0x60005033;
+pub(crate) const MASK_ROR: u32 = 0xfe00707f;
+pub(crate) const MATCH_ROLW: u32 = 0x6000103b;
+pub(crate) const MASK_ROLW: u32 = 0xfe00707f;
+pub(crate) const MATCH_RORW: u32 = 0x6000503b;
+pub(crate) const MASK_RORW: u32 = 0xfe00707f;
+pub(crate) const MATCH_RORI: u32 = 0x60005013;
+pub(crate) const MASK_RORI: u32 = 0xfc00707f;
+pub(crate) const MATCH_RORIW: u32 = 0x6000501b;
+pub(crate) const MASK_RORIW: u32 = 0xfe00707f;
+pub(crate) const MATCH_ORC_B: u32 = 0x28705013;
+pub(crate) const MASK_ORC_B: u32 = 0xfff0707f;
+pub(crate) const MATCH_REV8_RV32: u32 = 0x69805013;
+pub(crate) const MASK_REV8_RV32: u32 = 0xfff0707f;
+pub(crate) const MATCH_REV8: u32 = 0x6b805013;
+pub(crate) const MASK_REV8: u32 = 0xfff0707f;
+pub(crate) const MATCH_CLMUL: u32 = 0xa001033;
+pub(crate) const MASK_CLMUL: u32 = 0xfe00707f;
+pub(crate) const MATCH_CLMULR: u32 = 0xa002033;
+pub(crate) const MASK_CLMULR: u32 = 0xfe00707f;
+pub(crate) const MATCH_CLMULH: u32 = 0xa003033;
+pub(crate) const MASK_CLMULH: u32 = 0xfe00707f;
+pub(crate) const MATCH_BCLR: u32 = 0x48001033;
+pub(crate) const MASK_BCLR: u32 = 0xfe00707f;
+pub(crate) const MATCH_BCLRI: u32 = 0x48001013;
+pub(crate) const MASK_BCLRI: u32 = 0xfc00707f;
+pub(crate) const MATCH_BEXT: u32 = 0x48005033;
+pub(crate) const MASK_BEXT: u32 = 0xfe00707f;
+pub(crate) const MATCH_BEXTI: u32 = 0x48005013;
+pub(crate) const MASK_BEXTI: u32 = 0xfc00707f;
+pub(crate) const MATCH_BINV: u32 = 0x68001033;
+pub(crate) const MASK_BINV: u32 = 0xfe00707f;
+pub(crate) const MATCH_BINVI: u32 = 0x68001013;
+pub(crate) const MASK_BINVI: u32 = 0xfc00707f;
+pub(crate) const MATCH_BSET: u32 = 0x28001033;
+pub(crate) const MASK_BSET: u32 = 0xfe00707f;
+pub(crate) const MATCH_BSETI: u32 = 0x28001013;
+pub(crate) const MASK_BSETI: u32 = 0xfc00707f;
 pub(crate) const CSR_FFLAGS: u16 = 0x1;
 pub(crate) const CSR_FRM: u16 = 0x2;
 pub(crate) const CSR_FCSR: u16 = 0x3;
@@ -1,38 +1,71 @@
 use std::fmt;
 
-use super::csrs;
+use super::decode::{DecodeError, DecodedInstruction, InstructionFormat, Operand};
 use super::inst;
-use super::registers::{self, FP_REGISTER_ABI_NAMES, INT_REGISTER_ABI_NAMES};
+use super::registers;
 use super::{Extensions, Xlen};
 
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum InstructionLen {
     TwoByte,
     FourByte,
+    SixByte,
+    EightByte,
 }
 
-#[derive(Clone, Copy)]
+impl InstructionLen {
+    /// Returns the length in bytes.
+    pub fn bytes(self) -> usize {
+        match self {
+            Self::TwoByte => 2,
+            Self::FourByte => 4,
+            Self::SixByte => 6,
+            Self::EightByte => 8,
+        }
+    }
+}
+
+/// Returns the length in bytes of the instruction whose lowest 16-bit parcel is `parcel`, using
+/// the standard variable-length encoding (16, 32, 48, 64, and 80 to 176 bits), or `None` for the
+/// encodings reserved for 192 bits and longer.
+pub fn encoded_len(parcel: u16) -> Option<usize> {
+    if parcel & 0b11 != 0b11 {
+        Some(2)
+    } else if parcel & 0b1_1100 != 0b1_1100 {
+        Some(4)
+    } else if parcel & 0b10_0000 == 0 {
+        Some(6)
+    } else if parcel & 0b100_0000 == 0 {
+        Some(8)
+    } else {
+        match (parcel >> 12) & 0b111 {
+            0b111 => None,
+            nnn => Some(10 + 2 * nnn as usize),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
 pub struct InstructionBits {
-    pub bits: u32,
+    pub bits: u64,
     pub length: InstructionLen,
 }
 
 impl InstructionBits {
-    pub fn new(x: u32) -> Result<Self, String> {
-        let inst_len = {
-            if x & 0b11 != 0b11 {
-                InstructionLen::TwoByte
-            } else if x & 0b11100 != 0b11100 {
-                InstructionLen::FourByte
-            } else {
-                // TODO: Maybe a real error type?
-                return Err(format!("Unsupported instruction length for {:x}", x));
-            }
+    /// Reads an instruction from the low bits of `x`, which must encode a length of 64 bits or
+    /// less. Any bits above that length are discarded.
+    pub fn new(x: u64) -> Result<Self, DecodeError> {
+        let inst_len = match encoded_len(x as u16) {
+            Some(2) => InstructionLen::TwoByte,
+            Some(4) => InstructionLen::FourByte,
+            Some(6) => InstructionLen::SixByte,
+            Some(8) => InstructionLen::EightByte,
+            _ => return Err(DecodeError::BadLength(x)),
         };
 
         let inst_bits = match inst_len {
-            InstructionLen::TwoByte => x & 0x0000_ffff,
-            InstructionLen::FourByte => x,
+            InstructionLen::EightByte => x,
+            _ => x & ((1 << (8 * inst_len.bytes())) - 1),
         };
 
         Ok(Self {
@@ -43,7 +76,7 @@ impl InstructionBits {
 
     fn shift_and_mask(&self, offset: u8, mask_width: u8) -> u32 {
         let mask = (1 << mask_width) - 1;
-        (self.bits >> offset) & mask
+        ((self.bits >> offset) as u32) & mask
     }
 
     fn shift_and_mask_signed(&self, offset: u8, mask_width: u8) -> u32 {
@@ -54,117 +87,73 @@ impl InstructionBits {
     }
 
     /// Returns the index of register `rd`
-    fn get_idx_rd(&self) -> u32 {
-        self.shift_and_mask(7, 5)
+    pub(crate) fn get_idx_rd(&self) -> u8 {
+        self.shift_and_mask(7, 5) as u8
     }
 
     /// Returns the index of register `rs1`
-    fn get_idx_rs1(&self) -> u32 {
-        self.shift_and_mask(15, 5)
+    pub(crate) fn get_idx_rs1(&self) -> u8 {
+        self.shift_and_mask(15, 5) as u8
     }
 
     /// Returns the index of register `rs2`
-    fn get_idx_rs2(&self) -> u32 {
-        self.shift_and_mask(20, 5)
+    fn get_idx_rs2(&self) -> u8 {
+        self.shift_and_mask(20, 5) as u8
     }
 
     /// Returns the index of register `rs3`
-    fn get_idx_rs3(&self) -> u32 {
-        self.shift_and_mask(27, 5)
+    fn get_idx_rs3(&self) -> u8 {
+        self.shift_and_mask(27, 5) as u8
     }
 
     /// Returns the index of register `rs1` in compressed instructions
-    fn get_idx_c_rs1(&self) -> u32 {
-        self.shift_and_mask(7, 5)
+    pub(crate) fn get_idx_c_rs1(&self) -> u8 {
+        self.shift_and_mask(7, 5) as u8
     }
 
     /// Returns the index of register `rs2` in compressed instructions
-    fn get_idx_c_rs2(&self) -> u32 {
-        self.shift_and_mask(2, 5)
+    fn get_idx_c_rs2(&self) -> u8 {
+        self.shift_and_mask(2, 5) as u8
     }
 
     /// Returns the index of register `rd'` (3-bit register encoding) in compressed instructions
     ///
     /// Note that this is the same index as register `rs2'`.
-    fn get_idx_c3_rd(&self) -> u32 {
-        self.shift_and_mask(2, 3)
-    }
-
-    /// Returns the index of register `rs1'` (3-bit register encoding) in compressed instructions
-    fn get_idx_c3_rs1(&self) -> u32 {
-        self.shift_and_mask(7, 3)
-    }
-
-    /// Returns the ABI name of integer register `rd`
-    pub fn get_x_rd(&self) -> &str {
-        INT_REGISTER_ABI_NAMES[self.get_idx_rd() as usize]
+    fn get_idx_c3_rd(&self) -> u8 {
+        self.shift_and_mask(2, 3) as u8 + 8
     }
 
-    /// Returns the ABI name of integer register `rs1`
-    pub fn get_x_rs1(&self) -> &str {
-        INT_REGISTER_ABI_NAMES[self.get_idx_rs1() as usize]
+    /// Returns the index of register `rs2'` (3-bit register encoding) in compressed instructions
+    fn get_idx_c3_rs2(&self) -> u8 {
+        self.get_idx_c3_rd()
     }
 
-    /// Returns the ABI name of integer register `rs2`
-    pub fn get_x_rs2(&self) -> &str {
-        INT_REGISTER_ABI_NAMES[self.get_idx_rs2() as usize]
-    }
-
-    /// Returns the ABI name of floating-point register `rd`
-    pub fn get_f_rd(&self) -> &str {
-        FP_REGISTER_ABI_NAMES[self.get_idx_rd() as usize]
-    }
-
-    /// Returns the ABI name of floating-point register `rs1`
-    pub fn get_f_rs1(&self) -> &str {
-        FP_REGISTER_ABI_NAMES[self.get_idx_rs1() as usize]
-    }
-
-    /// Returns the ABI name of floating-point register `rs2`
-    pub fn get_f_rs2(&self) -> &str {
-        FP_REGISTER_ABI_NAMES[self.get_idx_rs2() as usize]
-    }
-
-    /// Returns the ABI name of floating-point register `rs3`
-    pub fn get_f_rs3(&self) -> &str {
-        FP_REGISTER_ABI_NAMES[self.get_idx_rs3() as usize]
-    }
-
-    /// Returns the ABI name of integer register `rs1` in compressed instructions
-    pub fn get_x_c_rs1(&self) -> &str {
-        INT_REGISTER_ABI_NAMES[self.get_idx_c_rs1() as usize]
+    /// Returns the index of register `rs1'` (3-bit register encoding) in compressed instructions
+    fn get_idx_c3_rs1(&self) -> u8 {
+        self.shift_and_mask(7, 3) as u8 + 8
     }
 
-    /// Returns the ABI name of integer register `rs2` in compressed instructions
-    pub fn get_x_c_rs2(&self) -> &str {
-        INT_REGISTER_ABI_NAMES[self.get_idx_c_rs2() as usize]
+    /// Returns `true` if the vector instruction is unmasked (`vm` bit set)
+    pub fn get_v_vm(&self) -> bool {
+        self.shift_and_mask(25, 1) == 1
     }
 
-    /// Returns the ABI name of integer register `rd'` (3-bit register encoding) in compressed
-    /// instructions
-    ///
-    /// Note that this is the same ABI name as integer register `rs2'`.
-    pub fn get_x_c3_rd(&self) -> &str {
-        INT_REGISTER_ABI_NAMES[(self.get_idx_c3_rd() + 8) as usize]
+    /// Returns the `nf` field of vector loads/stores, which is one less than the number of fields
+    /// in each segment
+    pub fn get_v_nf(&self) -> u32 {
+        self.shift_and_mask(29, 3)
     }
 
-    /// Returns the ABI name of integer register `rs1'` (3-bit register encoding) in compressed
-    /// instructions
-    pub fn get_x_c3_rs1(&self) -> &str {
-        INT_REGISTER_ABI_NAMES[(self.get_idx_c3_rs1() + 8) as usize]
+    pub fn get_v_simm5(&self) -> i32 {
+        self.shift_and_mask_signed(15, 5) as i32
     }
 
-    /// Returns the ABI name of floating-point register `rd'` (3-bit register encoding) in
-    /// compressed instructions
-    ///
-    /// Note that this is the same ABI name as floating-point register `rs2'`.
-    pub fn get_f_c3_rd(&self) -> &str {
-        FP_REGISTER_ABI_NAMES[(self.get_idx_c3_rd() + 8) as usize]
+    pub fn get_v_zimm11(&self) -> u32 {
+        self.shift_and_mask(20, 11)
     }
 
-    /// Returns the ABI name of floating-point register `rs2` in compressed instructions
-    pub fn get_f_c_rs2(&self) -> &str {
-        FP_REGISTER_ABI_NAMES[self.get_idx_c_rs2() as usize]
+    pub fn get_v_zimm10(&self) -> u32 {
+        self.shift_and_mask(20, 10)
     }
 
     pub fn get_i_imm(&self) -> i32 {
@@ -310,36 +299,58 @@ impl InstructionBits {
 impl fmt::Debug for InstructionBits {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.debug_struct("InstructionBits")
-            .field("bits", &format_args!("{:0>8x}", self.bits))
+            .field(
+                "bits",
+                &format_args!("{:0>width$x}", self.bits, width = 2 * self.length.bytes()),
+            )
             .field("length", &self.length)
             .finish()
     }
 }
 
-// type FmtFn = Box<dyn Fn(&InstructionFilter, InstructionBits) -> String>;
-type FmtFn = fn(&InstructionFilter, InstructionBits) -> String;
-
-const INSTRUCTION_NAME_WIDTH: usize = 7;
+type DecodeFn = fn(&InstructionFilter, InstructionBits) -> DecodedInstruction;
 
 pub struct InstructionFilter {
     pub name: &'static str,
     mask: u32,
     r#match: u32,
-    pub formatter: FmtFn,
+    extension: Extensions,
+    decoder: DecodeFn,
 }
 
 impl InstructionFilter {
-    pub fn new(name: &'static str, mask: u32, r#match: u32, formatter: FmtFn) -> Self {
+    pub fn new(name: &'static str, mask: u32, r#match: u32, decoder: DecodeFn) -> Self {
         Self {
             name,
             mask,
             r#match,
-            formatter,
+            extension: Extensions::empty(),
+            decoder,
         }
     }
 
     pub fn is_eq(&self, other: InstructionBits) -> bool {
-        (other.bits & self.mask) == self.r#match
+        // No filter matches the all-ones low bits of 48-bit and longer instructions, so this only
+        // needs to look at the low 32 bits.
+        (other.bits as u32 & self.mask) == self.r#match
+    }
+
+    pub fn mask(&self) -> u32 {
+        self.mask
+    }
+
+    pub fn r#match(&self) -> u32 {
+        self.r#match
+    }
+
+    /// Returns the extensions that this instruction belongs to (e.g. C and D for `c.fld`).
+    pub fn extension(&self) -> Extensions {
+        self.extension
+    }
+
+    /// Decodes `inst_bits`, which must match this filter.
+    pub fn decode(&self, inst_bits: InstructionBits) -> DecodedInstruction {
+        (self.decoder)(self, inst_bits)
     }
 }
 
@@ -355,628 +366,1512 @@ impl fmt::Debug for InstructionFilter {
             .field("name", &self.name)
             .field("mask", &format_args!("{:0>8x}", self.mask))
             .field("match", &format_args!("{:0>8x}", self.r#match))
+            .field("extension", &self.extension)
             .finish()
     }
 }
 
-fn fmt_i_type(inst_filter: &InstructionFilter, inst_bits: InstructionBits) -> String {
-    format!(
-        "{:<width$} {}, {}, {}",
-        inst_filter.name,
-        inst_bits.get_x_rd(),
-        inst_bits.get_x_rs1(),
-        inst_bits.get_i_imm(),
-        width = INSTRUCTION_NAME_WIDTH
+/// Assigns `extension` to each filter in `filters` that doesn't already have one.
+fn with_extension(
+    mut filters: Vec<InstructionFilter>,
+    extension: Extensions,
+) -> Vec<InstructionFilter> {
+    for filter in filters
+        .iter_mut()
+        .filter(|filter| filter.extension.is_empty())
+    {
+        filter.extension = extension;
+    }
+    filters
+}
+
+/// Appends the `v0.t` operand to masked vector instructions.
+fn push_v_mask(decoded: &mut DecodedInstruction, inst_bits: InstructionBits) {
+    if !inst_bits.get_v_vm() {
+        decoded.operands.push(Operand::VecMask);
+    }
+}
+
+/// Vector loads and stores share their operand layouts, and are told apart by major opcode.
+fn v_mem_format(inst_bits: InstructionBits) -> InstructionFormat {
+    if inst_bits.bits & 0x7f == 0b000_0111 {
+        InstructionFormat::VL
+    } else {
+        InstructionFormat::VS
+    }
+}
+
+fn decode_i_type(
+    inst_filter: &InstructionFilter,
+    inst_bits: InstructionBits,
+) -> DecodedInstruction {
+    DecodedInstruction::new(
+        inst_filter,
+        inst_bits,
+        InstructionFormat::I,
+        &[
+            Operand::IntReg(inst_bits.get_idx_rd()),
+            Operand::IntReg(inst_bits.get_idx_rs1()),
+            Operand::Imm(inst_bits.get_i_imm() as i64),
+        ],
     )
 }
 
-fn fmt_i_type_shift(inst_filter: &InstructionFilter, inst_bits: InstructionBits) -> String {
-    format!(
-        "{:<width$} {}, {}, {}",
-        inst_filter.name,
-        inst_bits.get_x_rd(),
-        inst_bits.get_x_rs1(),
-        inst_bits.get_shamt(),
-        width = INSTRUCTION_NAME_WIDTH
+fn decode_i_type_shift(
+    inst_filter: &InstructionFilter,
+    inst_bits: InstructionBits,
+) -> DecodedInstruction {
+    DecodedInstruction::new(
+        inst_filter,
+        inst_bits,
+        InstructionFormat::I,
+        &[
+            Operand::IntReg(inst_bits.get_idx_rd()),
+            Operand::IntReg(inst_bits.get_idx_rs1()),
+            Operand::Imm(inst_bits.get_shamt() as i64),
+        ],
     )
 }
 
-fn fmt_i_type_just_rs1(inst_filter: &InstructionFilter, inst_bits: InstructionBits) -> String {
-    format!(
-        "{:<width$} {}",
-        inst_filter.name,
-        inst_bits.get_x_rs1(),
-        width = INSTRUCTION_NAME_WIDTH
+fn decode_i_type_just_rs1(
+    inst_filter: &InstructionFilter,
+    inst_bits: InstructionBits,
+) -> DecodedInstruction {
+    DecodedInstruction::new(
+        inst_filter,
+        inst_bits,
+        InstructionFormat::I,
+        &[Operand::IntReg(inst_bits.get_idx_rs1())],
     )
 }
 
-fn fmt_i_type_no_rs1(inst_filter: &InstructionFilter, inst_bits: InstructionBits) -> String {
-    format!(
-        "{:<width$} {}, {}",
-        inst_filter.name,
-        inst_bits.get_x_rd(),
-        inst_bits.get_i_imm(),
-        width = INSTRUCTION_NAME_WIDTH
+fn decode_i_type_no_rs1(
+    inst_filter: &InstructionFilter,
+    inst_bits: InstructionBits,
+) -> DecodedInstruction {
+    DecodedInstruction::new(
+        inst_filter,
+        inst_bits,
+        InstructionFormat::I,
+        &[
+            Operand::IntReg(inst_bits.get_idx_rd()),
+            Operand::Imm(inst_bits.get_i_imm() as i64),
+        ],
     )
 }
 
-fn fmt_i_type_no_imm(inst_filter: &InstructionFilter, inst_bits: InstructionBits) -> String {
-    format!(
-        "{:<width$} {}, {}",
-        inst_filter.name,
-        inst_bits.get_x_rd(),
-        inst_bits.get_x_rs1(),
-        width = INSTRUCTION_NAME_WIDTH
+fn decode_i_type_no_imm(
+    inst_filter: &InstructionFilter,
+    inst_bits: InstructionBits,
+) -> DecodedInstruction {
+    DecodedInstruction::new(
+        inst_filter,
+        inst_bits,
+        InstructionFormat::I,
+        &[
+            Operand::IntReg(inst_bits.get_idx_rd()),
+            Operand::IntReg(inst_bits.get_idx_rs1()),
+        ],
     )
 }
 
-fn fmt_u_type(inst_filter: &InstructionFilter, inst_bits: InstructionBits) -> String {
-    format!(
-        "{:<width$} {}, {:#x}",
-        inst_filter.name,
-        inst_bits.get_x_rd(),
-        inst_bits.get_big_imm(),
-        width = INSTRUCTION_NAME_WIDTH
+fn decode_u_type(
+    inst_filter: &InstructionFilter,
+    inst_bits: InstructionBits,
+) -> DecodedInstruction {
+    DecodedInstruction::new(
+        inst_filter,
+        inst_bits,
+        InstructionFormat::U,
+        &[
+            Operand::IntReg(inst_bits.get_idx_rd()),
+            Operand::UpperImm(inst_bits.get_big_imm()),
+        ],
     )
 }
 
-fn fmt_r_type(inst_filter: &InstructionFilter, inst_bits: InstructionBits) -> String {
-    format!(
-        "{:<width$} {}, {}, {}",
-        inst_filter.name,
-        inst_bits.get_x_rd(),
-        inst_bits.get_x_rs1(),
-        inst_bits.get_x_rs2(),
-        width = INSTRUCTION_NAME_WIDTH
+fn decode_r_type(
+    inst_filter: &InstructionFilter,
+    inst_bits: InstructionBits,
+) -> DecodedInstruction {
+    DecodedInstruction::new(
+        inst_filter,
+        inst_bits,
+        InstructionFormat::R,
+        &[
+            Operand::IntReg(inst_bits.get_idx_rd()),
+            Operand::IntReg(inst_bits.get_idx_rs1()),
+            Operand::IntReg(inst_bits.get_idx_rs2()),
+        ],
     )
 }
 
-fn fmt_r_type_no_rs1(inst_filter: &InstructionFilter, inst_bits: InstructionBits) -> String {
-    format!(
-        "{:<width$} {}, {}",
-        inst_filter.name,
-        inst_bits.get_x_rd(),
-        inst_bits.get_x_rs2(),
-        width = INSTRUCTION_NAME_WIDTH
+fn decode_r_type_no_rs1(
+    inst_filter: &InstructionFilter,
+    inst_bits: InstructionBits,
+) -> DecodedInstruction {
+    DecodedInstruction::new(
+        inst_filter,
+        inst_bits,
+        InstructionFormat::R,
+        &[
+            Operand::IntReg(inst_bits.get_idx_rd()),
+            Operand::IntReg(inst_bits.get_idx_rs2()),
+        ],
     )
 }
 
-fn fmt_j_type(inst_filter: &InstructionFilter, inst_bits: InstructionBits) -> String {
-    let jump_immediate = inst_bits.get_j_imm();
-    let operator = if jump_immediate.is_negative() {
-        '-'
-    } else {
-        '+'
-    };
-    let jump_immediate_pos = jump_immediate.abs();
-    format!(
-        "{:<width$} {}, pc {} {:#x}",
-        inst_filter.name,
-        inst_bits.get_x_rd(),
-        operator,
-        jump_immediate_pos,
-        width = INSTRUCTION_NAME_WIDTH
+fn decode_r_type_no_rs2(
+    inst_filter: &InstructionFilter,
+    inst_bits: InstructionBits,
+) -> DecodedInstruction {
+    DecodedInstruction::new(
+        inst_filter,
+        inst_bits,
+        InstructionFormat::R,
+        &[
+            Operand::IntReg(inst_bits.get_idx_rd()),
+            Operand::IntReg(inst_bits.get_idx_rs1()),
+        ],
     )
 }
 
-fn fmt_j_type_no_rd(inst_filter: &InstructionFilter, inst_bits: InstructionBits) -> String {
-    let jump_immediate = inst_bits.get_j_imm();
-    let operator = if jump_immediate.is_negative() {
-        '-'
-    } else {
-        '+'
-    };
-    let jump_immediate_pos = jump_immediate.abs();
-    format!(
-        "{:<width$} pc {} {:#x}",
-        inst_filter.name,
-        operator,
-        jump_immediate_pos,
-        width = INSTRUCTION_NAME_WIDTH
+fn decode_j_type(
+    inst_filter: &InstructionFilter,
+    inst_bits: InstructionBits,
+) -> DecodedInstruction {
+    DecodedInstruction::new(
+        inst_filter,
+        inst_bits,
+        InstructionFormat::J,
+        &[
+            Operand::IntReg(inst_bits.get_idx_rd()),
+            Operand::PcRel(inst_bits.get_j_imm() as i64),
+        ],
     )
 }
 
-fn fmt_b_type(inst_filter: &InstructionFilter, inst_bits: InstructionBits) -> String {
-    let branch_immediate = inst_bits.get_b_imm();
-    let operator = if branch_immediate.is_negative() {
-        '-'
-    } else {
-        '+'
-    };
-    let branch_immediate_pos = branch_immediate.abs();
-    format!(
-        "{:<width$} {}, {}, pc {} {}",
-        inst_filter.name,
-        inst_bits.get_x_rs1(),
-        inst_bits.get_x_rs2(),
-        operator,
-        branch_immediate_pos,
-        width = INSTRUCTION_NAME_WIDTH
-    )
-}
-
-fn fmt_b_type_no_rs2(inst_filter: &InstructionFilter, inst_bits: InstructionBits) -> String {
-    let branch_immediate = inst_bits.get_b_imm();
-    let operator = if branch_immediate.is_negative() {
-        '-'
-    } else {
-        '+'
-    };
-    let branch_immediate_pos = branch_immediate.abs();
-    format!(
-        "{:<width$} {}, pc {} {}",
-        inst_filter.name,
-        inst_bits.get_x_rs1(),
-        operator,
-        branch_immediate_pos,
-        width = INSTRUCTION_NAME_WIDTH
+fn decode_j_type_no_rd(
+    inst_filter: &InstructionFilter,
+    inst_bits: InstructionBits,
+) -> DecodedInstruction {
+    DecodedInstruction::new(
+        inst_filter,
+        inst_bits,
+        InstructionFormat::J,
+        &[Operand::PcRel(inst_bits.get_j_imm() as i64)],
     )
 }
 
-fn fmt_load(inst_filter: &InstructionFilter, inst_bits: InstructionBits) -> String {
-    format!(
-        "{:<width$} {}, {}({})",
-        inst_filter.name,
-        inst_bits.get_x_rd(),
-        inst_bits.get_i_imm(),
-        inst_bits.get_x_rs1(),
-        width = INSTRUCTION_NAME_WIDTH
+fn decode_b_type(
+    inst_filter: &InstructionFilter,
+    inst_bits: InstructionBits,
+) -> DecodedInstruction {
+    DecodedInstruction::new(
+        inst_filter,
+        inst_bits,
+        InstructionFormat::B,
+        &[
+            Operand::IntReg(inst_bits.get_idx_rs1()),
+            Operand::IntReg(inst_bits.get_idx_rs2()),
+            Operand::PcRel(inst_bits.get_b_imm() as i64),
+        ],
     )
 }
 
-fn fmt_store(inst_filter: &InstructionFilter, inst_bits: InstructionBits) -> String {
-    format!(
-        "{:<width$} {}, {}({})",
-        inst_filter.name,
-        inst_bits.get_x_rs2(),
-        inst_bits.get_s_imm(),
-        inst_bits.get_x_rs1(),
-        width = INSTRUCTION_NAME_WIDTH
+fn decode_b_type_no_rs2(
+    inst_filter: &InstructionFilter,
+    inst_bits: InstructionBits,
+) -> DecodedInstruction {
+    DecodedInstruction::new(
+        inst_filter,
+        inst_bits,
+        InstructionFormat::B,
+        &[
+            Operand::IntReg(inst_bits.get_idx_rs1()),
+            Operand::PcRel(inst_bits.get_b_imm() as i64),
+        ],
     )
 }
 
-fn fmt_no_args(inst_filter: &InstructionFilter, _inst_bits: InstructionBits) -> String {
-    inst_filter.name.to_string()
+fn decode_load(inst_filter: &InstructionFilter, inst_bits: InstructionBits) -> DecodedInstruction {
+    DecodedInstruction::new(
+        inst_filter,
+        inst_bits,
+        InstructionFormat::I,
+        &[
+            Operand::IntReg(inst_bits.get_idx_rd()),
+            Operand::Mem {
+                base: inst_bits.get_idx_rs1(),
+                offset: Some(inst_bits.get_i_imm() as i64),
+            },
+        ],
+    )
 }
 
-fn fmt_rs1_rs2(inst_filter: &InstructionFilter, inst_bits: InstructionBits) -> String {
-    format!(
-        "{:<width$} {}, {}",
-        inst_filter.name,
-        inst_bits.get_x_rs1(),
-        inst_bits.get_x_rs2(),
-        width = INSTRUCTION_NAME_WIDTH
+fn decode_store(inst_filter: &InstructionFilter, inst_bits: InstructionBits) -> DecodedInstruction {
+    DecodedInstruction::new(
+        inst_filter,
+        inst_bits,
+        InstructionFormat::S,
+        &[
+            Operand::IntReg(inst_bits.get_idx_rs2()),
+            Operand::Mem {
+                base: inst_bits.get_idx_rs1(),
+                offset: Some(inst_bits.get_s_imm() as i64),
+            },
+        ],
     )
 }
 
-fn fmt_csr(inst_filter: &InstructionFilter, inst_bits: InstructionBits) -> String {
-    let csr_index = inst_bits.get_csr();
-    let csr_str = csrs::lookup_csr(csr_index).unwrap_or("unknown");
-    format!(
-        "{:<width$} {}, {}, {}",
-        inst_filter.name,
-        inst_bits.get_x_rd(),
-        csr_str,
-        inst_bits.get_x_rs1(),
-        width = INSTRUCTION_NAME_WIDTH
+fn decode_no_args(
+    inst_filter: &InstructionFilter,
+    inst_bits: InstructionBits,
+) -> DecodedInstruction {
+    DecodedInstruction::new(inst_filter, inst_bits, InstructionFormat::I, &[])
+}
+
+fn decode_rs1_rs2(
+    inst_filter: &InstructionFilter,
+    inst_bits: InstructionBits,
+) -> DecodedInstruction {
+    DecodedInstruction::new(
+        inst_filter,
+        inst_bits,
+        InstructionFormat::R,
+        &[
+            Operand::IntReg(inst_bits.get_idx_rs1()),
+            Operand::IntReg(inst_bits.get_idx_rs2()),
+        ],
     )
 }
 
-fn fmt_csr_no_rs1(inst_filter: &InstructionFilter, inst_bits: InstructionBits) -> String {
-    let csr_index = inst_bits.get_csr();
-    let csr_str = csrs::lookup_csr(csr_index).unwrap_or("unknown");
-    format!(
-        "{:<width$} {}, {}",
-        inst_filter.name,
-        inst_bits.get_x_rd(),
-        csr_str,
-        width = INSTRUCTION_NAME_WIDTH
+fn decode_csr(inst_filter: &InstructionFilter, inst_bits: InstructionBits) -> DecodedInstruction {
+    DecodedInstruction::new(
+        inst_filter,
+        inst_bits,
+        InstructionFormat::I,
+        &[
+            Operand::IntReg(inst_bits.get_idx_rd()),
+            Operand::Csr(inst_bits.get_csr() as u16),
+            Operand::IntReg(inst_bits.get_idx_rs1()),
+        ],
     )
 }
 
-fn fmt_csr_no_rd(inst_filter: &InstructionFilter, inst_bits: InstructionBits) -> String {
-    let csr_index = inst_bits.get_csr();
-    let csr_str = csrs::lookup_csr(csr_index).unwrap_or("unknown");
-    format!(
-        "{:<width$} {}, {}",
-        inst_filter.name,
-        csr_str,
-        inst_bits.get_x_rs1(),
-        width = INSTRUCTION_NAME_WIDTH
+fn decode_csr_no_rs1(
+    inst_filter: &InstructionFilter,
+    inst_bits: InstructionBits,
+) -> DecodedInstruction {
+    DecodedInstruction::new(
+        inst_filter,
+        inst_bits,
+        InstructionFormat::I,
+        &[
+            Operand::IntReg(inst_bits.get_idx_rd()),
+            Operand::Csr(inst_bits.get_csr() as u16),
+        ],
     )
 }
 
-fn fmt_csr_imm(inst_filter: &InstructionFilter, inst_bits: InstructionBits) -> String {
-    let csr_index = inst_bits.get_csr();
-    let csr_str = csrs::lookup_csr(csr_index).unwrap_or("unknown");
-    format!(
-        "{:<width$} {}, {}, {}",
-        inst_filter.name,
-        inst_bits.get_x_rd(),
-        csr_str,
-        inst_bits.get_uimm5(),
-        width = INSTRUCTION_NAME_WIDTH
+fn decode_csr_no_rd(
+    inst_filter: &InstructionFilter,
+    inst_bits: InstructionBits,
+) -> DecodedInstruction {
+    DecodedInstruction::new(
+        inst_filter,
+        inst_bits,
+        InstructionFormat::I,
+        &[
+            Operand::Csr(inst_bits.get_csr() as u16),
+            Operand::IntReg(inst_bits.get_idx_rs1()),
+        ],
     )
 }
 
-fn fmt_csr_imm_no_rd(inst_filter: &InstructionFilter, inst_bits: InstructionBits) -> String {
-    let csr_index = inst_bits.get_csr();
-    let csr_str = csrs::lookup_csr(csr_index).unwrap_or("unknown");
-    format!(
-        "{:<width$} {}, {}",
-        inst_filter.name,
-        csr_str,
-        inst_bits.get_uimm5(),
-        width = INSTRUCTION_NAME_WIDTH
+fn decode_csr_imm(
+    inst_filter: &InstructionFilter,
+    inst_bits: InstructionBits,
+) -> DecodedInstruction {
+    DecodedInstruction::new(
+        inst_filter,
+        inst_bits,
+        InstructionFormat::I,
+        &[
+            Operand::IntReg(inst_bits.get_idx_rd()),
+            Operand::Csr(inst_bits.get_csr() as u16),
+            Operand::Imm(inst_bits.get_uimm5() as i64),
+        ],
     )
 }
 
-fn fmt_amo_lr(inst_filter: &InstructionFilter, inst_bits: InstructionBits) -> String {
-    format!(
-        "{:<width$} {}, ({})",
-        inst_filter.name,
-        inst_bits.get_x_rd(),
-        inst_bits.get_x_rs1(),
-        width = INSTRUCTION_NAME_WIDTH
+fn decode_csr_imm_no_rd(
+    inst_filter: &InstructionFilter,
+    inst_bits: InstructionBits,
+) -> DecodedInstruction {
+    DecodedInstruction::new(
+        inst_filter,
+        inst_bits,
+        InstructionFormat::I,
+        &[
+            Operand::Csr(inst_bits.get_csr() as u16),
+            Operand::Imm(inst_bits.get_uimm5() as i64),
+        ],
     )
 }
 
-fn fmt_amo(inst_filter: &InstructionFilter, inst_bits: InstructionBits) -> String {
-    format!(
-        "{:<width$} {}, {}, ({})",
-        inst_filter.name,
-        inst_bits.get_x_rd(),
-        inst_bits.get_x_rs2(),
-        inst_bits.get_x_rs1(),
-        width = INSTRUCTION_NAME_WIDTH
+fn decode_amo_lr(
+    inst_filter: &InstructionFilter,
+    inst_bits: InstructionBits,
+) -> DecodedInstruction {
+    DecodedInstruction::new(
+        inst_filter,
+        inst_bits,
+        InstructionFormat::R,
+        &[
+            Operand::IntReg(inst_bits.get_idx_rd()),
+            Operand::Mem {
+                base: inst_bits.get_idx_rs1(),
+                offset: None,
+            },
+        ],
     )
 }
 
-fn fmt_fp_load(inst_filter: &InstructionFilter, inst_bits: InstructionBits) -> String {
-    format!(
-        "{:<width$} {}, {}({})",
-        inst_filter.name,
-        inst_bits.get_f_rd(),
-        inst_bits.get_i_imm(),
-        inst_bits.get_x_rs1(),
-        width = INSTRUCTION_NAME_WIDTH
+fn decode_amo(inst_filter: &InstructionFilter, inst_bits: InstructionBits) -> DecodedInstruction {
+    DecodedInstruction::new(
+        inst_filter,
+        inst_bits,
+        InstructionFormat::R,
+        &[
+            Operand::IntReg(inst_bits.get_idx_rd()),
+            Operand::IntReg(inst_bits.get_idx_rs2()),
+            Operand::Mem {
+                base: inst_bits.get_idx_rs1(),
+                offset: None,
+            },
+        ],
     )
 }
 
-fn fmt_fp_store(inst_filter: &InstructionFilter, inst_bits: InstructionBits) -> String {
-    format!(
-        "{:<width$} {}, {}({})",
-        inst_filter.name,
-        inst_bits.get_f_rs2(),
-        inst_bits.get_s_imm(),
-        inst_bits.get_x_rs1(),
-        width = INSTRUCTION_NAME_WIDTH
+fn decode_hyp_load(
+    inst_filter: &InstructionFilter,
+    inst_bits: InstructionBits,
+) -> DecodedInstruction {
+    DecodedInstruction::new(
+        inst_filter,
+        inst_bits,
+        InstructionFormat::R,
+        &[
+            Operand::IntReg(inst_bits.get_idx_rd()),
+            Operand::Mem {
+                base: inst_bits.get_idx_rs1(),
+                offset: None,
+            },
+        ],
     )
 }
 
-fn fmt_fp_r_type(inst_filter: &InstructionFilter, inst_bits: InstructionBits) -> String {
-    format!(
-        "{:<width$} {}, {}, {}",
-        inst_filter.name,
-        inst_bits.get_f_rd(),
-        inst_bits.get_f_rs1(),
-        inst_bits.get_f_rs2(),
-        width = INSTRUCTION_NAME_WIDTH
+fn decode_hyp_store(
+    inst_filter: &InstructionFilter,
+    inst_bits: InstructionBits,
+) -> DecodedInstruction {
+    DecodedInstruction::new(
+        inst_filter,
+        inst_bits,
+        InstructionFormat::R,
+        &[
+            Operand::IntReg(inst_bits.get_idx_rs2()),
+            Operand::Mem {
+                base: inst_bits.get_idx_rs1(),
+                offset: None,
+            },
+        ],
     )
 }
 
-fn fmt_fp_r_type_no_rs2(inst_filter: &InstructionFilter, inst_bits: InstructionBits) -> String {
-    format!(
-        "{:<width$} {}, {}",
-        inst_filter.name,
-        inst_bits.get_f_rd(),
-        inst_bits.get_f_rs1(),
-        width = INSTRUCTION_NAME_WIDTH
+fn decode_fp_load(
+    inst_filter: &InstructionFilter,
+    inst_bits: InstructionBits,
+) -> DecodedInstruction {
+    DecodedInstruction::new(
+        inst_filter,
+        inst_bits,
+        InstructionFormat::I,
+        &[
+            Operand::FpReg(inst_bits.get_idx_rd()),
+            Operand::Mem {
+                base: inst_bits.get_idx_rs1(),
+                offset: Some(inst_bits.get_i_imm() as i64),
+            },
+        ],
     )
 }
 
-fn fmt_fp_r_type_with_rs3(inst_filter: &InstructionFilter, inst_bits: InstructionBits) -> String {
-    format!(
-        "{:<width$} {}, {}, {}, {}",
-        inst_filter.name,
-        inst_bits.get_f_rd(),
-        inst_bits.get_f_rs1(),
-        inst_bits.get_f_rs2(),
-        inst_bits.get_f_rs3(),
-        width = INSTRUCTION_NAME_WIDTH
+fn decode_fp_store(
+    inst_filter: &InstructionFilter,
+    inst_bits: InstructionBits,
+) -> DecodedInstruction {
+    DecodedInstruction::new(
+        inst_filter,
+        inst_bits,
+        InstructionFormat::S,
+        &[
+            Operand::FpReg(inst_bits.get_idx_rs2()),
+            Operand::Mem {
+                base: inst_bits.get_idx_rs1(),
+                offset: Some(inst_bits.get_s_imm() as i64),
+            },
+        ],
     )
 }
 
-fn fmt_fp_r_type_from_int(inst_filter: &InstructionFilter, inst_bits: InstructionBits) -> String {
-    format!(
-        "{:<width$} {}, {}",
-        inst_filter.name,
-        inst_bits.get_f_rd(),
-        inst_bits.get_x_rs1(),
-        width = INSTRUCTION_NAME_WIDTH
+fn decode_fp_r_type(
+    inst_filter: &InstructionFilter,
+    inst_bits: InstructionBits,
+) -> DecodedInstruction {
+    DecodedInstruction::new(
+        inst_filter,
+        inst_bits,
+        InstructionFormat::R,
+        &[
+            Operand::FpReg(inst_bits.get_idx_rd()),
+            Operand::FpReg(inst_bits.get_idx_rs1()),
+            Operand::FpReg(inst_bits.get_idx_rs2()),
+        ],
     )
 }
 
-fn fmt_fp_r_type_to_int(inst_filter: &InstructionFilter, inst_bits: InstructionBits) -> String {
-    format!(
-        "{:<width$} {}, {}",
-        inst_filter.name,
-        inst_bits.get_x_rd(),
-        inst_bits.get_f_rs1(),
-        width = INSTRUCTION_NAME_WIDTH
+fn decode_fp_r_type_no_rs2(
+    inst_filter: &InstructionFilter,
+    inst_bits: InstructionBits,
+) -> DecodedInstruction {
+    DecodedInstruction::new(
+        inst_filter,
+        inst_bits,
+        InstructionFormat::R,
+        &[
+            Operand::FpReg(inst_bits.get_idx_rd()),
+            Operand::FpReg(inst_bits.get_idx_rs1()),
+        ],
     )
 }
 
-fn fmt_fp_r_type_int_rd(inst_filter: &InstructionFilter, inst_bits: InstructionBits) -> String {
-    format!(
-        "{:<width$} {}, {}, {}",
-        inst_filter.name,
-        inst_bits.get_x_rd(),
-        inst_bits.get_f_rs1(),
-        inst_bits.get_f_rs2(),
-        width = INSTRUCTION_NAME_WIDTH
+fn decode_fp_r_type_with_rs3(
+    inst_filter: &InstructionFilter,
+    inst_bits: InstructionBits,
+) -> DecodedInstruction {
+    DecodedInstruction::new(
+        inst_filter,
+        inst_bits,
+        InstructionFormat::R4,
+        &[
+            Operand::FpReg(inst_bits.get_idx_rd()),
+            Operand::FpReg(inst_bits.get_idx_rs1()),
+            Operand::FpReg(inst_bits.get_idx_rs2()),
+            Operand::FpReg(inst_bits.get_idx_rs3()),
+        ],
     )
 }
 
-fn fmt_ci_type_lwsp(inst_filter: &InstructionFilter, inst_bits: InstructionBits) -> String {
-    format!(
-        "{:<width$} {}, {}(sp)",
-        inst_filter.name,
-        inst_bits.get_x_rd(),
-        inst_bits.get_ci_lwsp_imm(),
-        width = INSTRUCTION_NAME_WIDTH
+fn decode_fp_r_type_from_int(
+    inst_filter: &InstructionFilter,
+    inst_bits: InstructionBits,
+) -> DecodedInstruction {
+    DecodedInstruction::new(
+        inst_filter,
+        inst_bits,
+        InstructionFormat::R,
+        &[
+            Operand::FpReg(inst_bits.get_idx_rd()),
+            Operand::IntReg(inst_bits.get_idx_rs1()),
+        ],
     )
 }
 
-fn fmt_ci_type_flwsp(inst_filter: &InstructionFilter, inst_bits: InstructionBits) -> String {
-    format!(
-        "{:<width$} {}, {}(sp)",
-        inst_filter.name,
-        inst_bits.get_f_rd(),
-        inst_bits.get_ci_lwsp_imm(),
-        width = INSTRUCTION_NAME_WIDTH
+fn decode_fp_r_type_to_int(
+    inst_filter: &InstructionFilter,
+    inst_bits: InstructionBits,
+) -> DecodedInstruction {
+    DecodedInstruction::new(
+        inst_filter,
+        inst_bits,
+        InstructionFormat::R,
+        &[
+            Operand::IntReg(inst_bits.get_idx_rd()),
+            Operand::FpReg(inst_bits.get_idx_rs1()),
+        ],
     )
 }
 
-fn fmt_ci_type_ldsp(inst_filter: &InstructionFilter, inst_bits: InstructionBits) -> String {
-    format!(
-        "{:<width$} {}, {}(sp)",
-        inst_filter.name,
-        inst_bits.get_x_rd(),
-        inst_bits.get_ci_ldsp_imm(),
-        width = INSTRUCTION_NAME_WIDTH
+fn decode_fp_r_type_int_rd(
+    inst_filter: &InstructionFilter,
+    inst_bits: InstructionBits,
+) -> DecodedInstruction {
+    DecodedInstruction::new(
+        inst_filter,
+        inst_bits,
+        InstructionFormat::R,
+        &[
+            Operand::IntReg(inst_bits.get_idx_rd()),
+            Operand::FpReg(inst_bits.get_idx_rs1()),
+            Operand::FpReg(inst_bits.get_idx_rs2()),
+        ],
     )
 }
 
-fn fmt_ci_type_fldsp(inst_filter: &InstructionFilter, inst_bits: InstructionBits) -> String {
-    format!(
-        "{:<width$} {}, {}(sp)",
-        inst_filter.name,
-        inst_bits.get_f_rd(),
-        inst_bits.get_ci_ldsp_imm(),
-        width = INSTRUCTION_NAME_WIDTH
+fn decode_ci_type_lwsp(
+    inst_filter: &InstructionFilter,
+    inst_bits: InstructionBits,
+) -> DecodedInstruction {
+    DecodedInstruction::new(
+        inst_filter,
+        inst_bits,
+        InstructionFormat::CI,
+        &[
+            Operand::IntReg(inst_bits.get_idx_rd()),
+            Operand::Mem {
+                base: 2,
+                offset: Some(inst_bits.get_ci_lwsp_imm() as i64),
+            },
+        ],
     )
 }
 
-fn fmt_css_type_swsp(inst_filter: &InstructionFilter, inst_bits: InstructionBits) -> String {
-    format!(
-        "{:<width$} {}, {}(sp)",
-        inst_filter.name,
-        inst_bits.get_x_c_rs2(),
-        inst_bits.get_css_swsp_imm(),
-        width = INSTRUCTION_NAME_WIDTH
+fn decode_ci_type_flwsp(
+    inst_filter: &InstructionFilter,
+    inst_bits: InstructionBits,
+) -> DecodedInstruction {
+    DecodedInstruction::new(
+        inst_filter,
+        inst_bits,
+        InstructionFormat::CI,
+        &[
+            Operand::FpReg(inst_bits.get_idx_rd()),
+            Operand::Mem {
+                base: 2,
+                offset: Some(inst_bits.get_ci_lwsp_imm() as i64),
+            },
+        ],
     )
 }
 
-fn fmt_css_type_fswsp(inst_filter: &InstructionFilter, inst_bits: InstructionBits) -> String {
-    format!(
-        "{:<width$} {}, {}(sp)",
-        inst_filter.name,
-        inst_bits.get_f_c_rs2(),
-        inst_bits.get_css_swsp_imm(),
-        width = INSTRUCTION_NAME_WIDTH
+fn decode_ci_type_ldsp(
+    inst_filter: &InstructionFilter,
+    inst_bits: InstructionBits,
+) -> DecodedInstruction {
+    DecodedInstruction::new(
+        inst_filter,
+        inst_bits,
+        InstructionFormat::CI,
+        &[
+            Operand::IntReg(inst_bits.get_idx_rd()),
+            Operand::Mem {
+                base: 2,
+                offset: Some(inst_bits.get_ci_ldsp_imm() as i64),
+            },
+        ],
     )
 }
 
-fn fmt_css_type_sdsp(inst_filter: &InstructionFilter, inst_bits: InstructionBits) -> String {
-    format!(
-        "{:<width$} {}, {}(sp)",
-        inst_filter.name,
-        inst_bits.get_x_c_rs2(),
-        inst_bits.get_css_sdsp_imm(),
-        width = INSTRUCTION_NAME_WIDTH
+fn decode_ci_type_fldsp(
+    inst_filter: &InstructionFilter,
+    inst_bits: InstructionBits,
+) -> DecodedInstruction {
+    DecodedInstruction::new(
+        inst_filter,
+        inst_bits,
+        InstructionFormat::CI,
+        &[
+            Operand::FpReg(inst_bits.get_idx_rd()),
+            Operand::Mem {
+                base: 2,
+                offset: Some(inst_bits.get_ci_ldsp_imm() as i64),
+            },
+        ],
     )
 }
 
-fn fmt_css_type_fsdsp(inst_filter: &InstructionFilter, inst_bits: InstructionBits) -> String {
-    format!(
-        "{:<width$} {}, {}(sp)",
-        inst_filter.name,
-        inst_bits.get_f_c_rs2(),
-        inst_bits.get_css_sdsp_imm(),
-        width = INSTRUCTION_NAME_WIDTH
+fn decode_css_type_swsp(
+    inst_filter: &InstructionFilter,
+    inst_bits: InstructionBits,
+) -> DecodedInstruction {
+    DecodedInstruction::new(
+        inst_filter,
+        inst_bits,
+        InstructionFormat::CSS,
+        &[
+            Operand::IntReg(inst_bits.get_idx_c_rs2()),
+            Operand::Mem {
+                base: 2,
+                offset: Some(inst_bits.get_css_swsp_imm() as i64),
+            },
+        ],
     )
 }
 
-fn fmt_cl_type_lw(inst_filter: &InstructionFilter, inst_bits: InstructionBits) -> String {
-    format!(
-        "{:<width$} {}, {}({})",
-        inst_filter.name,
-        inst_bits.get_x_c3_rd(),
-        inst_bits.get_cl_lw_imm(),
-        inst_bits.get_x_c3_rs1(),
-        width = INSTRUCTION_NAME_WIDTH
+fn decode_css_type_fswsp(
+    inst_filter: &InstructionFilter,
+    inst_bits: InstructionBits,
+) -> DecodedInstruction {
+    DecodedInstruction::new(
+        inst_filter,
+        inst_bits,
+        InstructionFormat::CSS,
+        &[
+            Operand::FpReg(inst_bits.get_idx_c_rs2()),
+            Operand::Mem {
+                base: 2,
+                offset: Some(inst_bits.get_css_swsp_imm() as i64),
+            },
+        ],
     )
 }
 
-fn fmt_cl_type_ld(inst_filter: &InstructionFilter, inst_bits: InstructionBits) -> String {
-    format!(
-        "{:<width$} {}, {}({})",
-        inst_filter.name,
-        inst_bits.get_x_c3_rd(),
-        inst_bits.get_cl_ld_imm(),
-        inst_bits.get_x_c3_rs1(),
-        width = INSTRUCTION_NAME_WIDTH
+fn decode_css_type_sdsp(
+    inst_filter: &InstructionFilter,
+    inst_bits: InstructionBits,
+) -> DecodedInstruction {
+    DecodedInstruction::new(
+        inst_filter,
+        inst_bits,
+        InstructionFormat::CSS,
+        &[
+            Operand::IntReg(inst_bits.get_idx_c_rs2()),
+            Operand::Mem {
+                base: 2,
+                offset: Some(inst_bits.get_css_sdsp_imm() as i64),
+            },
+        ],
     )
 }
 
-fn fmt_cl_type_flw(inst_filter: &InstructionFilter, inst_bits: InstructionBits) -> String {
-    format!(
-        "{:<width$} {}, {}({})",
-        inst_filter.name,
-        inst_bits.get_f_c3_rd(),
-        inst_bits.get_cl_lw_imm(),
-        inst_bits.get_x_c3_rs1(),
-        width = INSTRUCTION_NAME_WIDTH
+fn decode_css_type_fsdsp(
+    inst_filter: &InstructionFilter,
+    inst_bits: InstructionBits,
+) -> DecodedInstruction {
+    DecodedInstruction::new(
+        inst_filter,
+        inst_bits,
+        InstructionFormat::CSS,
+        &[
+            Operand::FpReg(inst_bits.get_idx_c_rs2()),
+            Operand::Mem {
+                base: 2,
+                offset: Some(inst_bits.get_css_sdsp_imm() as i64),
+            },
+        ],
     )
 }
 
-fn fmt_cl_type_fld(inst_filter: &InstructionFilter, inst_bits: InstructionBits) -> String {
-    format!(
-        "{:<width$} {}, {}({})",
-        inst_filter.name,
-        inst_bits.get_f_c3_rd(),
-        inst_bits.get_cl_ld_imm(),
-        inst_bits.get_x_c3_rs1(),
-        width = INSTRUCTION_NAME_WIDTH
+fn decode_cl_type_lw(
+    inst_filter: &InstructionFilter,
+    inst_bits: InstructionBits,
+) -> DecodedInstruction {
+    DecodedInstruction::new(
+        inst_filter,
+        inst_bits,
+        InstructionFormat::CL,
+        &[
+            Operand::IntReg(inst_bits.get_idx_c3_rd()),
+            Operand::Mem {
+                base: inst_bits.get_idx_c3_rs1(),
+                offset: Some(inst_bits.get_cl_lw_imm() as i64),
+            },
+        ],
     )
 }
 
-fn fmt_cj_type(inst_filter: &InstructionFilter, inst_bits: InstructionBits) -> String {
-    let jump_immediate = inst_bits.get_cj_imm();
-    let operator = if jump_immediate.is_negative() {
-        '-'
-    } else {
-        '+'
-    };
-    let jump_immediate_pos = jump_immediate.abs();
-    format!(
-        "{:<width$} pc {} {}",
-        inst_filter.name,
-        operator,
-        jump_immediate_pos,
-        width = INSTRUCTION_NAME_WIDTH
+fn decode_cl_type_ld(
+    inst_filter: &InstructionFilter,
+    inst_bits: InstructionBits,
+) -> DecodedInstruction {
+    DecodedInstruction::new(
+        inst_filter,
+        inst_bits,
+        InstructionFormat::CL,
+        &[
+            Operand::IntReg(inst_bits.get_idx_c3_rd()),
+            Operand::Mem {
+                base: inst_bits.get_idx_c3_rs1(),
+                offset: Some(inst_bits.get_cl_ld_imm() as i64),
+            },
+        ],
     )
 }
 
-fn fmt_cr_type(inst_filter: &InstructionFilter, inst_bits: InstructionBits) -> String {
-    format!(
-        "{:<width$} {}, {}",
-        inst_filter.name,
-        inst_bits.get_x_c_rs1(),
-        inst_bits.get_x_c_rs2(),
-        width = INSTRUCTION_NAME_WIDTH
+fn decode_cl_type_flw(
+    inst_filter: &InstructionFilter,
+    inst_bits: InstructionBits,
+) -> DecodedInstruction {
+    DecodedInstruction::new(
+        inst_filter,
+        inst_bits,
+        InstructionFormat::CL,
+        &[
+            Operand::FpReg(inst_bits.get_idx_c3_rd()),
+            Operand::Mem {
+                base: inst_bits.get_idx_c3_rs1(),
+                offset: Some(inst_bits.get_cl_lw_imm() as i64),
+            },
+        ],
     )
 }
 
-fn fmt_cr_type_no_rs2(inst_filter: &InstructionFilter, inst_bits: InstructionBits) -> String {
-    format!(
-        "{:<width$} {}",
-        inst_filter.name,
-        inst_bits.get_x_c_rs1(),
-        width = INSTRUCTION_NAME_WIDTH
+fn decode_cl_type_fld(
+    inst_filter: &InstructionFilter,
+    inst_bits: InstructionBits,
+) -> DecodedInstruction {
+    DecodedInstruction::new(
+        inst_filter,
+        inst_bits,
+        InstructionFormat::CL,
+        &[
+            Operand::FpReg(inst_bits.get_idx_c3_rd()),
+            Operand::Mem {
+                base: inst_bits.get_idx_c3_rs1(),
+                offset: Some(inst_bits.get_cl_ld_imm() as i64),
+            },
+        ],
     )
 }
 
-fn fmt_cb_type(inst_filter: &InstructionFilter, inst_bits: InstructionBits) -> String {
-    let branch_immediate = inst_bits.get_cb_imm();
-    let operator = if branch_immediate.is_negative() {
-        '-'
-    } else {
-        '+'
-    };
-    let branch_immediate_pos = branch_immediate.abs();
-    format!(
-        "{:<width$} {}, pc {} {}",
-        inst_filter.name,
-        inst_bits.get_x_c3_rs1(),
-        operator,
-        branch_immediate_pos,
-        width = INSTRUCTION_NAME_WIDTH
+fn decode_cs_type_sw(
+    inst_filter: &InstructionFilter,
+    inst_bits: InstructionBits,
+) -> DecodedInstruction {
+    DecodedInstruction::new(
+        inst_filter,
+        inst_bits,
+        InstructionFormat::CS,
+        &[
+            Operand::IntReg(inst_bits.get_idx_c3_rs2()),
+            Operand::Mem {
+                base: inst_bits.get_idx_c3_rs1(),
+                offset: Some(inst_bits.get_cl_lw_imm() as i64),
+            },
+        ],
     )
 }
 
-fn fmt_cb_type_shift(inst_filter: &InstructionFilter, inst_bits: InstructionBits) -> String {
-    format!(
-        "{:<width$} {}, {}",
-        inst_filter.name,
-        inst_bits.get_x_c3_rs1(),
-        inst_bits.get_c_shamt(),
-        width = INSTRUCTION_NAME_WIDTH
+fn decode_cs_type_sd(
+    inst_filter: &InstructionFilter,
+    inst_bits: InstructionBits,
+) -> DecodedInstruction {
+    DecodedInstruction::new(
+        inst_filter,
+        inst_bits,
+        InstructionFormat::CS,
+        &[
+            Operand::IntReg(inst_bits.get_idx_c3_rs2()),
+            Operand::Mem {
+                base: inst_bits.get_idx_c3_rs1(),
+                offset: Some(inst_bits.get_cl_ld_imm() as i64),
+            },
+        ],
     )
 }
 
-fn fmt_cb_type_andi(inst_filter: &InstructionFilter, inst_bits: InstructionBits) -> String {
-    format!(
-        "{:<width$} {}, {}",
-        inst_filter.name,
-        inst_bits.get_x_c3_rs1(),
-        inst_bits.get_ci_imm(),
-        width = INSTRUCTION_NAME_WIDTH
+fn decode_cs_type_fsw(
+    inst_filter: &InstructionFilter,
+    inst_bits: InstructionBits,
+) -> DecodedInstruction {
+    DecodedInstruction::new(
+        inst_filter,
+        inst_bits,
+        InstructionFormat::CS,
+        &[
+            Operand::FpReg(inst_bits.get_idx_c3_rs2()),
+            Operand::Mem {
+                base: inst_bits.get_idx_c3_rs1(),
+                offset: Some(inst_bits.get_cl_lw_imm() as i64),
+            },
+        ],
     )
 }
 
-fn fmt_ci_type(inst_filter: &InstructionFilter, inst_bits: InstructionBits) -> String {
-    format!(
-        "{:<width$} {}, {}",
-        inst_filter.name,
-        inst_bits.get_x_c_rs1(), // rd and rs1 overlap in compressed instructions
-        inst_bits.get_ci_imm(),
-        width = INSTRUCTION_NAME_WIDTH
+fn decode_cs_type_fsd(
+    inst_filter: &InstructionFilter,
+    inst_bits: InstructionBits,
+) -> DecodedInstruction {
+    DecodedInstruction::new(
+        inst_filter,
+        inst_bits,
+        InstructionFormat::CS,
+        &[
+            Operand::FpReg(inst_bits.get_idx_c3_rs2()),
+            Operand::Mem {
+                base: inst_bits.get_idx_c3_rs1(),
+                offset: Some(inst_bits.get_cl_ld_imm() as i64),
+            },
+        ],
     )
 }
 
-fn fmt_ci_type_lui(inst_filter: &InstructionFilter, inst_bits: InstructionBits) -> String {
+fn decode_cj_type(
+    inst_filter: &InstructionFilter,
+    inst_bits: InstructionBits,
+) -> DecodedInstruction {
+    DecodedInstruction::new(
+        inst_filter,
+        inst_bits,
+        InstructionFormat::CJ,
+        &[Operand::PcRel(inst_bits.get_cj_imm() as i64)],
+    )
+}
+
+fn decode_cr_type(
+    inst_filter: &InstructionFilter,
+    inst_bits: InstructionBits,
+) -> DecodedInstruction {
+    DecodedInstruction::new(
+        inst_filter,
+        inst_bits,
+        InstructionFormat::CR,
+        &[
+            Operand::IntReg(inst_bits.get_idx_c_rs1()),
+            Operand::IntReg(inst_bits.get_idx_c_rs2()),
+        ],
+    )
+}
+
+fn decode_cr_type_no_rs2(
+    inst_filter: &InstructionFilter,
+    inst_bits: InstructionBits,
+) -> DecodedInstruction {
+    DecodedInstruction::new(
+        inst_filter,
+        inst_bits,
+        InstructionFormat::CR,
+        &[Operand::IntReg(inst_bits.get_idx_c_rs1())],
+    )
+}
+
+fn decode_cr_type_no_args(
+    inst_filter: &InstructionFilter,
+    inst_bits: InstructionBits,
+) -> DecodedInstruction {
+    DecodedInstruction::new(inst_filter, inst_bits, InstructionFormat::CR, &[])
+}
+
+fn decode_cb_type(
+    inst_filter: &InstructionFilter,
+    inst_bits: InstructionBits,
+) -> DecodedInstruction {
+    DecodedInstruction::new(
+        inst_filter,
+        inst_bits,
+        InstructionFormat::CB,
+        &[
+            Operand::IntReg(inst_bits.get_idx_c3_rs1()),
+            Operand::PcRel(inst_bits.get_cb_imm() as i64),
+        ],
+    )
+}
+
+fn decode_cb_type_shift(
+    inst_filter: &InstructionFilter,
+    inst_bits: InstructionBits,
+) -> DecodedInstruction {
+    DecodedInstruction::new(
+        inst_filter,
+        inst_bits,
+        InstructionFormat::CB,
+        &[
+            Operand::IntReg(inst_bits.get_idx_c3_rs1()),
+            Operand::Imm(inst_bits.get_c_shamt() as i64),
+        ],
+    )
+}
+
+fn decode_cb_type_andi(
+    inst_filter: &InstructionFilter,
+    inst_bits: InstructionBits,
+) -> DecodedInstruction {
+    DecodedInstruction::new(
+        inst_filter,
+        inst_bits,
+        InstructionFormat::CB,
+        &[
+            Operand::IntReg(inst_bits.get_idx_c3_rs1()),
+            Operand::Imm(inst_bits.get_ci_imm() as i64),
+        ],
+    )
+}
+
+fn decode_ci_type(
+    inst_filter: &InstructionFilter,
+    inst_bits: InstructionBits,
+) -> DecodedInstruction {
+    DecodedInstruction::new(
+        inst_filter,
+        inst_bits,
+        InstructionFormat::CI,
+        &[
+            // rd and rs1 overlap in compressed instructions
+            Operand::IntReg(inst_bits.get_idx_c_rs1()),
+            Operand::Imm(inst_bits.get_ci_imm() as i64),
+        ],
+    )
+}
+
+fn decode_ci_type_no_args(
+    inst_filter: &InstructionFilter,
+    inst_bits: InstructionBits,
+) -> DecodedInstruction {
+    DecodedInstruction::new(inst_filter, inst_bits, InstructionFormat::CI, &[])
+}
+
+fn decode_ci_type_lui(
+    inst_filter: &InstructionFilter,
+    inst_bits: InstructionBits,
+) -> DecodedInstruction {
     // Mask off the 12 most-significant bits. The `c.lui` instruction takes the 6-bit immediate
     // field `ci_imm`, sign-extends it, and left-shifts it by 12. The disassembly doesn't need to
     // bother showing those 12 trailing zeros, so we just show the 20-bit sign-extended immediate
     // which will be placed in bits 31:12 of `rd`.
     let ci_uimm = (inst_bits.get_ci_imm() as u32) << 12 >> 12;
-    format!(
-        "{:<width$} {}, {:#x}",
-        inst_filter.name,
-        inst_bits.get_x_c_rs1(), // rd and rs1 overlap in compressed instructions
-        ci_uimm,
-        width = INSTRUCTION_NAME_WIDTH
+    DecodedInstruction::new(
+        inst_filter,
+        inst_bits,
+        InstructionFormat::CI,
+        &[
+            // rd and rs1 overlap in compressed instructions
+            Operand::IntReg(inst_bits.get_idx_c_rs1()),
+            Operand::UpperImm(ci_uimm),
+        ],
+    )
+}
+
+fn decode_ci_type_addi16sp(
+    inst_filter: &InstructionFilter,
+    inst_bits: InstructionBits,
+) -> DecodedInstruction {
+    DecodedInstruction::new(
+        inst_filter,
+        inst_bits,
+        InstructionFormat::CI,
+        &[
+            Operand::IntReg(2),
+            Operand::Imm(inst_bits.get_ci_addi16sp_imm() as i64),
+        ],
+    )
+}
+
+fn decode_ciw_type_addi4spn(
+    inst_filter: &InstructionFilter,
+    inst_bits: InstructionBits,
+) -> DecodedInstruction {
+    DecodedInstruction::new(
+        inst_filter,
+        inst_bits,
+        InstructionFormat::CIW,
+        &[
+            Operand::IntReg(inst_bits.get_idx_c3_rd()),
+            Operand::IntReg(2),
+            Operand::Imm(inst_bits.get_ciw_addi4spn_imm() as i64),
+        ],
+    )
+}
+
+fn decode_ci_type_shift(
+    inst_filter: &InstructionFilter,
+    inst_bits: InstructionBits,
+) -> DecodedInstruction {
+    DecodedInstruction::new(
+        inst_filter,
+        inst_bits,
+        InstructionFormat::CI,
+        &[
+            // rd and rs1 overlap in compressed instructions
+            Operand::IntReg(inst_bits.get_idx_c_rs1()),
+            Operand::Imm(inst_bits.get_c_shamt() as i64),
+        ],
+    )
+}
+
+fn decode_ca_type(
+    inst_filter: &InstructionFilter,
+    inst_bits: InstructionBits,
+) -> DecodedInstruction {
+    DecodedInstruction::new(
+        inst_filter,
+        inst_bits,
+        InstructionFormat::CA,
+        &[
+            Operand::IntReg(inst_bits.get_idx_c3_rs1()),
+            Operand::IntReg(inst_bits.get_idx_c3_rd()),
+        ],
+    )
+}
+
+fn decode_vsetvli(
+    inst_filter: &InstructionFilter,
+    inst_bits: InstructionBits,
+) -> DecodedInstruction {
+    DecodedInstruction::new(
+        inst_filter,
+        inst_bits,
+        InstructionFormat::VSet,
+        &[
+            Operand::IntReg(inst_bits.get_idx_rd()),
+            Operand::IntReg(inst_bits.get_idx_rs1()),
+            Operand::VType(inst_bits.get_v_zimm11()),
+        ],
+    )
+}
+
+fn decode_vsetivli(
+    inst_filter: &InstructionFilter,
+    inst_bits: InstructionBits,
+) -> DecodedInstruction {
+    DecodedInstruction::new(
+        inst_filter,
+        inst_bits,
+        InstructionFormat::VSet,
+        &[
+            Operand::IntReg(inst_bits.get_idx_rd()),
+            Operand::Imm(inst_bits.get_uimm5() as i64),
+            Operand::VType(inst_bits.get_v_zimm10()),
+        ],
+    )
+}
+
+fn decode_vsetvl(
+    inst_filter: &InstructionFilter,
+    inst_bits: InstructionBits,
+) -> DecodedInstruction {
+    DecodedInstruction::new(
+        inst_filter,
+        inst_bits,
+        InstructionFormat::VSet,
+        &[
+            Operand::IntReg(inst_bits.get_idx_rd()),
+            Operand::IntReg(inst_bits.get_idx_rs1()),
+            Operand::IntReg(inst_bits.get_idx_rs2()),
+        ],
+    )
+}
+
+fn decode_v_mem_unit(
+    inst_filter: &InstructionFilter,
+    inst_bits: InstructionBits,
+) -> DecodedInstruction {
+    let mut decoded = DecodedInstruction::new(
+        inst_filter,
+        inst_bits,
+        v_mem_format(inst_bits),
+        &[
+            Operand::VecReg(inst_bits.get_idx_rd()),
+            Operand::Mem {
+                base: inst_bits.get_idx_rs1(),
+                offset: None,
+            },
+        ],
+    );
+    decoded.mnemonic.segment_fields = inst_bits.get_v_nf() as u8 + 1;
+    push_v_mask(&mut decoded, inst_bits);
+    decoded
+}
+
+fn decode_v_mem_strided(
+    inst_filter: &InstructionFilter,
+    inst_bits: InstructionBits,
+) -> DecodedInstruction {
+    let mut decoded = DecodedInstruction::new(
+        inst_filter,
+        inst_bits,
+        v_mem_format(inst_bits),
+        &[
+            Operand::VecReg(inst_bits.get_idx_rd()),
+            Operand::Mem {
+                base: inst_bits.get_idx_rs1(),
+                offset: None,
+            },
+            Operand::IntReg(inst_bits.get_idx_rs2()),
+        ],
+    );
+    decoded.mnemonic.segment_fields = inst_bits.get_v_nf() as u8 + 1;
+    push_v_mask(&mut decoded, inst_bits);
+    decoded
+}
+
+fn decode_v_mem_indexed(
+    inst_filter: &InstructionFilter,
+    inst_bits: InstructionBits,
+) -> DecodedInstruction {
+    let mut decoded = DecodedInstruction::new(
+        inst_filter,
+        inst_bits,
+        v_mem_format(inst_bits),
+        &[
+            Operand::VecReg(inst_bits.get_idx_rd()),
+            Operand::Mem {
+                base: inst_bits.get_idx_rs1(),
+                offset: None,
+            },
+            Operand::VecReg(inst_bits.get_idx_rs2()),
+        ],
+    );
+    decoded.mnemonic.segment_fields = inst_bits.get_v_nf() as u8 + 1;
+    push_v_mask(&mut decoded, inst_bits);
+    decoded
+}
+
+fn decode_v_mem_whole(
+    inst_filter: &InstructionFilter,
+    inst_bits: InstructionBits,
+) -> DecodedInstruction {
+    DecodedInstruction::new(
+        inst_filter,
+        inst_bits,
+        v_mem_format(inst_bits),
+        &[
+            Operand::VecReg(inst_bits.get_idx_rd()),
+            Operand::Mem {
+                base: inst_bits.get_idx_rs1(),
+                offset: None,
+            },
+        ],
+    )
+}
+
+fn decode_v_vv(inst_filter: &InstructionFilter, inst_bits: InstructionBits) -> DecodedInstruction {
+    let mut decoded = DecodedInstruction::new(
+        inst_filter,
+        inst_bits,
+        InstructionFormat::V,
+        &[
+            Operand::VecReg(inst_bits.get_idx_rd()),
+            Operand::VecReg(inst_bits.get_idx_rs2()),
+            Operand::VecReg(inst_bits.get_idx_rs1()),
+        ],
+    );
+    push_v_mask(&mut decoded, inst_bits);
+    decoded
+}
+
+fn decode_v_vx(inst_filter: &InstructionFilter, inst_bits: InstructionBits) -> DecodedInstruction {
+    let mut decoded = DecodedInstruction::new(
+        inst_filter,
+        inst_bits,
+        InstructionFormat::V,
+        &[
+            Operand::VecReg(inst_bits.get_idx_rd()),
+            Operand::VecReg(inst_bits.get_idx_rs2()),
+            Operand::IntReg(inst_bits.get_idx_rs1()),
+        ],
+    );
+    push_v_mask(&mut decoded, inst_bits);
+    decoded
+}
+
+fn decode_v_vf(inst_filter: &InstructionFilter, inst_bits: InstructionBits) -> DecodedInstruction {
+    let mut decoded = DecodedInstruction::new(
+        inst_filter,
+        inst_bits,
+        InstructionFormat::V,
+        &[
+            Operand::VecReg(inst_bits.get_idx_rd()),
+            Operand::VecReg(inst_bits.get_idx_rs2()),
+            Operand::FpReg(inst_bits.get_idx_rs1()),
+        ],
+    );
+    push_v_mask(&mut decoded, inst_bits);
+    decoded
+}
+
+fn decode_v_vi(inst_filter: &InstructionFilter, inst_bits: InstructionBits) -> DecodedInstruction {
+    let mut decoded = DecodedInstruction::new(
+        inst_filter,
+        inst_bits,
+        InstructionFormat::V,
+        &[
+            Operand::VecReg(inst_bits.get_idx_rd()),
+            Operand::VecReg(inst_bits.get_idx_rs2()),
+            Operand::Imm(inst_bits.get_v_simm5() as i64),
+        ],
+    );
+    push_v_mask(&mut decoded, inst_bits);
+    decoded
+}
+
+fn decode_v_vi_unsigned(
+    inst_filter: &InstructionFilter,
+    inst_bits: InstructionBits,
+) -> DecodedInstruction {
+    let mut decoded = DecodedInstruction::new(
+        inst_filter,
+        inst_bits,
+        InstructionFormat::V,
+        &[
+            Operand::VecReg(inst_bits.get_idx_rd()),
+            Operand::VecReg(inst_bits.get_idx_rs2()),
+            Operand::Imm(inst_bits.get_uimm5() as i64),
+        ],
+    );
+    push_v_mask(&mut decoded, inst_bits);
+    decoded
+}
+
+fn decode_v_vvm(inst_filter: &InstructionFilter, inst_bits: InstructionBits) -> DecodedInstruction {
+    DecodedInstruction::new(
+        inst_filter,
+        inst_bits,
+        InstructionFormat::V,
+        &[
+            Operand::VecReg(inst_bits.get_idx_rd()),
+            Operand::VecReg(inst_bits.get_idx_rs2()),
+            Operand::VecReg(inst_bits.get_idx_rs1()),
+            Operand::VecReg(0),
+        ],
+    )
+}
+
+fn decode_v_vxm(inst_filter: &InstructionFilter, inst_bits: InstructionBits) -> DecodedInstruction {
+    DecodedInstruction::new(
+        inst_filter,
+        inst_bits,
+        InstructionFormat::V,
+        &[
+            Operand::VecReg(inst_bits.get_idx_rd()),
+            Operand::VecReg(inst_bits.get_idx_rs2()),
+            Operand::IntReg(inst_bits.get_idx_rs1()),
+            Operand::VecReg(0),
+        ],
+    )
+}
+
+fn decode_v_vfm(inst_filter: &InstructionFilter, inst_bits: InstructionBits) -> DecodedInstruction {
+    DecodedInstruction::new(
+        inst_filter,
+        inst_bits,
+        InstructionFormat::V,
+        &[
+            Operand::VecReg(inst_bits.get_idx_rd()),
+            Operand::VecReg(inst_bits.get_idx_rs2()),
+            Operand::FpReg(inst_bits.get_idx_rs1()),
+            Operand::VecReg(0),
+        ],
+    )
+}
+
+fn decode_v_vim(inst_filter: &InstructionFilter, inst_bits: InstructionBits) -> DecodedInstruction {
+    DecodedInstruction::new(
+        inst_filter,
+        inst_bits,
+        InstructionFormat::V,
+        &[
+            Operand::VecReg(inst_bits.get_idx_rd()),
+            Operand::VecReg(inst_bits.get_idx_rs2()),
+            Operand::Imm(inst_bits.get_v_simm5() as i64),
+            Operand::VecReg(0),
+        ],
+    )
+}
+
+fn decode_v_vv_madd(
+    inst_filter: &InstructionFilter,
+    inst_bits: InstructionBits,
+) -> DecodedInstruction {
+    let mut decoded = DecodedInstruction::new(
+        inst_filter,
+        inst_bits,
+        InstructionFormat::V,
+        &[
+            Operand::VecReg(inst_bits.get_idx_rd()),
+            Operand::VecReg(inst_bits.get_idx_rs1()),
+            Operand::VecReg(inst_bits.get_idx_rs2()),
+        ],
+    );
+    push_v_mask(&mut decoded, inst_bits);
+    decoded
+}
+
+fn decode_v_vx_madd(
+    inst_filter: &InstructionFilter,
+    inst_bits: InstructionBits,
+) -> DecodedInstruction {
+    let mut decoded = DecodedInstruction::new(
+        inst_filter,
+        inst_bits,
+        InstructionFormat::V,
+        &[
+            Operand::VecReg(inst_bits.get_idx_rd()),
+            Operand::IntReg(inst_bits.get_idx_rs1()),
+            Operand::VecReg(inst_bits.get_idx_rs2()),
+        ],
+    );
+    push_v_mask(&mut decoded, inst_bits);
+    decoded
+}
+
+fn decode_v_vf_madd(
+    inst_filter: &InstructionFilter,
+    inst_bits: InstructionBits,
+) -> DecodedInstruction {
+    let mut decoded = DecodedInstruction::new(
+        inst_filter,
+        inst_bits,
+        InstructionFormat::V,
+        &[
+            Operand::VecReg(inst_bits.get_idx_rd()),
+            Operand::FpReg(inst_bits.get_idx_rs1()),
+            Operand::VecReg(inst_bits.get_idx_rs2()),
+        ],
+    );
+    push_v_mask(&mut decoded, inst_bits);
+    decoded
+}
+
+fn decode_v_unary(
+    inst_filter: &InstructionFilter,
+    inst_bits: InstructionBits,
+) -> DecodedInstruction {
+    let mut decoded = DecodedInstruction::new(
+        inst_filter,
+        inst_bits,
+        InstructionFormat::V,
+        &[
+            Operand::VecReg(inst_bits.get_idx_rd()),
+            Operand::VecReg(inst_bits.get_idx_rs2()),
+        ],
+    );
+    push_v_mask(&mut decoded, inst_bits);
+    decoded
+}
+
+fn decode_v_unary_no_mask(
+    inst_filter: &InstructionFilter,
+    inst_bits: InstructionBits,
+) -> DecodedInstruction {
+    DecodedInstruction::new(
+        inst_filter,
+        inst_bits,
+        InstructionFormat::V,
+        &[
+            Operand::VecReg(inst_bits.get_idx_rd()),
+            Operand::VecReg(inst_bits.get_idx_rs2()),
+        ],
+    )
+}
+
+fn decode_v_mm(inst_filter: &InstructionFilter, inst_bits: InstructionBits) -> DecodedInstruction {
+    DecodedInstruction::new(
+        inst_filter,
+        inst_bits,
+        InstructionFormat::V,
+        &[
+            Operand::VecReg(inst_bits.get_idx_rd()),
+            Operand::VecReg(inst_bits.get_idx_rs2()),
+            Operand::VecReg(inst_bits.get_idx_rs1()),
+        ],
+    )
+}
+
+fn decode_v_vd_only(
+    inst_filter: &InstructionFilter,
+    inst_bits: InstructionBits,
+) -> DecodedInstruction {
+    let mut decoded = DecodedInstruction::new(
+        inst_filter,
+        inst_bits,
+        InstructionFormat::V,
+        &[Operand::VecReg(inst_bits.get_idx_rd())],
+    );
+    push_v_mask(&mut decoded, inst_bits);
+    decoded
+}
+
+fn decode_v_x_rd(
+    inst_filter: &InstructionFilter,
+    inst_bits: InstructionBits,
+) -> DecodedInstruction {
+    let mut decoded = DecodedInstruction::new(
+        inst_filter,
+        inst_bits,
+        InstructionFormat::V,
+        &[
+            Operand::IntReg(inst_bits.get_idx_rd()),
+            Operand::VecReg(inst_bits.get_idx_rs2()),
+        ],
+    );
+    push_v_mask(&mut decoded, inst_bits);
+    decoded
+}
+
+fn decode_v_f_rd(
+    inst_filter: &InstructionFilter,
+    inst_bits: InstructionBits,
+) -> DecodedInstruction {
+    DecodedInstruction::new(
+        inst_filter,
+        inst_bits,
+        InstructionFormat::V,
+        &[
+            Operand::FpReg(inst_bits.get_idx_rd()),
+            Operand::VecReg(inst_bits.get_idx_rs2()),
+        ],
     )
 }
 
-fn fmt_ci_type_addi16sp(inst_filter: &InstructionFilter, inst_bits: InstructionBits) -> String {
-    format!(
-        "{:<width$} sp, {}",
-        inst_filter.name,
-        inst_bits.get_ci_addi16sp_imm(),
-        width = INSTRUCTION_NAME_WIDTH
+fn decode_v_mv_v(
+    inst_filter: &InstructionFilter,
+    inst_bits: InstructionBits,
+) -> DecodedInstruction {
+    DecodedInstruction::new(
+        inst_filter,
+        inst_bits,
+        InstructionFormat::V,
+        &[
+            Operand::VecReg(inst_bits.get_idx_rd()),
+            Operand::VecReg(inst_bits.get_idx_rs1()),
+        ],
     )
 }
 
-fn fmt_ciw_type_addi4spn(inst_filter: &InstructionFilter, inst_bits: InstructionBits) -> String {
-    format!(
-        "{:<width$} {}, sp, {}",
-        inst_filter.name,
-        inst_bits.get_x_c3_rd(),
-        inst_bits.get_ciw_addi4spn_imm(),
-        width = INSTRUCTION_NAME_WIDTH
+fn decode_v_mv_x(
+    inst_filter: &InstructionFilter,
+    inst_bits: InstructionBits,
+) -> DecodedInstruction {
+    DecodedInstruction::new(
+        inst_filter,
+        inst_bits,
+        InstructionFormat::V,
+        &[
+            Operand::VecReg(inst_bits.get_idx_rd()),
+            Operand::IntReg(inst_bits.get_idx_rs1()),
+        ],
     )
 }
 
-fn fmt_ci_type_shift(inst_filter: &InstructionFilter, inst_bits: InstructionBits) -> String {
-    format!(
-        "{:<width$} {}, {}",
-        inst_filter.name,
-        inst_bits.get_x_c_rs1(), // rd and rs1 overlap in compressed instructions
-        inst_bits.get_c_shamt(),
-        width = INSTRUCTION_NAME_WIDTH
+fn decode_v_mv_f(
+    inst_filter: &InstructionFilter,
+    inst_bits: InstructionBits,
+) -> DecodedInstruction {
+    DecodedInstruction::new(
+        inst_filter,
+        inst_bits,
+        InstructionFormat::V,
+        &[
+            Operand::VecReg(inst_bits.get_idx_rd()),
+            Operand::FpReg(inst_bits.get_idx_rs1()),
+        ],
     )
 }
 
-fn fmt_ca_type(inst_filter: &InstructionFilter, inst_bits: InstructionBits) -> String {
-    format!(
-        "{:<width$} {}, {}",
-        inst_filter.name,
-        inst_bits.get_x_c3_rs1(),
-        inst_bits.get_x_c3_rd(),
-        width = INSTRUCTION_NAME_WIDTH
+fn decode_v_mv_i(
+    inst_filter: &InstructionFilter,
+    inst_bits: InstructionBits,
+) -> DecodedInstruction {
+    DecodedInstruction::new(
+        inst_filter,
+        inst_bits,
+        InstructionFormat::V,
+        &[
+            Operand::VecReg(inst_bits.get_idx_rd()),
+            Operand::Imm(inst_bits.get_v_simm5() as i64),
+        ],
     )
 }
 
@@ -996,57 +1891,51 @@ pub fn gen_instructions(
                     | registers::MASK_RS1
                     | registers::MASK_I_TYPE_IMM,
                 inst::MATCH_ADDI,
-                fmt_no_args,
+                decode_no_args,
             ),
             InstructionFilter::new(
                 "li",
                 inst::MASK_ADDI | registers::MASK_RS1,
                 inst::MATCH_ADDI,
-                fmt_i_type_no_rs1,
+                decode_i_type_no_rs1,
             ),
             InstructionFilter::new(
                 "mv",
                 inst::MASK_ADDI | registers::MASK_I_TYPE_IMM,
                 inst::MATCH_ADDI,
-                fmt_i_type_no_imm,
+                decode_i_type_no_imm,
             ),
             InstructionFilter::new(
                 "not",
                 inst::MASK_XORI | registers::MASK_I_TYPE_IMM,
                 inst::MATCH_XORI | registers::MATCH_I_TYPE_IMM_EQUALS_NEG1,
-                fmt_i_type_no_imm,
-            ),
-            InstructionFilter::new(
-                "sext.w",
-                inst::MASK_ADDIW | registers::MASK_I_TYPE_IMM,
-                inst::MATCH_ADDIW,
-                fmt_i_type_no_imm,
+                decode_i_type_no_imm,
             ),
             InstructionFilter::new(
                 "seqz",
                 inst::MASK_SLTIU | registers::MASK_I_TYPE_IMM,
                 inst::MATCH_SLTIU | registers::MATCH_I_TYPE_IMM_EQUALS_1,
-                fmt_i_type_no_imm,
+                decode_i_type_no_imm,
             ),
             // Register-register
             InstructionFilter::new(
                 "snez",
                 inst::MASK_SLTU | registers::MASK_RS1,
                 inst::MATCH_SLTU,
-                fmt_r_type_no_rs1,
+                decode_r_type_no_rs1,
             ),
             // Jumps
             InstructionFilter::new(
                 "j",
                 inst::MASK_JAL | registers::MASK_RD,
                 inst::MATCH_JAL,
-                fmt_j_type_no_rd,
+                decode_j_type_no_rd,
             ),
             InstructionFilter::new(
                 "jal",
                 inst::MASK_JAL | registers::MASK_RD,
                 inst::MATCH_JAL | registers::MATCH_RD_EQUALS_RA,
-                fmt_j_type_no_rd,
+                decode_j_type_no_rd,
             ),
             InstructionFilter::new(
                 "ret",
@@ -1055,948 +1944,3974 @@ pub fn gen_instructions(
                     | registers::MASK_RS1
                     | registers::MASK_I_TYPE_IMM,
                 inst::MATCH_JALR | registers::MATCH_RS1_EQUALS_RA,
-                fmt_no_args,
+                decode_no_args,
             ),
             InstructionFilter::new(
                 "jr",
                 inst::MASK_JALR | registers::MASK_RD | registers::MASK_I_TYPE_IMM,
                 inst::MATCH_JALR,
-                fmt_i_type_just_rs1,
+                decode_i_type_just_rs1,
             ),
             InstructionFilter::new(
                 "jalr",
                 inst::MASK_JALR | registers::MASK_RD | registers::MASK_I_TYPE_IMM,
                 inst::MATCH_JALR | registers::MATCH_RD_EQUALS_RA,
-                fmt_i_type_just_rs1,
+                decode_i_type_just_rs1,
             ),
             // Branches
             InstructionFilter::new(
                 "beqz",
                 inst::MASK_BEQ | registers::MASK_RS2,
                 inst::MATCH_BEQ,
-                fmt_b_type_no_rs2,
+                decode_b_type_no_rs2,
             ),
             InstructionFilter::new(
                 "bnez",
                 inst::MASK_BNE | registers::MASK_RS2,
                 inst::MATCH_BNE,
-                fmt_b_type_no_rs2,
+                decode_b_type_no_rs2,
             ),
             InstructionFilter::new(
                 "bltz",
                 inst::MASK_BLT | registers::MASK_RS2,
                 inst::MATCH_BLT,
-                fmt_b_type_no_rs2,
+                decode_b_type_no_rs2,
             ),
             InstructionFilter::new(
                 "bgez",
                 inst::MASK_BGE | registers::MASK_RS2,
                 inst::MATCH_BGE,
-                fmt_b_type_no_rs2,
-            ),
-            // Control and status registers, Zicsr extension
-            InstructionFilter::new(
-                "csrr",
-                inst::MASK_CSRRS | registers::MASK_RS1,
-                inst::MATCH_CSRRS,
-                fmt_csr_no_rs1,
-            ),
-            InstructionFilter::new(
-                "csrw",
-                inst::MASK_CSRRW | registers::MASK_RD,
-                inst::MATCH_CSRRW,
-                fmt_csr_no_rd,
-            ),
-            InstructionFilter::new(
-                "csrs",
-                inst::MASK_CSRRS | registers::MASK_RD,
-                inst::MATCH_CSRRS,
-                fmt_csr_no_rd,
-            ),
-            InstructionFilter::new(
-                "csrc",
-                inst::MASK_CSRRC | registers::MASK_RD,
-                inst::MATCH_CSRRC,
-                fmt_csr_no_rd,
-            ),
-            InstructionFilter::new(
-                "csrwi",
-                inst::MASK_CSRRWI | registers::MASK_RD,
-                inst::MATCH_CSRRWI,
-                fmt_csr_imm_no_rd,
-            ),
-            InstructionFilter::new(
-                "csrsi",
-                inst::MASK_CSRRSI | registers::MASK_RD,
-                inst::MATCH_CSRRSI,
-                fmt_csr_imm_no_rd,
-            ),
-            InstructionFilter::new(
-                "csrci",
-                inst::MASK_CSRRCI | registers::MASK_RD,
-                inst::MATCH_CSRRCI,
-                fmt_csr_imm_no_rd,
+                decode_b_type_no_rs2,
             ),
         ]
     } else {
         vec![]
     };
+    if enable_pseudo_instructions && xlen == Xlen::Rv64 {
+        i_pseudo_instruction_inst_filters.push(InstructionFilter::new(
+            "sext.w",
+            inst::MASK_ADDIW | registers::MASK_I_TYPE_IMM,
+            inst::MATCH_ADDIW,
+            decode_i_type_no_imm,
+        ));
+    }
 
     let i_inst_filters = {
         let mut xlen_filters = match xlen {
             Xlen::Rv32 => vec![],
             Xlen::Rv64 => vec![
-                InstructionFilter::new("addiw", inst::MASK_ADDIW, inst::MATCH_ADDIW, fmt_i_type),
+                InstructionFilter::new("addiw", inst::MASK_ADDIW, inst::MATCH_ADDIW, decode_i_type),
                 InstructionFilter::new(
                     "slliw",
                     inst::MASK_SLLIW,
                     inst::MATCH_SLLIW,
-                    fmt_i_type_shift,
+                    decode_i_type_shift,
                 ),
                 InstructionFilter::new(
                     "srliw",
                     inst::MASK_SRLIW,
                     inst::MATCH_SRLIW,
-                    fmt_i_type_shift,
+                    decode_i_type_shift,
                 ),
                 InstructionFilter::new(
                     "sraiw",
                     inst::MASK_SRAIW,
                     inst::MATCH_SRAIW,
-                    fmt_i_type_shift,
+                    decode_i_type_shift,
                 ),
-                InstructionFilter::new("addw", inst::MASK_ADDW, inst::MATCH_ADDW, fmt_r_type),
-                InstructionFilter::new("subw", inst::MASK_SUBW, inst::MATCH_SUBW, fmt_r_type),
-                InstructionFilter::new("sllw", inst::MASK_SLLW, inst::MATCH_SLLW, fmt_r_type),
-                InstructionFilter::new("srlw", inst::MASK_SRLW, inst::MATCH_SRLW, fmt_r_type),
-                InstructionFilter::new("sraw", inst::MASK_SRAW, inst::MATCH_SRAW, fmt_r_type),
-                InstructionFilter::new("lwu", inst::MASK_LWU, inst::MATCH_LWU, fmt_load),
-                InstructionFilter::new("ld", inst::MASK_LD, inst::MATCH_LD, fmt_load),
-                InstructionFilter::new("sd", inst::MASK_SD, inst::MATCH_SD, fmt_store),
+                InstructionFilter::new("addw", inst::MASK_ADDW, inst::MATCH_ADDW, decode_r_type),
+                InstructionFilter::new("subw", inst::MASK_SUBW, inst::MATCH_SUBW, decode_r_type),
+                InstructionFilter::new("sllw", inst::MASK_SLLW, inst::MATCH_SLLW, decode_r_type),
+                InstructionFilter::new("srlw", inst::MASK_SRLW, inst::MATCH_SRLW, decode_r_type),
+                InstructionFilter::new("sraw", inst::MASK_SRAW, inst::MATCH_SRAW, decode_r_type),
+                InstructionFilter::new("lwu", inst::MASK_LWU, inst::MATCH_LWU, decode_load),
+                InstructionFilter::new("ld", inst::MASK_LD, inst::MATCH_LD, decode_load),
+                InstructionFilter::new("sd", inst::MASK_SD, inst::MATCH_SD, decode_store),
             ],
         };
 
         let global_filters = vec![
             // Integer-immediate
-            InstructionFilter::new("addi", inst::MASK_ADDI, inst::MATCH_ADDI, fmt_i_type),
-            InstructionFilter::new("slti", inst::MASK_SLTI, inst::MATCH_SLTI, fmt_i_type),
-            InstructionFilter::new("sltiu", inst::MASK_SLTIU, inst::MATCH_SLTIU, fmt_i_type),
-            InstructionFilter::new("ori", inst::MASK_ORI, inst::MATCH_ORI, fmt_i_type),
-            InstructionFilter::new("xori", inst::MASK_XORI, inst::MATCH_XORI, fmt_i_type),
-            InstructionFilter::new("andi", inst::MASK_ANDI, inst::MATCH_ANDI, fmt_i_type),
+            InstructionFilter::new("addi", inst::MASK_ADDI, inst::MATCH_ADDI, decode_i_type),
+            InstructionFilter::new("slti", inst::MASK_SLTI, inst::MATCH_SLTI, decode_i_type),
+            InstructionFilter::new("sltiu", inst::MASK_SLTIU, inst::MATCH_SLTIU, decode_i_type),
+            InstructionFilter::new("ori", inst::MASK_ORI, inst::MATCH_ORI, decode_i_type),
+            InstructionFilter::new("xori", inst::MASK_XORI, inst::MATCH_XORI, decode_i_type),
+            InstructionFilter::new("andi", inst::MASK_ANDI, inst::MATCH_ANDI, decode_i_type),
             // Integer-immediate shift
             // Note that while there are RV32-specific encodings for SLLI, SRLI, and SRAI, the RV64
             // encodings match those instructions as well, since the only difference between those
             // different XLEN settings is the most-significant bit of the 'shamt' field always
             // being 0 on RV32.
-            InstructionFilter::new("slli", inst::MASK_SLLI, inst::MATCH_SLLI, fmt_i_type_shift),
-            InstructionFilter::new("srli", inst::MASK_SRLI, inst::MATCH_SRLI, fmt_i_type_shift),
-            InstructionFilter::new("srai", inst::MASK_SRAI, inst::MATCH_SRAI, fmt_i_type_shift),
+            InstructionFilter::new(
+                "slli",
+                inst::MASK_SLLI,
+                inst::MATCH_SLLI,
+                decode_i_type_shift,
+            ),
+            InstructionFilter::new(
+                "srli",
+                inst::MASK_SRLI,
+                inst::MATCH_SRLI,
+                decode_i_type_shift,
+            ),
+            InstructionFilter::new(
+                "srai",
+                inst::MASK_SRAI,
+                inst::MATCH_SRAI,
+                decode_i_type_shift,
+            ),
             // Upper-immediate
-            InstructionFilter::new("lui", inst::MASK_LUI, inst::MATCH_LUI, fmt_u_type),
-            InstructionFilter::new("auipc", inst::MASK_AUIPC, inst::MATCH_AUIPC, fmt_u_type),
+            InstructionFilter::new("lui", inst::MASK_LUI, inst::MATCH_LUI, decode_u_type),
+            InstructionFilter::new("auipc", inst::MASK_AUIPC, inst::MATCH_AUIPC, decode_u_type),
             // Register-register
-            InstructionFilter::new("add", inst::MASK_ADD, inst::MATCH_ADD, fmt_r_type),
-            InstructionFilter::new("slt", inst::MASK_SLT, inst::MATCH_SLT, fmt_r_type),
-            InstructionFilter::new("sltu", inst::MASK_SLTU, inst::MATCH_SLTU, fmt_r_type),
-            InstructionFilter::new("and", inst::MASK_AND, inst::MATCH_AND, fmt_r_type),
-            InstructionFilter::new("or", inst::MASK_OR, inst::MATCH_OR, fmt_r_type),
-            InstructionFilter::new("xor", inst::MASK_XOR, inst::MATCH_XOR, fmt_r_type),
-            InstructionFilter::new("sll", inst::MASK_SLL, inst::MATCH_SLL, fmt_r_type),
-            InstructionFilter::new("srl", inst::MASK_SRL, inst::MATCH_SRL, fmt_r_type),
-            InstructionFilter::new("sub", inst::MASK_SUB, inst::MATCH_SUB, fmt_r_type),
-            InstructionFilter::new("sra", inst::MASK_SRA, inst::MATCH_SRA, fmt_r_type),
+            InstructionFilter::new("add", inst::MASK_ADD, inst::MATCH_ADD, decode_r_type),
+            InstructionFilter::new("slt", inst::MASK_SLT, inst::MATCH_SLT, decode_r_type),
+            InstructionFilter::new("sltu", inst::MASK_SLTU, inst::MATCH_SLTU, decode_r_type),
+            InstructionFilter::new("and", inst::MASK_AND, inst::MATCH_AND, decode_r_type),
+            InstructionFilter::new("or", inst::MASK_OR, inst::MATCH_OR, decode_r_type),
+            InstructionFilter::new("xor", inst::MASK_XOR, inst::MATCH_XOR, decode_r_type),
+            InstructionFilter::new("sll", inst::MASK_SLL, inst::MATCH_SLL, decode_r_type),
+            InstructionFilter::new("srl", inst::MASK_SRL, inst::MATCH_SRL, decode_r_type),
+            InstructionFilter::new("sub", inst::MASK_SUB, inst::MATCH_SUB, decode_r_type),
+            InstructionFilter::new("sra", inst::MASK_SRA, inst::MATCH_SRA, decode_r_type),
             // Jumps
-            InstructionFilter::new("jal", inst::MASK_JAL, inst::MATCH_JAL, fmt_j_type),
-            InstructionFilter::new("jalr", inst::MASK_JALR, inst::MATCH_JALR, fmt_i_type),
+            InstructionFilter::new("jal", inst::MASK_JAL, inst::MATCH_JAL, decode_j_type),
+            InstructionFilter::new("jalr", inst::MASK_JALR, inst::MATCH_JALR, decode_i_type),
             // Branches
-            InstructionFilter::new("beq", inst::MASK_BEQ, inst::MATCH_BEQ, fmt_b_type),
-            InstructionFilter::new("bne", inst::MASK_BNE, inst::MATCH_BNE, fmt_b_type),
-            InstructionFilter::new("blt", inst::MASK_BLT, inst::MATCH_BLT, fmt_b_type),
-            InstructionFilter::new("bltu", inst::MASK_BLTU, inst::MATCH_BLTU, fmt_b_type),
-            InstructionFilter::new("bge", inst::MASK_BGE, inst::MATCH_BGE, fmt_b_type),
-            InstructionFilter::new("bgeu", inst::MASK_BGEU, inst::MATCH_BGEU, fmt_b_type),
+            InstructionFilter::new("beq", inst::MASK_BEQ, inst::MATCH_BEQ, decode_b_type),
+            InstructionFilter::new("bne", inst::MASK_BNE, inst::MATCH_BNE, decode_b_type),
+            InstructionFilter::new("blt", inst::MASK_BLT, inst::MATCH_BLT, decode_b_type),
+            InstructionFilter::new("bltu", inst::MASK_BLTU, inst::MATCH_BLTU, decode_b_type),
+            InstructionFilter::new("bge", inst::MASK_BGE, inst::MATCH_BGE, decode_b_type),
+            InstructionFilter::new("bgeu", inst::MASK_BGEU, inst::MATCH_BGEU, decode_b_type),
             // Loads
-            InstructionFilter::new("lb", inst::MASK_LB, inst::MATCH_LB, fmt_load),
-            InstructionFilter::new("lbu", inst::MASK_LBU, inst::MATCH_LBU, fmt_load),
-            InstructionFilter::new("lh", inst::MASK_LH, inst::MATCH_LH, fmt_load),
-            InstructionFilter::new("lhu", inst::MASK_LHU, inst::MATCH_LHU, fmt_load),
-            InstructionFilter::new("lw", inst::MASK_LW, inst::MATCH_LW, fmt_load),
+            InstructionFilter::new("lb", inst::MASK_LB, inst::MATCH_LB, decode_load),
+            InstructionFilter::new("lbu", inst::MASK_LBU, inst::MATCH_LBU, decode_load),
+            InstructionFilter::new("lh", inst::MASK_LH, inst::MATCH_LH, decode_load),
+            InstructionFilter::new("lhu", inst::MASK_LHU, inst::MATCH_LHU, decode_load),
+            InstructionFilter::new("lw", inst::MASK_LW, inst::MATCH_LW, decode_load),
             // Stores
-            InstructionFilter::new("sb", inst::MASK_SB, inst::MATCH_SB, fmt_store),
-            InstructionFilter::new("sh", inst::MASK_SH, inst::MATCH_SH, fmt_store),
-            InstructionFilter::new("sw", inst::MASK_SW, inst::MATCH_SW, fmt_store),
+            InstructionFilter::new("sb", inst::MASK_SB, inst::MATCH_SB, decode_store),
+            InstructionFilter::new("sh", inst::MASK_SH, inst::MATCH_SH, decode_store),
+            InstructionFilter::new("sw", inst::MASK_SW, inst::MATCH_SW, decode_store),
             // Fences
-            InstructionFilter::new("fence", inst::MASK_FENCE, inst::MATCH_FENCE, fmt_no_args),
-            // Zifencei extension
+            InstructionFilter::new("fence", inst::MASK_FENCE, inst::MATCH_FENCE, decode_no_args),
+            // Environment calls & breakpoints
+            InstructionFilter::new("ecall", inst::MASK_ECALL, inst::MATCH_ECALL, decode_no_args),
             InstructionFilter::new(
-                "fence.i",
-                inst::MASK_FENCE_I,
-                inst::MATCH_FENCE_I,
-                fmt_no_args,
+                "ebreak",
+                inst::MASK_EBREAK,
+                inst::MATCH_EBREAK,
+                decode_no_args,
             ),
-            // Environment calls & breakpoints
-            InstructionFilter::new("ecall", inst::MASK_ECALL, inst::MATCH_ECALL, fmt_no_args),
-            InstructionFilter::new("ebreak", inst::MASK_EBREAK, inst::MATCH_EBREAK, fmt_no_args),
             // Privileged instructions
-            InstructionFilter::new("wfi", inst::MASK_WFI, inst::MATCH_WFI, fmt_no_args),
-            InstructionFilter::new("mret", inst::MASK_MRET, inst::MATCH_MRET, fmt_no_args),
-            InstructionFilter::new("sret", inst::MASK_SRET, inst::MATCH_SRET, fmt_no_args),
-            InstructionFilter::new("uret", inst::MASK_URET, inst::MATCH_URET, fmt_no_args),
+            InstructionFilter::new("wfi", inst::MASK_WFI, inst::MATCH_WFI, decode_no_args),
+            InstructionFilter::new("mret", inst::MASK_MRET, inst::MATCH_MRET, decode_no_args),
+            InstructionFilter::new("sret", inst::MASK_SRET, inst::MATCH_SRET, decode_no_args),
+            InstructionFilter::new("uret", inst::MASK_URET, inst::MATCH_URET, decode_no_args),
             InstructionFilter::new(
                 "sfence.vma",
                 inst::MASK_SFENCE_VMA,
                 inst::MATCH_SFENCE_VMA,
-                fmt_rs1_rs2,
-            ),
-            // Control and status registers, Zicsr extension
-            InstructionFilter::new("csrrw", inst::MASK_CSRRW, inst::MATCH_CSRRW, fmt_csr),
-            InstructionFilter::new("csrrs", inst::MASK_CSRRS, inst::MATCH_CSRRS, fmt_csr),
-            InstructionFilter::new("csrrc", inst::MASK_CSRRC, inst::MATCH_CSRRC, fmt_csr),
-            InstructionFilter::new("csrrwi", inst::MASK_CSRRWI, inst::MATCH_CSRRWI, fmt_csr_imm),
-            InstructionFilter::new("csrrsi", inst::MASK_CSRRSI, inst::MATCH_CSRRSI, fmt_csr_imm),
-            InstructionFilter::new("csrrci", inst::MASK_CSRRCI, inst::MATCH_CSRRCI, fmt_csr_imm),
+                decode_rs1_rs2,
+            ),
         ];
 
         xlen_filters.extend(global_filters);
         xlen_filters
     };
 
-    // M extension, integer multiplication and division
-    let m_inst_filters = if isa_extensions.has_m() {
+    // Zicsr extension, control and status registers
+    let zicsr_inst_filters = if isa_extensions.has_zicsr() {
+        let mut pseudo_instruction_filters = if enable_pseudo_instructions {
+            vec![
+                InstructionFilter::new(
+                    "csrr",
+                    inst::MASK_CSRRS | registers::MASK_RS1,
+                    inst::MATCH_CSRRS,
+                    decode_csr_no_rs1,
+                ),
+                InstructionFilter::new(
+                    "csrw",
+                    inst::MASK_CSRRW | registers::MASK_RD,
+                    inst::MATCH_CSRRW,
+                    decode_csr_no_rd,
+                ),
+                InstructionFilter::new(
+                    "csrs",
+                    inst::MASK_CSRRS | registers::MASK_RD,
+                    inst::MATCH_CSRRS,
+                    decode_csr_no_rd,
+                ),
+                InstructionFilter::new(
+                    "csrc",
+                    inst::MASK_CSRRC | registers::MASK_RD,
+                    inst::MATCH_CSRRC,
+                    decode_csr_no_rd,
+                ),
+                InstructionFilter::new(
+                    "csrwi",
+                    inst::MASK_CSRRWI | registers::MASK_RD,
+                    inst::MATCH_CSRRWI,
+                    decode_csr_imm_no_rd,
+                ),
+                InstructionFilter::new(
+                    "csrsi",
+                    inst::MASK_CSRRSI | registers::MASK_RD,
+                    inst::MATCH_CSRRSI,
+                    decode_csr_imm_no_rd,
+                ),
+                InstructionFilter::new(
+                    "csrci",
+                    inst::MASK_CSRRCI | registers::MASK_RD,
+                    inst::MATCH_CSRRCI,
+                    decode_csr_imm_no_rd,
+                ),
+            ]
+        } else {
+            vec![]
+        };
+
+        pseudo_instruction_filters.extend(vec![
+            InstructionFilter::new("csrrw", inst::MASK_CSRRW, inst::MATCH_CSRRW, decode_csr),
+            InstructionFilter::new("csrrs", inst::MASK_CSRRS, inst::MATCH_CSRRS, decode_csr),
+            InstructionFilter::new("csrrc", inst::MASK_CSRRC, inst::MATCH_CSRRC, decode_csr),
+            InstructionFilter::new(
+                "csrrwi",
+                inst::MASK_CSRRWI,
+                inst::MATCH_CSRRWI,
+                decode_csr_imm,
+            ),
+            InstructionFilter::new(
+                "csrrsi",
+                inst::MASK_CSRRSI,
+                inst::MATCH_CSRRSI,
+                decode_csr_imm,
+            ),
+            InstructionFilter::new(
+                "csrrci",
+                inst::MASK_CSRRCI,
+                inst::MATCH_CSRRCI,
+                decode_csr_imm,
+            ),
+        ]);
+        pseudo_instruction_filters
+    } else {
+        vec![]
+    };
+
+    // Zifencei extension, instruction-fetch fence
+    let zifencei_inst_filters = if isa_extensions.has_zifencei() {
+        vec![InstructionFilter::new(
+            "fence.i",
+            inst::MASK_FENCE_I,
+            inst::MATCH_FENCE_I,
+            decode_no_args,
+        )]
+    } else {
+        vec![]
+    };
+
+    // M extension, integer multiplication and division. Zmmul has just the multiplications, which
+    // belong to it when M isn't enabled.
+    let mut mul_inst_filters = if isa_extensions.has_m() || isa_extensions.has_zmmul() {
         vec![
-            InstructionFilter::new("mul", inst::MASK_MUL, inst::MATCH_MUL, fmt_r_type),
-            InstructionFilter::new("mulh", inst::MASK_MULH, inst::MATCH_MULH, fmt_r_type),
-            InstructionFilter::new("mulhu", inst::MASK_MULHU, inst::MATCH_MULHU, fmt_r_type),
-            InstructionFilter::new("mulhsu", inst::MASK_MULHSU, inst::MATCH_MULHSU, fmt_r_type),
-            InstructionFilter::new("mulw", inst::MASK_MULW, inst::MATCH_MULW, fmt_r_type),
-            InstructionFilter::new("div", inst::MASK_DIV, inst::MATCH_DIV, fmt_r_type),
-            InstructionFilter::new("divu", inst::MASK_DIVU, inst::MATCH_DIVU, fmt_r_type),
-            InstructionFilter::new("rem", inst::MASK_REM, inst::MATCH_REM, fmt_r_type),
-            InstructionFilter::new("remu", inst::MASK_REMU, inst::MATCH_REMU, fmt_r_type),
-            InstructionFilter::new("divw", inst::MASK_DIVW, inst::MATCH_DIVW, fmt_r_type),
-            InstructionFilter::new("divuw", inst::MASK_DIVUW, inst::MATCH_DIVUW, fmt_r_type),
-            InstructionFilter::new("remw", inst::MASK_REMW, inst::MATCH_REMW, fmt_r_type),
-            InstructionFilter::new("remuw", inst::MASK_REMUW, inst::MATCH_REMUW, fmt_r_type),
+            InstructionFilter::new("mul", inst::MASK_MUL, inst::MATCH_MUL, decode_r_type),
+            InstructionFilter::new("mulh", inst::MASK_MULH, inst::MATCH_MULH, decode_r_type),
+            InstructionFilter::new("mulhu", inst::MASK_MULHU, inst::MATCH_MULHU, decode_r_type),
+            InstructionFilter::new(
+                "mulhsu",
+                inst::MASK_MULHSU,
+                inst::MATCH_MULHSU,
+                decode_r_type,
+            ),
         ]
     } else {
         vec![]
     };
+    if (isa_extensions.has_m() || isa_extensions.has_zmmul()) && xlen == Xlen::Rv64 {
+        mul_inst_filters.push(InstructionFilter::new(
+            "mulw",
+            inst::MASK_MULW,
+            inst::MATCH_MULW,
+            decode_r_type,
+        ));
+    }
+    let mul_extension = if isa_extensions.has_m() {
+        Extensions::M
+    } else {
+        Extensions::ZMMUL
+    };
+    let mut div_inst_filters = vec![];
+    if isa_extensions.has_m() {
+        div_inst_filters.extend(vec![
+            InstructionFilter::new("div", inst::MASK_DIV, inst::MATCH_DIV, decode_r_type),
+            InstructionFilter::new("divu", inst::MASK_DIVU, inst::MATCH_DIVU, decode_r_type),
+            InstructionFilter::new("rem", inst::MASK_REM, inst::MATCH_REM, decode_r_type),
+            InstructionFilter::new("remu", inst::MASK_REMU, inst::MATCH_REMU, decode_r_type),
+        ]);
+        if xlen == Xlen::Rv64 {
+            div_inst_filters.extend(vec![
+                InstructionFilter::new("divw", inst::MASK_DIVW, inst::MATCH_DIVW, decode_r_type),
+                InstructionFilter::new("divuw", inst::MASK_DIVUW, inst::MATCH_DIVUW, decode_r_type),
+                InstructionFilter::new("remw", inst::MASK_REMW, inst::MATCH_REMW, decode_r_type),
+                InstructionFilter::new("remuw", inst::MASK_REMUW, inst::MATCH_REMUW, decode_r_type),
+            ]);
+        }
+    }
 
     // A extension, atomic instructions
-    let a_inst_filters = if isa_extensions.has_a() {
+    let mut a_inst_filters = if isa_extensions.has_a() {
         vec![
-            InstructionFilter::new("lr.w", inst::MASK_LR_W, inst::MATCH_LR_W, fmt_amo_lr),
-            InstructionFilter::new("sc.w", inst::MASK_SC_W, inst::MATCH_SC_W, fmt_amo),
-            InstructionFilter::new("lr.d", inst::MASK_LR_D, inst::MATCH_LR_D, fmt_amo_lr),
-            InstructionFilter::new("sc.d", inst::MASK_SC_D, inst::MATCH_SC_D, fmt_amo),
+            InstructionFilter::new("lr.w", inst::MASK_LR_W, inst::MATCH_LR_W, decode_amo_lr),
+            InstructionFilter::new("sc.w", inst::MASK_SC_W, inst::MATCH_SC_W, decode_amo),
             InstructionFilter::new(
                 "amoswap.w",
                 inst::MASK_AMOSWAP_W,
                 inst::MATCH_AMOSWAP_W,
-                fmt_amo,
-            ),
-            InstructionFilter::new(
-                "amoswap.d",
-                inst::MASK_AMOSWAP_D,
-                inst::MATCH_AMOSWAP_D,
-                fmt_amo,
+                decode_amo,
             ),
             InstructionFilter::new(
                 "amoadd.w",
                 inst::MASK_AMOADD_W,
                 inst::MATCH_AMOADD_W,
-                fmt_amo,
-            ),
-            InstructionFilter::new(
-                "amoadd.d",
-                inst::MASK_AMOADD_D,
-                inst::MATCH_AMOADD_D,
-                fmt_amo,
+                decode_amo,
             ),
             InstructionFilter::new(
                 "amoand.w",
                 inst::MASK_AMOAND_W,
                 inst::MATCH_AMOAND_W,
-                fmt_amo,
+                decode_amo,
             ),
             InstructionFilter::new(
-                "amoand.d",
-                inst::MASK_AMOAND_D,
-                inst::MATCH_AMOAND_D,
-                fmt_amo,
+                "amoor.w",
+                inst::MASK_AMOOR_W,
+                inst::MATCH_AMOOR_W,
+                decode_amo,
             ),
-            InstructionFilter::new("amoor.w", inst::MASK_AMOOR_W, inst::MATCH_AMOOR_W, fmt_amo),
-            InstructionFilter::new("amoor.d", inst::MASK_AMOOR_D, inst::MATCH_AMOOR_D, fmt_amo),
             InstructionFilter::new(
                 "amoxor.w",
                 inst::MASK_AMOXOR_W,
                 inst::MATCH_AMOXOR_W,
-                fmt_amo,
-            ),
-            InstructionFilter::new(
-                "amoxor.d",
-                inst::MASK_AMOXOR_D,
-                inst::MATCH_AMOXOR_D,
-                fmt_amo,
+                decode_amo,
             ),
             InstructionFilter::new(
                 "amomax.w",
                 inst::MASK_AMOMAX_W,
                 inst::MATCH_AMOMAX_W,
-                fmt_amo,
-            ),
-            InstructionFilter::new(
-                "amomax.d",
-                inst::MASK_AMOMAX_D,
-                inst::MATCH_AMOMAX_D,
-                fmt_amo,
+                decode_amo,
             ),
             InstructionFilter::new(
                 "amomaxu.w",
                 inst::MASK_AMOMAXU_W,
                 inst::MATCH_AMOMAXU_W,
-                fmt_amo,
-            ),
-            InstructionFilter::new(
-                "amomaxu.d",
-                inst::MASK_AMOMAXU_D,
-                inst::MATCH_AMOMAXU_D,
-                fmt_amo,
+                decode_amo,
             ),
             InstructionFilter::new(
                 "amomin.w",
                 inst::MASK_AMOMIN_W,
                 inst::MATCH_AMOMIN_W,
-                fmt_amo,
-            ),
-            InstructionFilter::new(
-                "amomin.d",
-                inst::MASK_AMOMIN_D,
-                inst::MATCH_AMOMIN_D,
-                fmt_amo,
+                decode_amo,
             ),
             InstructionFilter::new(
                 "amominu.w",
                 inst::MASK_AMOMINU_W,
                 inst::MATCH_AMOMINU_W,
-                fmt_amo,
+                decode_amo,
+            ),
+        ]
+    } else {
+        vec![]
+    };
+    // Doubleword atomics, on RV64 only.
+    if isa_extensions.has_a() && xlen == Xlen::Rv64 {
+        a_inst_filters.extend(vec![
+            InstructionFilter::new("lr.d", inst::MASK_LR_D, inst::MATCH_LR_D, decode_amo_lr),
+            InstructionFilter::new("sc.d", inst::MASK_SC_D, inst::MATCH_SC_D, decode_amo),
+            InstructionFilter::new(
+                "amoswap.d",
+                inst::MASK_AMOSWAP_D,
+                inst::MATCH_AMOSWAP_D,
+                decode_amo,
+            ),
+            InstructionFilter::new(
+                "amoadd.d",
+                inst::MASK_AMOADD_D,
+                inst::MATCH_AMOADD_D,
+                decode_amo,
+            ),
+            InstructionFilter::new(
+                "amoand.d",
+                inst::MASK_AMOAND_D,
+                inst::MATCH_AMOAND_D,
+                decode_amo,
+            ),
+            InstructionFilter::new(
+                "amoor.d",
+                inst::MASK_AMOOR_D,
+                inst::MATCH_AMOOR_D,
+                decode_amo,
+            ),
+            InstructionFilter::new(
+                "amoxor.d",
+                inst::MASK_AMOXOR_D,
+                inst::MATCH_AMOXOR_D,
+                decode_amo,
+            ),
+            InstructionFilter::new(
+                "amomax.d",
+                inst::MASK_AMOMAX_D,
+                inst::MATCH_AMOMAX_D,
+                decode_amo,
+            ),
+            InstructionFilter::new(
+                "amomaxu.d",
+                inst::MASK_AMOMAXU_D,
+                inst::MATCH_AMOMAXU_D,
+                decode_amo,
+            ),
+            InstructionFilter::new(
+                "amomin.d",
+                inst::MASK_AMOMIN_D,
+                inst::MATCH_AMOMIN_D,
+                decode_amo,
             ),
             InstructionFilter::new(
                 "amominu.d",
                 inst::MASK_AMOMINU_D,
                 inst::MATCH_AMOMINU_D,
-                fmt_amo,
+                decode_amo,
             ),
-        ]
-    } else {
-        vec![]
-    };
+        ]);
+    }
 
     // F extension, single-precision floating-point
-    let f_inst_filters = if isa_extensions.has_f() {
+    let mut f_inst_filters = if isa_extensions.has_f() {
         vec![
-            InstructionFilter::new("flw", inst::MASK_FLW, inst::MATCH_FLW, fmt_fp_load),
-            InstructionFilter::new("fsw", inst::MASK_FSW, inst::MATCH_FSW, fmt_fp_store),
+            InstructionFilter::new("flw", inst::MASK_FLW, inst::MATCH_FLW, decode_fp_load),
+            InstructionFilter::new("fsw", inst::MASK_FSW, inst::MATCH_FSW, decode_fp_store),
             InstructionFilter::new(
                 "fadd.s",
                 inst::MASK_FADD_S,
                 inst::MATCH_FADD_S,
-                fmt_fp_r_type,
+                decode_fp_r_type,
             ),
             InstructionFilter::new(
                 "fsub.s",
                 inst::MASK_FSUB_S,
                 inst::MATCH_FSUB_S,
-                fmt_fp_r_type,
+                decode_fp_r_type,
             ),
             InstructionFilter::new(
                 "fmul.s",
                 inst::MASK_FMUL_S,
                 inst::MATCH_FMUL_S,
-                fmt_fp_r_type,
+                decode_fp_r_type,
             ),
             InstructionFilter::new(
                 "fdiv.s",
                 inst::MASK_FDIV_S,
                 inst::MATCH_FDIV_S,
-                fmt_fp_r_type,
+                decode_fp_r_type,
             ),
             InstructionFilter::new(
                 "fsqrt.s",
                 inst::MASK_FSQRT_S,
                 inst::MATCH_FSQRT_S,
-                fmt_fp_r_type_no_rs2,
+                decode_fp_r_type_no_rs2,
             ),
             InstructionFilter::new(
                 "fmin.s",
                 inst::MASK_FMIN_S,
                 inst::MATCH_FMIN_S,
-                fmt_fp_r_type,
+                decode_fp_r_type,
             ),
             InstructionFilter::new(
                 "fmax.s",
                 inst::MASK_FMAX_S,
                 inst::MATCH_FMAX_S,
-                fmt_fp_r_type,
+                decode_fp_r_type,
             ),
             InstructionFilter::new(
                 "fmadd.s",
                 inst::MASK_FMADD_S,
                 inst::MATCH_FMADD_S,
-                fmt_fp_r_type_with_rs3,
+                decode_fp_r_type_with_rs3,
             ),
             InstructionFilter::new(
                 "fnmadd.s",
                 inst::MASK_FNMADD_S,
                 inst::MATCH_FNMADD_S,
-                fmt_fp_r_type_with_rs3,
+                decode_fp_r_type_with_rs3,
             ),
             InstructionFilter::new(
                 "fmsub.s",
                 inst::MASK_FMSUB_S,
                 inst::MATCH_FMSUB_S,
-                fmt_fp_r_type_with_rs3,
+                decode_fp_r_type_with_rs3,
             ),
             InstructionFilter::new(
                 "fnmsub.s",
                 inst::MASK_FNMSUB_S,
                 inst::MATCH_FNMSUB_S,
-                fmt_fp_r_type_with_rs3,
+                decode_fp_r_type_with_rs3,
             ),
             InstructionFilter::new(
                 "fcvt.s.w",
                 inst::MASK_FCVT_S_W,
                 inst::MATCH_FCVT_S_W,
-                fmt_fp_r_type_from_int,
-            ),
-            InstructionFilter::new(
-                "fcvt.s.l",
-                inst::MASK_FCVT_S_L,
-                inst::MATCH_FCVT_S_L,
-                fmt_fp_r_type_from_int,
+                decode_fp_r_type_from_int,
             ),
             InstructionFilter::new(
                 "fcvt.s.wu",
                 inst::MASK_FCVT_S_WU,
                 inst::MATCH_FCVT_S_WU,
-                fmt_fp_r_type_from_int,
-            ),
-            InstructionFilter::new(
-                "fcvt.s.lu",
-                inst::MASK_FCVT_S_LU,
-                inst::MATCH_FCVT_S_LU,
-                fmt_fp_r_type_from_int,
+                decode_fp_r_type_from_int,
             ),
             InstructionFilter::new(
                 "fcvt.w.s",
                 inst::MASK_FCVT_W_S,
                 inst::MATCH_FCVT_W_S,
-                fmt_fp_r_type_to_int,
-            ),
-            InstructionFilter::new(
-                "fcvt.l.s",
-                inst::MASK_FCVT_L_S,
-                inst::MATCH_FCVT_L_S,
-                fmt_fp_r_type_to_int,
+                decode_fp_r_type_to_int,
             ),
             InstructionFilter::new(
                 "fcvt.wu.s",
                 inst::MASK_FCVT_WU_S,
                 inst::MATCH_FCVT_WU_S,
-                fmt_fp_r_type_to_int,
-            ),
-            InstructionFilter::new(
-                "fcvt.lu.s",
-                inst::MASK_FCVT_LU_S,
-                inst::MATCH_FCVT_LU_S,
-                fmt_fp_r_type_to_int,
+                decode_fp_r_type_to_int,
             ),
             InstructionFilter::new(
                 "fsgnj.s",
                 inst::MASK_FSGNJ_S,
                 inst::MATCH_FSGNJ_S,
-                fmt_fp_r_type,
+                decode_fp_r_type,
             ),
             InstructionFilter::new(
                 "fsgnjn.s",
                 inst::MASK_FSGNJN_S,
                 inst::MATCH_FSGNJN_S,
-                fmt_fp_r_type,
+                decode_fp_r_type,
             ),
             InstructionFilter::new(
                 "fsgnjx.s",
                 inst::MASK_FSGNJX_S,
                 inst::MATCH_FSGNJX_S,
-                fmt_fp_r_type,
+                decode_fp_r_type,
             ),
             InstructionFilter::new(
                 "fmv.w.x",
                 inst::MASK_FMV_W_X,
                 inst::MATCH_FMV_W_X,
-                fmt_fp_r_type_from_int,
+                decode_fp_r_type_from_int,
             ),
             InstructionFilter::new(
                 "fmv.x.w",
                 inst::MASK_FMV_X_W,
                 inst::MATCH_FMV_X_W,
-                fmt_fp_r_type_to_int,
+                decode_fp_r_type_to_int,
             ),
             InstructionFilter::new(
                 "feq.s",
                 inst::MASK_FEQ_S,
                 inst::MATCH_FEQ_S,
-                fmt_fp_r_type_int_rd,
+                decode_fp_r_type_int_rd,
             ),
             InstructionFilter::new(
                 "flt.s",
                 inst::MASK_FLT_S,
                 inst::MATCH_FLT_S,
-                fmt_fp_r_type_int_rd,
+                decode_fp_r_type_int_rd,
             ),
             InstructionFilter::new(
                 "fle.s",
                 inst::MASK_FLE_S,
                 inst::MATCH_FLE_S,
-                fmt_fp_r_type_int_rd,
+                decode_fp_r_type_int_rd,
             ),
             InstructionFilter::new(
                 "fclass.s",
                 inst::MASK_FCLASS_S,
                 inst::MATCH_FCLASS_S,
-                fmt_fp_r_type_to_int,
+                decode_fp_r_type_to_int,
             ),
         ]
     } else {
         vec![]
     };
+    // Conversions to and from 64-bit integers, on RV64 only.
+    if isa_extensions.has_f() && xlen == Xlen::Rv64 {
+        f_inst_filters.extend(vec![
+            InstructionFilter::new(
+                "fcvt.s.l",
+                inst::MASK_FCVT_S_L,
+                inst::MATCH_FCVT_S_L,
+                decode_fp_r_type_from_int,
+            ),
+            InstructionFilter::new(
+                "fcvt.s.lu",
+                inst::MASK_FCVT_S_LU,
+                inst::MATCH_FCVT_S_LU,
+                decode_fp_r_type_from_int,
+            ),
+            InstructionFilter::new(
+                "fcvt.l.s",
+                inst::MASK_FCVT_L_S,
+                inst::MATCH_FCVT_L_S,
+                decode_fp_r_type_to_int,
+            ),
+            InstructionFilter::new(
+                "fcvt.lu.s",
+                inst::MASK_FCVT_LU_S,
+                inst::MATCH_FCVT_LU_S,
+                decode_fp_r_type_to_int,
+            ),
+        ]);
+    }
 
     // D extension, double-precision floating-point
-    let d_inst_filters = if isa_extensions.has_d() {
+    let mut d_inst_filters = if isa_extensions.has_d() {
         vec![
-            InstructionFilter::new("fld", inst::MASK_FLD, inst::MATCH_FLD, fmt_fp_load),
-            InstructionFilter::new("fsd", inst::MASK_FSD, inst::MATCH_FSD, fmt_fp_store),
+            InstructionFilter::new("fld", inst::MASK_FLD, inst::MATCH_FLD, decode_fp_load),
+            InstructionFilter::new("fsd", inst::MASK_FSD, inst::MATCH_FSD, decode_fp_store),
             InstructionFilter::new(
                 "fadd.d",
                 inst::MASK_FADD_D,
                 inst::MATCH_FADD_D,
-                fmt_fp_r_type,
+                decode_fp_r_type,
             ),
             InstructionFilter::new(
                 "fsub.d",
                 inst::MASK_FSUB_D,
                 inst::MATCH_FSUB_D,
-                fmt_fp_r_type,
+                decode_fp_r_type,
             ),
             InstructionFilter::new(
                 "fmul.d",
                 inst::MASK_FMUL_D,
                 inst::MATCH_FMUL_D,
-                fmt_fp_r_type,
+                decode_fp_r_type,
             ),
             InstructionFilter::new(
                 "fdiv.d",
                 inst::MASK_FDIV_D,
                 inst::MATCH_FDIV_D,
-                fmt_fp_r_type,
+                decode_fp_r_type,
             ),
             InstructionFilter::new(
                 "fsqrt.d",
                 inst::MASK_FSQRT_D,
                 inst::MATCH_FSQRT_D,
-                fmt_fp_r_type_no_rs2,
+                decode_fp_r_type_no_rs2,
             ),
             InstructionFilter::new(
                 "fmin.d",
                 inst::MASK_FMIN_D,
                 inst::MATCH_FMIN_D,
-                fmt_fp_r_type,
+                decode_fp_r_type,
             ),
             InstructionFilter::new(
                 "fmax.d",
                 inst::MASK_FMAX_D,
                 inst::MATCH_FMAX_D,
-                fmt_fp_r_type,
+                decode_fp_r_type,
             ),
             InstructionFilter::new(
                 "fmadd.d",
                 inst::MASK_FMADD_D,
                 inst::MATCH_FMADD_D,
-                fmt_fp_r_type_with_rs3,
+                decode_fp_r_type_with_rs3,
             ),
             InstructionFilter::new(
                 "fnmadd.d",
                 inst::MASK_FNMADD_D,
                 inst::MATCH_FNMADD_D,
-                fmt_fp_r_type_with_rs3,
+                decode_fp_r_type_with_rs3,
             ),
             InstructionFilter::new(
                 "fmsub.d",
                 inst::MASK_FMSUB_D,
                 inst::MATCH_FMSUB_D,
-                fmt_fp_r_type_with_rs3,
+                decode_fp_r_type_with_rs3,
             ),
             InstructionFilter::new(
                 "fnmsub.d",
                 inst::MASK_FNMSUB_D,
                 inst::MATCH_FNMSUB_D,
-                fmt_fp_r_type_with_rs3,
+                decode_fp_r_type_with_rs3,
             ),
             InstructionFilter::new(
                 "fcvt.d.w",
                 inst::MASK_FCVT_D_W,
                 inst::MATCH_FCVT_D_W,
-                fmt_fp_r_type_from_int,
-            ),
-            InstructionFilter::new(
-                "fcvt.d.l",
-                inst::MASK_FCVT_D_L,
-                inst::MATCH_FCVT_D_L,
-                fmt_fp_r_type_from_int,
+                decode_fp_r_type_from_int,
             ),
             InstructionFilter::new(
                 "fcvt.d.wu",
                 inst::MASK_FCVT_D_WU,
                 inst::MATCH_FCVT_D_WU,
-                fmt_fp_r_type_from_int,
-            ),
-            InstructionFilter::new(
-                "fcvt.d.lu",
-                inst::MASK_FCVT_D_LU,
-                inst::MATCH_FCVT_D_LU,
-                fmt_fp_r_type_from_int,
+                decode_fp_r_type_from_int,
             ),
             InstructionFilter::new(
                 "fcvt.w.d",
                 inst::MASK_FCVT_W_D,
                 inst::MATCH_FCVT_W_D,
-                fmt_fp_r_type_to_int,
-            ),
-            InstructionFilter::new(
-                "fcvt.l.d",
-                inst::MASK_FCVT_L_D,
-                inst::MATCH_FCVT_L_D,
-                fmt_fp_r_type_to_int,
+                decode_fp_r_type_to_int,
             ),
             InstructionFilter::new(
                 "fcvt.wu.d",
                 inst::MASK_FCVT_WU_D,
                 inst::MATCH_FCVT_WU_D,
-                fmt_fp_r_type_to_int,
-            ),
-            InstructionFilter::new(
-                "fcvt.lu.d",
-                inst::MASK_FCVT_LU_D,
-                inst::MATCH_FCVT_LU_D,
-                fmt_fp_r_type_to_int,
+                decode_fp_r_type_to_int,
             ),
             InstructionFilter::new(
                 "fcvt.s.d",
                 inst::MASK_FCVT_S_D,
                 inst::MATCH_FCVT_S_D,
-                fmt_fp_r_type_no_rs2,
+                decode_fp_r_type_no_rs2,
             ),
             InstructionFilter::new(
                 "fcvt.d.s",
                 inst::MASK_FCVT_D_S,
                 inst::MATCH_FCVT_D_S,
-                fmt_fp_r_type_no_rs2,
+                decode_fp_r_type_no_rs2,
             ),
             InstructionFilter::new(
                 "fsgnj.d",
                 inst::MASK_FSGNJ_D,
                 inst::MATCH_FSGNJ_D,
-                fmt_fp_r_type,
+                decode_fp_r_type,
             ),
             InstructionFilter::new(
                 "fsgnjn.d",
                 inst::MASK_FSGNJN_D,
                 inst::MATCH_FSGNJN_D,
-                fmt_fp_r_type,
+                decode_fp_r_type,
             ),
             InstructionFilter::new(
                 "fsgnjx.d",
                 inst::MASK_FSGNJX_D,
                 inst::MATCH_FSGNJX_D,
-                fmt_fp_r_type,
-            ),
-            InstructionFilter::new(
-                "fmv.x.d",
-                inst::MASK_FMV_X_D,
-                inst::MATCH_FMV_X_D,
-                fmt_fp_r_type_to_int,
-            ),
-            InstructionFilter::new(
-                "fmv.d.x",
-                inst::MASK_FMV_D_X,
-                inst::MATCH_FMV_D_X,
-                fmt_fp_r_type_from_int,
+                decode_fp_r_type,
             ),
             InstructionFilter::new(
                 "feq.d",
                 inst::MASK_FEQ_D,
                 inst::MATCH_FEQ_D,
-                fmt_fp_r_type_int_rd,
+                decode_fp_r_type_int_rd,
             ),
             InstructionFilter::new(
                 "flt.d",
                 inst::MASK_FLT_D,
                 inst::MATCH_FLT_D,
-                fmt_fp_r_type_int_rd,
+                decode_fp_r_type_int_rd,
             ),
             InstructionFilter::new(
                 "fle.d",
                 inst::MASK_FLE_D,
                 inst::MATCH_FLE_D,
-                fmt_fp_r_type_int_rd,
+                decode_fp_r_type_int_rd,
             ),
             InstructionFilter::new(
                 "fclass.d",
                 inst::MASK_FCLASS_D,
                 inst::MATCH_FCLASS_D,
-                fmt_fp_r_type_to_int,
+                decode_fp_r_type_to_int,
             ),
         ]
     } else {
         vec![]
     };
+    // Conversions to and from 64-bit integers, and moves between 64-bit registers, on RV64 only.
+    if isa_extensions.has_d() && xlen == Xlen::Rv64 {
+        d_inst_filters.extend(vec![
+            InstructionFilter::new(
+                "fcvt.d.l",
+                inst::MASK_FCVT_D_L,
+                inst::MATCH_FCVT_D_L,
+                decode_fp_r_type_from_int,
+            ),
+            InstructionFilter::new(
+                "fcvt.d.lu",
+                inst::MASK_FCVT_D_LU,
+                inst::MATCH_FCVT_D_LU,
+                decode_fp_r_type_from_int,
+            ),
+            InstructionFilter::new(
+                "fcvt.l.d",
+                inst::MASK_FCVT_L_D,
+                inst::MATCH_FCVT_L_D,
+                decode_fp_r_type_to_int,
+            ),
+            InstructionFilter::new(
+                "fcvt.lu.d",
+                inst::MASK_FCVT_LU_D,
+                inst::MATCH_FCVT_LU_D,
+                decode_fp_r_type_to_int,
+            ),
+            InstructionFilter::new(
+                "fmv.x.d",
+                inst::MASK_FMV_X_D,
+                inst::MATCH_FMV_X_D,
+                decode_fp_r_type_to_int,
+            ),
+            InstructionFilter::new(
+                "fmv.d.x",
+                inst::MASK_FMV_D_X,
+                inst::MATCH_FMV_D_X,
+                decode_fp_r_type_from_int,
+            ),
+        ]);
+    }
 
-    // C extension, compressed instructions
-    let c_inst_filters = if isa_extensions.has_c() {
-        let mut pseudo_instruction_filters = if enable_pseudo_instructions {
-            vec![InstructionFilter::new(
-                "ret",
-                inst::MASK_C_JR | registers::MASK_RD,
-                inst::MATCH_C_JR | registers::MATCH_RD_EQUALS_RA,
-                fmt_no_args,
-            )]
-        } else {
-            vec![]
-        };
-
-        let xlen_filters = match xlen {
-            Xlen::Rv32 => {
-                let mut rv32_filters = vec![InstructionFilter::new(
-                    "c.jal",
-                    inst::MASK_C_JAL,
-                    inst::MATCH_C_JAL,
-                    fmt_cj_type,
-                )];
-                let rv32f_filters = if isa_extensions.has_f() {
-                    vec![
-                        InstructionFilter::new(
-                            "c.flwsp",
-                            inst::MASK_C_FLWSP,
-                            inst::MATCH_C_FLWSP,
-                            fmt_ci_type_flwsp,
-                        ),
-                        InstructionFilter::new(
-                            "c.fswsp",
-                            inst::MASK_C_FSWSP,
-                            inst::MATCH_C_FSWSP,
-                            fmt_css_type_fswsp,
-                        ),
-                        InstructionFilter::new(
-                            "c.flw",
-                            inst::MASK_C_FLW,
-                            inst::MATCH_C_FLW,
-                            fmt_cl_type_flw,
-                        ),
-                        InstructionFilter::new(
-                            "c.fsw",
-                            inst::MASK_C_FSW,
-                            inst::MATCH_C_FSW,
-                            fmt_cl_type_flw,
-                        ),
-                    ]
-                } else {
-                    vec![]
-                };
-                rv32_filters.extend(rv32f_filters);
-                rv32_filters
-            }
-
+    // Q extension, quad-precision floating-point
+    let q_inst_filters = if isa_extensions.has_q() {
+        let mut xlen_filters = match xlen {
+            Xlen::Rv32 => vec![],
             Xlen::Rv64 => vec![
                 InstructionFilter::new(
-                    "c.ldsp",
-                    inst::MASK_C_LDSP,
-                    inst::MATCH_C_LDSP,
-                    fmt_ci_type_ldsp,
-                ),
-                InstructionFilter::new(
-                    "c.sdsp",
-                    inst::MASK_C_SDSP,
-                    inst::MATCH_C_SDSP,
-                    fmt_css_type_sdsp,
+                    "fcvt.l.q",
+                    inst::MASK_FCVT_L_Q,
+                    inst::MATCH_FCVT_L_Q,
+                    decode_fp_r_type_to_int,
                 ),
-                InstructionFilter::new("c.ld", inst::MASK_C_LD, inst::MATCH_C_LD, fmt_cl_type_ld),
-                InstructionFilter::new("c.sd", inst::MASK_C_SD, inst::MATCH_C_SD, fmt_cl_type_ld),
                 InstructionFilter::new(
-                    "c.addiw",
-                    inst::MASK_C_ADDIW,
-                    inst::MATCH_C_ADDIW,
-                    fmt_ci_type,
+                    "fcvt.lu.q",
+                    inst::MASK_FCVT_LU_Q,
+                    inst::MATCH_FCVT_LU_Q,
+                    decode_fp_r_type_to_int,
                 ),
                 InstructionFilter::new(
-                    "c.addw",
-                    inst::MASK_C_ADDW,
-                    inst::MATCH_C_ADDW,
-                    fmt_ca_type,
+                    "fcvt.q.l",
+                    inst::MASK_FCVT_Q_L,
+                    inst::MATCH_FCVT_Q_L,
+                    decode_fp_r_type_from_int,
                 ),
                 InstructionFilter::new(
-                    "c.subw",
-                    inst::MASK_C_SUBW,
-                    inst::MATCH_C_SUBW,
-                    fmt_ca_type,
+                    "fcvt.q.lu",
+                    inst::MASK_FCVT_Q_LU,
+                    inst::MATCH_FCVT_Q_LU,
+                    decode_fp_r_type_from_int,
                 ),
             ],
         };
 
-        let d_filters = if isa_extensions.has_d() {
-            vec![
-                InstructionFilter::new(
-                    "c.fldsp",
-                    inst::MASK_C_FLDSP,
-                    inst::MATCH_C_FLDSP,
-                    fmt_ci_type_fldsp,
-                ),
-                InstructionFilter::new(
-                    "c.fsdsp",
-                    inst::MASK_C_FSDSP,
-                    inst::MATCH_C_FSDSP,
-                    fmt_css_type_fsdsp,
-                ),
-                InstructionFilter::new(
-                    "c.fld",
-                    inst::MASK_C_FLD,
-                    inst::MATCH_C_FLD,
-                    fmt_cl_type_fld,
-                ),
-                InstructionFilter::new(
-                    "c.fsd",
-                    inst::MASK_C_FSD,
-                    inst::MATCH_C_FSD,
-                    fmt_cl_type_fld,
-                ),
-            ]
-        } else {
-            vec![]
-        };
-
+        let global_filters = vec![
+            InstructionFilter::new("flq", inst::MASK_FLQ, inst::MATCH_FLQ, decode_fp_load),
+            InstructionFilter::new("fsq", inst::MASK_FSQ, inst::MATCH_FSQ, decode_fp_store),
+            InstructionFilter::new(
+                "fadd.q",
+                inst::MASK_FADD_Q,
+                inst::MATCH_FADD_Q,
+                decode_fp_r_type,
+            ),
+            InstructionFilter::new(
+                "fsub.q",
+                inst::MASK_FSUB_Q,
+                inst::MATCH_FSUB_Q,
+                decode_fp_r_type,
+            ),
+            InstructionFilter::new(
+                "fmul.q",
+                inst::MASK_FMUL_Q,
+                inst::MATCH_FMUL_Q,
+                decode_fp_r_type,
+            ),
+            InstructionFilter::new(
+                "fdiv.q",
+                inst::MASK_FDIV_Q,
+                inst::MATCH_FDIV_Q,
+                decode_fp_r_type,
+            ),
+            InstructionFilter::new(
+                "fsqrt.q",
+                inst::MASK_FSQRT_Q,
+                inst::MATCH_FSQRT_Q,
+                decode_fp_r_type_no_rs2,
+            ),
+            InstructionFilter::new(
+                "fmin.q",
+                inst::MASK_FMIN_Q,
+                inst::MATCH_FMIN_Q,
+                decode_fp_r_type,
+            ),
+            InstructionFilter::new(
+                "fmax.q",
+                inst::MASK_FMAX_Q,
+                inst::MATCH_FMAX_Q,
+                decode_fp_r_type,
+            ),
+            InstructionFilter::new(
+                "fmadd.q",
+                inst::MASK_FMADD_Q,
+                inst::MATCH_FMADD_Q,
+                decode_fp_r_type_with_rs3,
+            ),
+            InstructionFilter::new(
+                "fnmadd.q",
+                inst::MASK_FNMADD_Q,
+                inst::MATCH_FNMADD_Q,
+                decode_fp_r_type_with_rs3,
+            ),
+            InstructionFilter::new(
+                "fmsub.q",
+                inst::MASK_FMSUB_Q,
+                inst::MATCH_FMSUB_Q,
+                decode_fp_r_type_with_rs3,
+            ),
+            InstructionFilter::new(
+                "fnmsub.q",
+                inst::MASK_FNMSUB_Q,
+                inst::MATCH_FNMSUB_Q,
+                decode_fp_r_type_with_rs3,
+            ),
+            InstructionFilter::new(
+                "fcvt.q.w",
+                inst::MASK_FCVT_Q_W,
+                inst::MATCH_FCVT_Q_W,
+                decode_fp_r_type_from_int,
+            ),
+            InstructionFilter::new(
+                "fcvt.q.wu",
+                inst::MASK_FCVT_Q_WU,
+                inst::MATCH_FCVT_Q_WU,
+                decode_fp_r_type_from_int,
+            ),
+            InstructionFilter::new(
+                "fcvt.w.q",
+                inst::MASK_FCVT_W_Q,
+                inst::MATCH_FCVT_W_Q,
+                decode_fp_r_type_to_int,
+            ),
+            InstructionFilter::new(
+                "fcvt.wu.q",
+                inst::MASK_FCVT_WU_Q,
+                inst::MATCH_FCVT_WU_Q,
+                decode_fp_r_type_to_int,
+            ),
+            InstructionFilter::new(
+                "fcvt.s.q",
+                inst::MASK_FCVT_S_Q,
+                inst::MATCH_FCVT_S_Q,
+                decode_fp_r_type_no_rs2,
+            ),
+            InstructionFilter::new(
+                "fcvt.q.s",
+                inst::MASK_FCVT_Q_S,
+                inst::MATCH_FCVT_Q_S,
+                decode_fp_r_type_no_rs2,
+            ),
+            InstructionFilter::new(
+                "fcvt.d.q",
+                inst::MASK_FCVT_D_Q,
+                inst::MATCH_FCVT_D_Q,
+                decode_fp_r_type_no_rs2,
+            ),
+            InstructionFilter::new(
+                "fcvt.q.d",
+                inst::MASK_FCVT_Q_D,
+                inst::MATCH_FCVT_Q_D,
+                decode_fp_r_type_no_rs2,
+            ),
+            InstructionFilter::new(
+                "fsgnj.q",
+                inst::MASK_FSGNJ_Q,
+                inst::MATCH_FSGNJ_Q,
+                decode_fp_r_type,
+            ),
+            InstructionFilter::new(
+                "fsgnjn.q",
+                inst::MASK_FSGNJN_Q,
+                inst::MATCH_FSGNJN_Q,
+                decode_fp_r_type,
+            ),
+            InstructionFilter::new(
+                "fsgnjx.q",
+                inst::MASK_FSGNJX_Q,
+                inst::MATCH_FSGNJX_Q,
+                decode_fp_r_type,
+            ),
+            InstructionFilter::new(
+                "feq.q",
+                inst::MASK_FEQ_Q,
+                inst::MATCH_FEQ_Q,
+                decode_fp_r_type_int_rd,
+            ),
+            InstructionFilter::new(
+                "flt.q",
+                inst::MASK_FLT_Q,
+                inst::MATCH_FLT_Q,
+                decode_fp_r_type_int_rd,
+            ),
+            InstructionFilter::new(
+                "fle.q",
+                inst::MASK_FLE_Q,
+                inst::MATCH_FLE_Q,
+                decode_fp_r_type_int_rd,
+            ),
+            InstructionFilter::new(
+                "fclass.q",
+                inst::MASK_FCLASS_Q,
+                inst::MATCH_FCLASS_Q,
+                decode_fp_r_type_to_int,
+            ),
+        ];
+
+        xlen_filters.extend(global_filters);
+        xlen_filters
+    } else {
+        vec![]
+    };
+
+    // Zba extension, address generation
+    let zba_inst_filters = if isa_extensions.has_zba() {
+        let mut xlen_filters = match xlen {
+            Xlen::Rv32 => vec![],
+            Xlen::Rv64 => {
+                let mut rv64_filters = if enable_pseudo_instructions {
+                    vec![InstructionFilter::new(
+                        "zext.w",
+                        inst::MASK_ADD_UW | registers::MASK_RS2,
+                        inst::MATCH_ADD_UW,
+                        decode_r_type_no_rs2,
+                    )]
+                } else {
+                    vec![]
+                };
+                rv64_filters.extend(vec![
+                    InstructionFilter::new(
+                        "add.uw",
+                        inst::MASK_ADD_UW,
+                        inst::MATCH_ADD_UW,
+                        decode_r_type,
+                    ),
+                    InstructionFilter::new(
+                        "sh1add.uw",
+                        inst::MASK_SH1ADD_UW,
+                        inst::MATCH_SH1ADD_UW,
+                        decode_r_type,
+                    ),
+                    InstructionFilter::new(
+                        "sh2add.uw",
+                        inst::MASK_SH2ADD_UW,
+                        inst::MATCH_SH2ADD_UW,
+                        decode_r_type,
+                    ),
+                    InstructionFilter::new(
+                        "sh3add.uw",
+                        inst::MASK_SH3ADD_UW,
+                        inst::MATCH_SH3ADD_UW,
+                        decode_r_type,
+                    ),
+                    InstructionFilter::new(
+                        "slli.uw",
+                        inst::MASK_SLLI_UW,
+                        inst::MATCH_SLLI_UW,
+                        decode_i_type_shift,
+                    ),
+                ]);
+                rv64_filters
+            }
+        };
+
+        let global_filters = vec![
+            InstructionFilter::new(
+                "sh1add",
+                inst::MASK_SH1ADD,
+                inst::MATCH_SH1ADD,
+                decode_r_type,
+            ),
+            InstructionFilter::new(
+                "sh2add",
+                inst::MASK_SH2ADD,
+                inst::MATCH_SH2ADD,
+                decode_r_type,
+            ),
+            InstructionFilter::new(
+                "sh3add",
+                inst::MASK_SH3ADD,
+                inst::MATCH_SH3ADD,
+                decode_r_type,
+            ),
+        ];
+
+        xlen_filters.extend(global_filters);
+        xlen_filters
+    } else {
+        vec![]
+    };
+
+    // Zbb extension, basic bit-manipulation
+    let zbb_inst_filters = if isa_extensions.has_zbb() {
+        let mut xlen_filters = match xlen {
+            Xlen::Rv32 => vec![
+                InstructionFilter::new(
+                    "zext.h",
+                    inst::MASK_ZEXT_H_RV32,
+                    inst::MATCH_ZEXT_H_RV32,
+                    decode_r_type_no_rs2,
+                ),
+                InstructionFilter::new(
+                    "rev8",
+                    inst::MASK_REV8_RV32,
+                    inst::MATCH_REV8_RV32,
+                    decode_i_type_no_imm,
+                ),
+            ],
+            Xlen::Rv64 => vec![
+                InstructionFilter::new(
+                    "clzw",
+                    inst::MASK_CLZW,
+                    inst::MATCH_CLZW,
+                    decode_i_type_no_imm,
+                ),
+                InstructionFilter::new(
+                    "ctzw",
+                    inst::MASK_CTZW,
+                    inst::MATCH_CTZW,
+                    decode_i_type_no_imm,
+                ),
+                InstructionFilter::new(
+                    "cpopw",
+                    inst::MASK_CPOPW,
+                    inst::MATCH_CPOPW,
+                    decode_i_type_no_imm,
+                ),
+                InstructionFilter::new("rolw", inst::MASK_ROLW, inst::MATCH_ROLW, decode_r_type),
+                InstructionFilter::new("rorw", inst::MASK_RORW, inst::MATCH_RORW, decode_r_type),
+                InstructionFilter::new(
+                    "roriw",
+                    inst::MASK_RORIW,
+                    inst::MATCH_RORIW,
+                    decode_i_type_shift,
+                ),
+                InstructionFilter::new(
+                    "zext.h",
+                    inst::MASK_ZEXT_H,
+                    inst::MATCH_ZEXT_H,
+                    decode_r_type_no_rs2,
+                ),
+                InstructionFilter::new(
+                    "rev8",
+                    inst::MASK_REV8,
+                    inst::MATCH_REV8,
+                    decode_i_type_no_imm,
+                ),
+            ],
+        };
+
+        let global_filters = vec![
+            InstructionFilter::new("andn", inst::MASK_ANDN, inst::MATCH_ANDN, decode_r_type),
+            InstructionFilter::new("orn", inst::MASK_ORN, inst::MATCH_ORN, decode_r_type),
+            InstructionFilter::new("xnor", inst::MASK_XNOR, inst::MATCH_XNOR, decode_r_type),
+            InstructionFilter::new("clz", inst::MASK_CLZ, inst::MATCH_CLZ, decode_i_type_no_imm),
+            InstructionFilter::new("ctz", inst::MASK_CTZ, inst::MATCH_CTZ, decode_i_type_no_imm),
+            InstructionFilter::new(
+                "cpop",
+                inst::MASK_CPOP,
+                inst::MATCH_CPOP,
+                decode_i_type_no_imm,
+            ),
+            InstructionFilter::new("max", inst::MASK_MAX, inst::MATCH_MAX, decode_r_type),
+            InstructionFilter::new("maxu", inst::MASK_MAXU, inst::MATCH_MAXU, decode_r_type),
+            InstructionFilter::new("min", inst::MASK_MIN, inst::MATCH_MIN, decode_r_type),
+            InstructionFilter::new("minu", inst::MASK_MINU, inst::MATCH_MINU, decode_r_type),
+            InstructionFilter::new(
+                "sext.b",
+                inst::MASK_SEXT_B,
+                inst::MATCH_SEXT_B,
+                decode_i_type_no_imm,
+            ),
+            InstructionFilter::new(
+                "sext.h",
+                inst::MASK_SEXT_H,
+                inst::MATCH_SEXT_H,
+                decode_i_type_no_imm,
+            ),
+            InstructionFilter::new("rol", inst::MASK_ROL, inst::MATCH_ROL, decode_r_type),
+            InstructionFilter::new("ror", inst::MASK_ROR, inst::MATCH_ROR, decode_r_type),
+            InstructionFilter::new(
+                "rori",
+                inst::MASK_RORI,
+                inst::MATCH_RORI,
+                decode_i_type_shift,
+            ),
+            InstructionFilter::new(
+                "orc.b",
+                inst::MASK_ORC_B,
+                inst::MATCH_ORC_B,
+                decode_i_type_no_imm,
+            ),
+        ];
+
+        xlen_filters.extend(global_filters);
+        xlen_filters
+    } else {
+        vec![]
+    };
+
+    // Zbc extension, carry-less multiplication
+    let zbc_inst_filters = if isa_extensions.has_zbc() {
+        vec![
+            InstructionFilter::new("clmul", inst::MASK_CLMUL, inst::MATCH_CLMUL, decode_r_type),
+            InstructionFilter::new(
+                "clmulh",
+                inst::MASK_CLMULH,
+                inst::MATCH_CLMULH,
+                decode_r_type,
+            ),
+            InstructionFilter::new(
+                "clmulr",
+                inst::MASK_CLMULR,
+                inst::MATCH_CLMULR,
+                decode_r_type,
+            ),
+        ]
+    } else {
+        vec![]
+    };
+
+    // Zbkb extension, bit-manipulation for cryptography. Only the instructions it shares with Zbb
+    // are decoded, and they belong to Zbb when that's enabled.
+    let zbkb_inst_filters = if isa_extensions.has_zbkb() && !isa_extensions.has_zbb() {
+        let mut xlen_filters = match xlen {
+            Xlen::Rv32 => vec![InstructionFilter::new(
+                "rev8",
+                inst::MASK_REV8_RV32,
+                inst::MATCH_REV8_RV32,
+                decode_i_type_no_imm,
+            )],
+            Xlen::Rv64 => vec![
+                InstructionFilter::new("rolw", inst::MASK_ROLW, inst::MATCH_ROLW, decode_r_type),
+                InstructionFilter::new("rorw", inst::MASK_RORW, inst::MATCH_RORW, decode_r_type),
+                InstructionFilter::new(
+                    "roriw",
+                    inst::MASK_RORIW,
+                    inst::MATCH_RORIW,
+                    decode_i_type_shift,
+                ),
+                InstructionFilter::new(
+                    "rev8",
+                    inst::MASK_REV8,
+                    inst::MATCH_REV8,
+                    decode_i_type_no_imm,
+                ),
+            ],
+        };
+
+        let global_filters = vec![
+            InstructionFilter::new("andn", inst::MASK_ANDN, inst::MATCH_ANDN, decode_r_type),
+            InstructionFilter::new("orn", inst::MASK_ORN, inst::MATCH_ORN, decode_r_type),
+            InstructionFilter::new("xnor", inst::MASK_XNOR, inst::MATCH_XNOR, decode_r_type),
+            InstructionFilter::new("rol", inst::MASK_ROL, inst::MATCH_ROL, decode_r_type),
+            InstructionFilter::new("ror", inst::MASK_ROR, inst::MATCH_ROR, decode_r_type),
+            InstructionFilter::new(
+                "rori",
+                inst::MASK_RORI,
+                inst::MATCH_RORI,
+                decode_i_type_shift,
+            ),
+        ];
+
+        xlen_filters.extend(global_filters);
+        xlen_filters
+    } else {
+        vec![]
+    };
+
+    // Zbkc extension, carry-less multiplication for cryptography. Like Zbkb, it's decoded as the
+    // part of Zbc it shares.
+    let zbkc_inst_filters = if isa_extensions.has_zbkc() && !isa_extensions.has_zbc() {
+        vec![
+            InstructionFilter::new("clmul", inst::MASK_CLMUL, inst::MATCH_CLMUL, decode_r_type),
+            InstructionFilter::new(
+                "clmulh",
+                inst::MASK_CLMULH,
+                inst::MATCH_CLMULH,
+                decode_r_type,
+            ),
+        ]
+    } else {
+        vec![]
+    };
+
+    // Zbs extension, single-bit instructions
+    let zbs_inst_filters = if isa_extensions.has_zbs() {
+        vec![
+            InstructionFilter::new("bclr", inst::MASK_BCLR, inst::MATCH_BCLR, decode_r_type),
+            InstructionFilter::new(
+                "bclri",
+                inst::MASK_BCLRI,
+                inst::MATCH_BCLRI,
+                decode_i_type_shift,
+            ),
+            InstructionFilter::new("bext", inst::MASK_BEXT, inst::MATCH_BEXT, decode_r_type),
+            InstructionFilter::new(
+                "bexti",
+                inst::MASK_BEXTI,
+                inst::MATCH_BEXTI,
+                decode_i_type_shift,
+            ),
+            InstructionFilter::new("binv", inst::MASK_BINV, inst::MATCH_BINV, decode_r_type),
+            InstructionFilter::new(
+                "binvi",
+                inst::MASK_BINVI,
+                inst::MATCH_BINVI,
+                decode_i_type_shift,
+            ),
+            InstructionFilter::new("bset", inst::MASK_BSET, inst::MATCH_BSET, decode_r_type),
+            InstructionFilter::new(
+                "bseti",
+                inst::MASK_BSETI,
+                inst::MATCH_BSETI,
+                decode_i_type_shift,
+            ),
+        ]
+    } else {
+        vec![]
+    };
+
+    // C extension, compressed instructions
+    let c_inst_filters = if isa_extensions.has_c() {
+        let mut pseudo_instruction_filters = if enable_pseudo_instructions {
+            vec![InstructionFilter::new(
+                "ret",
+                inst::MASK_C_JR | registers::MASK_RD,
+                inst::MATCH_C_JR | registers::MATCH_RD_EQUALS_RA,
+                decode_cr_type_no_args,
+            )]
+        } else {
+            vec![]
+        };
+
+        let xlen_filters = match xlen {
+            Xlen::Rv32 => {
+                let mut rv32_filters = vec![InstructionFilter::new(
+                    "c.jal",
+                    inst::MASK_C_JAL,
+                    inst::MATCH_C_JAL,
+                    decode_cj_type,
+                )];
+                let rv32f_filters = if isa_extensions.has_f() {
+                    vec![
+                        InstructionFilter::new(
+                            "c.flwsp",
+                            inst::MASK_C_FLWSP,
+                            inst::MATCH_C_FLWSP,
+                            decode_ci_type_flwsp,
+                        ),
+                        InstructionFilter::new(
+                            "c.fswsp",
+                            inst::MASK_C_FSWSP,
+                            inst::MATCH_C_FSWSP,
+                            decode_css_type_fswsp,
+                        ),
+                        InstructionFilter::new(
+                            "c.flw",
+                            inst::MASK_C_FLW,
+                            inst::MATCH_C_FLW,
+                            decode_cl_type_flw,
+                        ),
+                        InstructionFilter::new(
+                            "c.fsw",
+                            inst::MASK_C_FSW,
+                            inst::MATCH_C_FSW,
+                            decode_cs_type_fsw,
+                        ),
+                    ]
+                } else {
+                    vec![]
+                };
+                rv32_filters.extend(with_extension(rv32f_filters, Extensions::C | Extensions::F));
+                rv32_filters
+            }
+
+            Xlen::Rv64 => vec![
+                InstructionFilter::new(
+                    "c.ldsp",
+                    inst::MASK_C_LDSP,
+                    inst::MATCH_C_LDSP,
+                    decode_ci_type_ldsp,
+                ),
+                InstructionFilter::new(
+                    "c.sdsp",
+                    inst::MASK_C_SDSP,
+                    inst::MATCH_C_SDSP,
+                    decode_css_type_sdsp,
+                ),
+                InstructionFilter::new(
+                    "c.ld",
+                    inst::MASK_C_LD,
+                    inst::MATCH_C_LD,
+                    decode_cl_type_ld,
+                ),
+                InstructionFilter::new(
+                    "c.sd",
+                    inst::MASK_C_SD,
+                    inst::MATCH_C_SD,
+                    decode_cs_type_sd,
+                ),
+                InstructionFilter::new(
+                    "c.addiw",
+                    inst::MASK_C_ADDIW,
+                    inst::MATCH_C_ADDIW,
+                    decode_ci_type,
+                ),
+                InstructionFilter::new(
+                    "c.addw",
+                    inst::MASK_C_ADDW,
+                    inst::MATCH_C_ADDW,
+                    decode_ca_type,
+                ),
+                InstructionFilter::new(
+                    "c.subw",
+                    inst::MASK_C_SUBW,
+                    inst::MATCH_C_SUBW,
+                    decode_ca_type,
+                ),
+            ],
+        };
+
+        let d_filters = if isa_extensions.has_d() {
+            vec![
+                InstructionFilter::new(
+                    "c.fldsp",
+                    inst::MASK_C_FLDSP,
+                    inst::MATCH_C_FLDSP,
+                    decode_ci_type_fldsp,
+                ),
+                InstructionFilter::new(
+                    "c.fsdsp",
+                    inst::MASK_C_FSDSP,
+                    inst::MATCH_C_FSDSP,
+                    decode_css_type_fsdsp,
+                ),
+                InstructionFilter::new(
+                    "c.fld",
+                    inst::MASK_C_FLD,
+                    inst::MATCH_C_FLD,
+                    decode_cl_type_fld,
+                ),
+                InstructionFilter::new(
+                    "c.fsd",
+                    inst::MASK_C_FSD,
+                    inst::MATCH_C_FSD,
+                    decode_cs_type_fsd,
+                ),
+            ]
+        } else {
+            vec![]
+        };
+
         let global_filters = vec![
             InstructionFilter::new(
                 "c.lwsp",
                 inst::MASK_C_LWSP,
                 inst::MATCH_C_LWSP,
-                fmt_ci_type_lwsp,
+                decode_ci_type_lwsp,
+            ),
+            InstructionFilter::new(
+                "c.swsp",
+                inst::MASK_C_SWSP,
+                inst::MATCH_C_SWSP,
+                decode_css_type_swsp,
+            ),
+            InstructionFilter::new("c.lw", inst::MASK_C_LW, inst::MATCH_C_LW, decode_cl_type_lw),
+            InstructionFilter::new("c.sw", inst::MASK_C_SW, inst::MATCH_C_SW, decode_cs_type_sw),
+            InstructionFilter::new(
+                "c.ebreak",
+                inst::MASK_C_EBREAK,
+                inst::MATCH_C_EBREAK,
+                decode_cr_type_no_args,
+            ),
+            InstructionFilter::new("c.j", inst::MASK_C_J, inst::MATCH_C_J, decode_cj_type),
+            InstructionFilter::new(
+                "c.jr",
+                inst::MASK_C_JR,
+                inst::MATCH_C_JR,
+                decode_cr_type_no_rs2,
+            ),
+            // `c.jalr` must come after `c.ebreak`, otherwise the `c.jalr` filter will match any
+            // `c.ebreak` instructions.
+            InstructionFilter::new(
+                "c.jalr",
+                inst::MASK_C_JALR,
+                inst::MATCH_C_JALR,
+                decode_cr_type_no_rs2,
+            ),
+            InstructionFilter::new(
+                "c.beqz",
+                inst::MASK_C_BEQZ,
+                inst::MATCH_C_BEQZ,
+                decode_cb_type,
+            ),
+            InstructionFilter::new(
+                "c.bnez",
+                inst::MASK_C_BNEZ,
+                inst::MATCH_C_BNEZ,
+                decode_cb_type,
+            ),
+            InstructionFilter::new("c.li", inst::MASK_C_LI, inst::MATCH_C_LI, decode_ci_type),
+            InstructionFilter::new(
+                "c.nop",
+                inst::MASK_C_NOP,
+                inst::MATCH_C_NOP,
+                decode_ci_type_no_args,
+            ),
+            // `c.addi` must come after `c.nop`, otherwise the `c.addi` filter will match any
+            // `c.nop` instructions.
+            InstructionFilter::new(
+                "c.addi",
+                inst::MASK_C_ADDI,
+                inst::MATCH_C_ADDI,
+                decode_ci_type,
+            ),
+            InstructionFilter::new(
+                "c.addi16sp",
+                inst::MASK_C_ADDI16SP,
+                inst::MATCH_C_ADDI16SP,
+                decode_ci_type_addi16sp,
+            ),
+            InstructionFilter::new(
+                "c.addi4spn",
+                inst::MASK_C_ADDI4SPN,
+                inst::MATCH_C_ADDI4SPN,
+                decode_ciw_type_addi4spn,
+            ),
+            InstructionFilter::new(
+                "c.slli",
+                inst::MASK_C_SLLI,
+                inst::MATCH_C_SLLI,
+                decode_ci_type_shift,
+            ),
+            InstructionFilter::new(
+                "c.srli",
+                inst::MASK_C_SRLI,
+                inst::MATCH_C_SRLI,
+                decode_cb_type_shift,
+            ),
+            InstructionFilter::new(
+                "c.srai",
+                inst::MASK_C_SRAI,
+                inst::MATCH_C_SRAI,
+                decode_cb_type_shift,
+            ),
+            InstructionFilter::new(
+                "c.andi",
+                inst::MASK_C_ANDI,
+                inst::MATCH_C_ANDI,
+                decode_cb_type_andi,
+            ),
+            // `c.mv` must come after `c.jr`, otherwise the `c.mv` filter will match any `c.jr`
+            // instructions.
+            InstructionFilter::new("c.mv", inst::MASK_C_MV, inst::MATCH_C_MV, decode_cr_type),
+            // `c.add` must come after `c.jalr` and `c.ebreak`, otherwise the `c.add` filter will
+            // match any `c.jalr`/`c.ebreak` instructions.
+            InstructionFilter::new("c.add", inst::MASK_C_ADD, inst::MATCH_C_ADD, decode_cr_type),
+            InstructionFilter::new("c.and", inst::MASK_C_AND, inst::MATCH_C_AND, decode_ca_type),
+            InstructionFilter::new("c.or", inst::MASK_C_OR, inst::MATCH_C_OR, decode_ca_type),
+            InstructionFilter::new("c.xor", inst::MASK_C_XOR, inst::MATCH_C_XOR, decode_ca_type),
+            InstructionFilter::new("c.sub", inst::MASK_C_SUB, inst::MATCH_C_SUB, decode_ca_type),
+            // `c.lui` must come after `c.addi16sp`, otherwise the `c.lui` filter will match any
+            // `c.addi16sp` instructions.
+            InstructionFilter::new(
+                "c.lui",
+                inst::MASK_C_LUI,
+                inst::MATCH_C_LUI,
+                decode_ci_type_lui,
+            ),
+        ];
+
+        pseudo_instruction_filters.extend(xlen_filters);
+        pseudo_instruction_filters.extend(with_extension(d_filters, Extensions::C | Extensions::D));
+        pseudo_instruction_filters.extend(global_filters);
+        pseudo_instruction_filters
+    } else {
+        vec![]
+    };
+
+    // H extension, hypervisor instructions
+    let h_inst_filters = if isa_extensions.has_h() {
+        let mut xlen_filters = match xlen {
+            Xlen::Rv32 => vec![],
+            Xlen::Rv64 => vec![
+                InstructionFilter::new(
+                    "hlv.wu",
+                    inst::MASK_HLV_WU,
+                    inst::MATCH_HLV_WU,
+                    decode_hyp_load,
+                ),
+                InstructionFilter::new(
+                    "hlv.d",
+                    inst::MASK_HLV_D,
+                    inst::MATCH_HLV_D,
+                    decode_hyp_load,
+                ),
+                InstructionFilter::new(
+                    "hsv.d",
+                    inst::MASK_HSV_D,
+                    inst::MATCH_HSV_D,
+                    decode_hyp_store,
+                ),
+            ],
+        };
+
+        let global_filters = vec![
+            InstructionFilter::new(
+                "hfence.vvma",
+                inst::MASK_HFENCE_VVMA,
+                inst::MATCH_HFENCE_VVMA,
+                decode_rs1_rs2,
+            ),
+            InstructionFilter::new(
+                "hfence.gvma",
+                inst::MASK_HFENCE_GVMA,
+                inst::MATCH_HFENCE_GVMA,
+                decode_rs1_rs2,
+            ),
+            InstructionFilter::new(
+                "hlv.b",
+                inst::MASK_HLV_B,
+                inst::MATCH_HLV_B,
+                decode_hyp_load,
+            ),
+            InstructionFilter::new(
+                "hlv.bu",
+                inst::MASK_HLV_BU,
+                inst::MATCH_HLV_BU,
+                decode_hyp_load,
+            ),
+            InstructionFilter::new(
+                "hlv.h",
+                inst::MASK_HLV_H,
+                inst::MATCH_HLV_H,
+                decode_hyp_load,
+            ),
+            InstructionFilter::new(
+                "hlv.hu",
+                inst::MASK_HLV_HU,
+                inst::MATCH_HLV_HU,
+                decode_hyp_load,
+            ),
+            InstructionFilter::new(
+                "hlvx.hu",
+                inst::MASK_HLVX_HU,
+                inst::MATCH_HLVX_HU,
+                decode_hyp_load,
+            ),
+            InstructionFilter::new(
+                "hlv.w",
+                inst::MASK_HLV_W,
+                inst::MATCH_HLV_W,
+                decode_hyp_load,
+            ),
+            InstructionFilter::new(
+                "hlvx.wu",
+                inst::MASK_HLVX_WU,
+                inst::MATCH_HLVX_WU,
+                decode_hyp_load,
+            ),
+            InstructionFilter::new(
+                "hsv.b",
+                inst::MASK_HSV_B,
+                inst::MATCH_HSV_B,
+                decode_hyp_store,
+            ),
+            InstructionFilter::new(
+                "hsv.h",
+                inst::MASK_HSV_H,
+                inst::MATCH_HSV_H,
+                decode_hyp_store,
+            ),
+            InstructionFilter::new(
+                "hsv.w",
+                inst::MASK_HSV_W,
+                inst::MATCH_HSV_W,
+                decode_hyp_store,
+            ),
+        ];
+
+        xlen_filters.extend(global_filters);
+        xlen_filters
+    } else {
+        vec![]
+    };
+
+    // V extension, vector instructions
+    //
+    // Some of the opcode constants use their pre-1.0 names (e.g. `VLXEI8_V`, `VPOPC_M`), but the
+    // mnemonics follow version 1.0 of the vector spec, like spike does.
+    let v_inst_filters = if isa_extensions.has_v() {
+        vec![
+            // Configuration-setting
+            InstructionFilter::new(
+                "vsetvli",
+                inst::MASK_VSETVLI,
+                inst::MATCH_VSETVLI,
+                decode_vsetvli,
+            ),
+            InstructionFilter::new(
+                "vsetivli",
+                inst::MASK_VSETIVLI,
+                inst::MATCH_VSETIVLI,
+                decode_vsetivli,
+            ),
+            InstructionFilter::new(
+                "vsetvl",
+                inst::MASK_VSETVL,
+                inst::MATCH_VSETVL,
+                decode_vsetvl,
+            ),
+            // Unit-stride, strided and indexed loads/stores, including segment variants
+            InstructionFilter::new(
+                "vle8.v",
+                inst::MASK_VLE8_V,
+                inst::MATCH_VLE8_V,
+                decode_v_mem_unit,
+            ),
+            InstructionFilter::new(
+                "vse8.v",
+                inst::MASK_VSE8_V,
+                inst::MATCH_VSE8_V,
+                decode_v_mem_unit,
+            ),
+            InstructionFilter::new(
+                "vle8ff.v",
+                inst::MASK_VLE8FF_V,
+                inst::MATCH_VLE8FF_V,
+                decode_v_mem_unit,
+            ),
+            InstructionFilter::new(
+                "vlse8.v",
+                inst::MASK_VLSE8_V,
+                inst::MATCH_VLSE8_V,
+                decode_v_mem_strided,
+            ),
+            InstructionFilter::new(
+                "vsse8.v",
+                inst::MASK_VSSE8_V,
+                inst::MATCH_VSSE8_V,
+                decode_v_mem_strided,
+            ),
+            InstructionFilter::new(
+                "vluxei8.v",
+                inst::MASK_VLUXEI8_V,
+                inst::MATCH_VLUXEI8_V,
+                decode_v_mem_indexed,
+            ),
+            InstructionFilter::new(
+                "vloxei8.v",
+                inst::MASK_VLXEI8_V,
+                inst::MATCH_VLXEI8_V,
+                decode_v_mem_indexed,
+            ),
+            InstructionFilter::new(
+                "vsuxei8.v",
+                inst::MASK_VSUXEI8_V,
+                inst::MATCH_VSUXEI8_V,
+                decode_v_mem_indexed,
+            ),
+            InstructionFilter::new(
+                "vsoxei8.v",
+                inst::MASK_VSXEI8_V,
+                inst::MATCH_VSXEI8_V,
+                decode_v_mem_indexed,
+            ),
+            InstructionFilter::new(
+                "vle16.v",
+                inst::MASK_VLE16_V,
+                inst::MATCH_VLE16_V,
+                decode_v_mem_unit,
+            ),
+            InstructionFilter::new(
+                "vse16.v",
+                inst::MASK_VSE16_V,
+                inst::MATCH_VSE16_V,
+                decode_v_mem_unit,
+            ),
+            InstructionFilter::new(
+                "vle16ff.v",
+                inst::MASK_VLE16FF_V,
+                inst::MATCH_VLE16FF_V,
+                decode_v_mem_unit,
+            ),
+            InstructionFilter::new(
+                "vlse16.v",
+                inst::MASK_VLSE16_V,
+                inst::MATCH_VLSE16_V,
+                decode_v_mem_strided,
+            ),
+            InstructionFilter::new(
+                "vsse16.v",
+                inst::MASK_VSSE16_V,
+                inst::MATCH_VSSE16_V,
+                decode_v_mem_strided,
+            ),
+            InstructionFilter::new(
+                "vluxei16.v",
+                inst::MASK_VLUXEI16_V,
+                inst::MATCH_VLUXEI16_V,
+                decode_v_mem_indexed,
+            ),
+            InstructionFilter::new(
+                "vloxei16.v",
+                inst::MASK_VLXEI16_V,
+                inst::MATCH_VLXEI16_V,
+                decode_v_mem_indexed,
+            ),
+            InstructionFilter::new(
+                "vsuxei16.v",
+                inst::MASK_VSUXEI16_V,
+                inst::MATCH_VSUXEI16_V,
+                decode_v_mem_indexed,
+            ),
+            InstructionFilter::new(
+                "vsoxei16.v",
+                inst::MASK_VSXEI16_V,
+                inst::MATCH_VSXEI16_V,
+                decode_v_mem_indexed,
+            ),
+            InstructionFilter::new(
+                "vle32.v",
+                inst::MASK_VLE32_V,
+                inst::MATCH_VLE32_V,
+                decode_v_mem_unit,
+            ),
+            InstructionFilter::new(
+                "vse32.v",
+                inst::MASK_VSE32_V,
+                inst::MATCH_VSE32_V,
+                decode_v_mem_unit,
+            ),
+            InstructionFilter::new(
+                "vle32ff.v",
+                inst::MASK_VLE32FF_V,
+                inst::MATCH_VLE32FF_V,
+                decode_v_mem_unit,
+            ),
+            InstructionFilter::new(
+                "vlse32.v",
+                inst::MASK_VLSE32_V,
+                inst::MATCH_VLSE32_V,
+                decode_v_mem_strided,
+            ),
+            InstructionFilter::new(
+                "vsse32.v",
+                inst::MASK_VSSE32_V,
+                inst::MATCH_VSSE32_V,
+                decode_v_mem_strided,
+            ),
+            InstructionFilter::new(
+                "vluxei32.v",
+                inst::MASK_VLUXEI32_V,
+                inst::MATCH_VLUXEI32_V,
+                decode_v_mem_indexed,
+            ),
+            InstructionFilter::new(
+                "vloxei32.v",
+                inst::MASK_VLXEI32_V,
+                inst::MATCH_VLXEI32_V,
+                decode_v_mem_indexed,
+            ),
+            InstructionFilter::new(
+                "vsuxei32.v",
+                inst::MASK_VSUXEI32_V,
+                inst::MATCH_VSUXEI32_V,
+                decode_v_mem_indexed,
+            ),
+            InstructionFilter::new(
+                "vsoxei32.v",
+                inst::MASK_VSXEI32_V,
+                inst::MATCH_VSXEI32_V,
+                decode_v_mem_indexed,
+            ),
+            InstructionFilter::new(
+                "vle64.v",
+                inst::MASK_VLE64_V,
+                inst::MATCH_VLE64_V,
+                decode_v_mem_unit,
+            ),
+            InstructionFilter::new(
+                "vse64.v",
+                inst::MASK_VSE64_V,
+                inst::MATCH_VSE64_V,
+                decode_v_mem_unit,
+            ),
+            InstructionFilter::new(
+                "vle64ff.v",
+                inst::MASK_VLE64FF_V,
+                inst::MATCH_VLE64FF_V,
+                decode_v_mem_unit,
+            ),
+            InstructionFilter::new(
+                "vlse64.v",
+                inst::MASK_VLSE64_V,
+                inst::MATCH_VLSE64_V,
+                decode_v_mem_strided,
+            ),
+            InstructionFilter::new(
+                "vsse64.v",
+                inst::MASK_VSSE64_V,
+                inst::MATCH_VSSE64_V,
+                decode_v_mem_strided,
+            ),
+            InstructionFilter::new(
+                "vluxei64.v",
+                inst::MASK_VLUXEI64_V,
+                inst::MATCH_VLUXEI64_V,
+                decode_v_mem_indexed,
+            ),
+            InstructionFilter::new(
+                "vloxei64.v",
+                inst::MASK_VLXEI64_V,
+                inst::MATCH_VLXEI64_V,
+                decode_v_mem_indexed,
+            ),
+            InstructionFilter::new(
+                "vsuxei64.v",
+                inst::MASK_VSUXEI64_V,
+                inst::MATCH_VSUXEI64_V,
+                decode_v_mem_indexed,
+            ),
+            InstructionFilter::new(
+                "vsoxei64.v",
+                inst::MASK_VSXEI64_V,
+                inst::MATCH_VSXEI64_V,
+                decode_v_mem_indexed,
+            ),
+            // Mask and whole-register loads/stores
+            InstructionFilter::new(
+                "vlm.v",
+                inst::MASK_VLM_V,
+                inst::MATCH_VLM_V,
+                decode_v_mem_whole,
+            ),
+            InstructionFilter::new(
+                "vsm.v",
+                inst::MASK_VSM_V,
+                inst::MATCH_VSM_V,
+                decode_v_mem_whole,
+            ),
+            InstructionFilter::new(
+                "vl1re8.v",
+                inst::MASK_VL1RE8_V,
+                inst::MATCH_VL1RE8_V,
+                decode_v_mem_whole,
+            ),
+            InstructionFilter::new(
+                "vl1re16.v",
+                inst::MASK_VL1RE16_V,
+                inst::MATCH_VL1RE16_V,
+                decode_v_mem_whole,
+            ),
+            InstructionFilter::new(
+                "vl1re32.v",
+                inst::MASK_VL1RE32_V,
+                inst::MATCH_VL1RE32_V,
+                decode_v_mem_whole,
+            ),
+            InstructionFilter::new(
+                "vl1re64.v",
+                inst::MASK_VL1RE64_V,
+                inst::MATCH_VL1RE64_V,
+                decode_v_mem_whole,
+            ),
+            InstructionFilter::new(
+                "vl2re8.v",
+                inst::MASK_VL2RE8_V,
+                inst::MATCH_VL2RE8_V,
+                decode_v_mem_whole,
+            ),
+            InstructionFilter::new(
+                "vl2re16.v",
+                inst::MASK_VL2RE16_V,
+                inst::MATCH_VL2RE16_V,
+                decode_v_mem_whole,
+            ),
+            InstructionFilter::new(
+                "vl2re32.v",
+                inst::MASK_VL2RE32_V,
+                inst::MATCH_VL2RE32_V,
+                decode_v_mem_whole,
+            ),
+            InstructionFilter::new(
+                "vl2re64.v",
+                inst::MASK_VL2RE64_V,
+                inst::MATCH_VL2RE64_V,
+                decode_v_mem_whole,
+            ),
+            InstructionFilter::new(
+                "vl4re8.v",
+                inst::MASK_VL4RE8_V,
+                inst::MATCH_VL4RE8_V,
+                decode_v_mem_whole,
+            ),
+            InstructionFilter::new(
+                "vl4re16.v",
+                inst::MASK_VL4RE16_V,
+                inst::MATCH_VL4RE16_V,
+                decode_v_mem_whole,
+            ),
+            InstructionFilter::new(
+                "vl4re32.v",
+                inst::MASK_VL4RE32_V,
+                inst::MATCH_VL4RE32_V,
+                decode_v_mem_whole,
+            ),
+            InstructionFilter::new(
+                "vl4re64.v",
+                inst::MASK_VL4RE64_V,
+                inst::MATCH_VL4RE64_V,
+                decode_v_mem_whole,
+            ),
+            InstructionFilter::new(
+                "vl8re8.v",
+                inst::MASK_VL8RE8_V,
+                inst::MATCH_VL8RE8_V,
+                decode_v_mem_whole,
+            ),
+            InstructionFilter::new(
+                "vl8re16.v",
+                inst::MASK_VL8RE16_V,
+                inst::MATCH_VL8RE16_V,
+                decode_v_mem_whole,
+            ),
+            InstructionFilter::new(
+                "vl8re32.v",
+                inst::MASK_VL8RE32_V,
+                inst::MATCH_VL8RE32_V,
+                decode_v_mem_whole,
+            ),
+            InstructionFilter::new(
+                "vl8re64.v",
+                inst::MASK_VL8RE64_V,
+                inst::MATCH_VL8RE64_V,
+                decode_v_mem_whole,
+            ),
+            InstructionFilter::new(
+                "vs1r.v",
+                inst::MASK_VS1R_V,
+                inst::MATCH_VS1R_V,
+                decode_v_mem_whole,
+            ),
+            InstructionFilter::new(
+                "vs2r.v",
+                inst::MASK_VS2R_V,
+                inst::MATCH_VS2R_V,
+                decode_v_mem_whole,
+            ),
+            InstructionFilter::new(
+                "vs4r.v",
+                inst::MASK_VS4R_V,
+                inst::MATCH_VS4R_V,
+                decode_v_mem_whole,
+            ),
+            InstructionFilter::new(
+                "vs8r.v",
+                inst::MASK_VS8R_V,
+                inst::MATCH_VS8R_V,
+                decode_v_mem_whole,
+            ),
+            // Integer arithmetic
+            InstructionFilter::new(
+                "vadd.vv",
+                inst::MASK_VADD_VV,
+                inst::MATCH_VADD_VV,
+                decode_v_vv,
+            ),
+            InstructionFilter::new(
+                "vadd.vx",
+                inst::MASK_VADD_VX,
+                inst::MATCH_VADD_VX,
+                decode_v_vx,
+            ),
+            InstructionFilter::new(
+                "vadd.vi",
+                inst::MASK_VADD_VI,
+                inst::MATCH_VADD_VI,
+                decode_v_vi,
+            ),
+            InstructionFilter::new(
+                "vsub.vv",
+                inst::MASK_VSUB_VV,
+                inst::MATCH_VSUB_VV,
+                decode_v_vv,
+            ),
+            InstructionFilter::new(
+                "vsub.vx",
+                inst::MASK_VSUB_VX,
+                inst::MATCH_VSUB_VX,
+                decode_v_vx,
+            ),
+            InstructionFilter::new(
+                "vrsub.vx",
+                inst::MASK_VRSUB_VX,
+                inst::MATCH_VRSUB_VX,
+                decode_v_vx,
+            ),
+            InstructionFilter::new(
+                "vrsub.vi",
+                inst::MASK_VRSUB_VI,
+                inst::MATCH_VRSUB_VI,
+                decode_v_vi,
+            ),
+            InstructionFilter::new(
+                "vwaddu.vv",
+                inst::MASK_VWADDU_VV,
+                inst::MATCH_VWADDU_VV,
+                decode_v_vv,
+            ),
+            InstructionFilter::new(
+                "vwaddu.vx",
+                inst::MASK_VWADDU_VX,
+                inst::MATCH_VWADDU_VX,
+                decode_v_vx,
+            ),
+            InstructionFilter::new(
+                "vwaddu.wv",
+                inst::MASK_VWADDU_WV,
+                inst::MATCH_VWADDU_WV,
+                decode_v_vv,
+            ),
+            InstructionFilter::new(
+                "vwaddu.wx",
+                inst::MASK_VWADDU_WX,
+                inst::MATCH_VWADDU_WX,
+                decode_v_vx,
+            ),
+            InstructionFilter::new(
+                "vwadd.vv",
+                inst::MASK_VWADD_VV,
+                inst::MATCH_VWADD_VV,
+                decode_v_vv,
+            ),
+            InstructionFilter::new(
+                "vwadd.vx",
+                inst::MASK_VWADD_VX,
+                inst::MATCH_VWADD_VX,
+                decode_v_vx,
+            ),
+            InstructionFilter::new(
+                "vwadd.wv",
+                inst::MASK_VWADD_WV,
+                inst::MATCH_VWADD_WV,
+                decode_v_vv,
+            ),
+            InstructionFilter::new(
+                "vwadd.wx",
+                inst::MASK_VWADD_WX,
+                inst::MATCH_VWADD_WX,
+                decode_v_vx,
+            ),
+            InstructionFilter::new(
+                "vwsubu.vv",
+                inst::MASK_VWSUBU_VV,
+                inst::MATCH_VWSUBU_VV,
+                decode_v_vv,
+            ),
+            InstructionFilter::new(
+                "vwsubu.vx",
+                inst::MASK_VWSUBU_VX,
+                inst::MATCH_VWSUBU_VX,
+                decode_v_vx,
+            ),
+            InstructionFilter::new(
+                "vwsubu.wv",
+                inst::MASK_VWSUBU_WV,
+                inst::MATCH_VWSUBU_WV,
+                decode_v_vv,
+            ),
+            InstructionFilter::new(
+                "vwsubu.wx",
+                inst::MASK_VWSUBU_WX,
+                inst::MATCH_VWSUBU_WX,
+                decode_v_vx,
+            ),
+            InstructionFilter::new(
+                "vwsub.vv",
+                inst::MASK_VWSUB_VV,
+                inst::MATCH_VWSUB_VV,
+                decode_v_vv,
+            ),
+            InstructionFilter::new(
+                "vwsub.vx",
+                inst::MASK_VWSUB_VX,
+                inst::MATCH_VWSUB_VX,
+                decode_v_vx,
+            ),
+            InstructionFilter::new(
+                "vwsub.wv",
+                inst::MASK_VWSUB_WV,
+                inst::MATCH_VWSUB_WV,
+                decode_v_vv,
+            ),
+            InstructionFilter::new(
+                "vwsub.wx",
+                inst::MASK_VWSUB_WX,
+                inst::MATCH_VWSUB_WX,
+                decode_v_vx,
+            ),
+            InstructionFilter::new(
+                "vzext.vf2",
+                inst::MASK_VZEXT_VF2,
+                inst::MATCH_VZEXT_VF2,
+                decode_v_unary,
+            ),
+            InstructionFilter::new(
+                "vzext.vf4",
+                inst::MASK_VZEXT_VF4,
+                inst::MATCH_VZEXT_VF4,
+                decode_v_unary,
+            ),
+            InstructionFilter::new(
+                "vzext.vf8",
+                inst::MASK_VZEXT_VF8,
+                inst::MATCH_VZEXT_VF8,
+                decode_v_unary,
+            ),
+            InstructionFilter::new(
+                "vsext.vf2",
+                inst::MASK_VSEXT_VF2,
+                inst::MATCH_VSEXT_VF2,
+                decode_v_unary,
+            ),
+            InstructionFilter::new(
+                "vsext.vf4",
+                inst::MASK_VSEXT_VF4,
+                inst::MATCH_VSEXT_VF4,
+                decode_v_unary,
+            ),
+            InstructionFilter::new(
+                "vsext.vf8",
+                inst::MASK_VSEXT_VF8,
+                inst::MATCH_VSEXT_VF8,
+                decode_v_unary,
+            ),
+            InstructionFilter::new(
+                "vadc.vvm",
+                inst::MASK_VADC_VVM,
+                inst::MATCH_VADC_VVM,
+                decode_v_vvm,
+            ),
+            InstructionFilter::new(
+                "vadc.vxm",
+                inst::MASK_VADC_VXM,
+                inst::MATCH_VADC_VXM,
+                decode_v_vxm,
+            ),
+            InstructionFilter::new(
+                "vadc.vim",
+                inst::MASK_VADC_VIM,
+                inst::MATCH_VADC_VIM,
+                decode_v_vim,
+            ),
+            InstructionFilter::new(
+                "vmadc.vvm",
+                inst::MASK_VMADC_VVM | registers::MASK_V_VM,
+                inst::MATCH_VMADC_VVM,
+                decode_v_vvm,
+            ),
+            InstructionFilter::new(
+                "vmadc.vv",
+                inst::MASK_VMADC_VVM | registers::MASK_V_VM,
+                inst::MATCH_VMADC_VVM | registers::MATCH_V_VM_UNMASKED,
+                decode_v_vv,
+            ),
+            InstructionFilter::new(
+                "vmadc.vxm",
+                inst::MASK_VMADC_VXM | registers::MASK_V_VM,
+                inst::MATCH_VMADC_VXM,
+                decode_v_vxm,
+            ),
+            InstructionFilter::new(
+                "vmadc.vx",
+                inst::MASK_VMADC_VXM | registers::MASK_V_VM,
+                inst::MATCH_VMADC_VXM | registers::MATCH_V_VM_UNMASKED,
+                decode_v_vx,
+            ),
+            InstructionFilter::new(
+                "vmadc.vim",
+                inst::MASK_VMADC_VIM | registers::MASK_V_VM,
+                inst::MATCH_VMADC_VIM,
+                decode_v_vim,
+            ),
+            InstructionFilter::new(
+                "vmadc.vi",
+                inst::MASK_VMADC_VIM | registers::MASK_V_VM,
+                inst::MATCH_VMADC_VIM | registers::MATCH_V_VM_UNMASKED,
+                decode_v_vi,
+            ),
+            InstructionFilter::new(
+                "vsbc.vvm",
+                inst::MASK_VSBC_VVM,
+                inst::MATCH_VSBC_VVM,
+                decode_v_vvm,
+            ),
+            InstructionFilter::new(
+                "vsbc.vxm",
+                inst::MASK_VSBC_VXM,
+                inst::MATCH_VSBC_VXM,
+                decode_v_vxm,
+            ),
+            InstructionFilter::new(
+                "vmsbc.vvm",
+                inst::MASK_VMSBC_VVM | registers::MASK_V_VM,
+                inst::MATCH_VMSBC_VVM,
+                decode_v_vvm,
+            ),
+            InstructionFilter::new(
+                "vmsbc.vv",
+                inst::MASK_VMSBC_VVM | registers::MASK_V_VM,
+                inst::MATCH_VMSBC_VVM | registers::MATCH_V_VM_UNMASKED,
+                decode_v_vv,
+            ),
+            InstructionFilter::new(
+                "vmsbc.vxm",
+                inst::MASK_VMSBC_VXM | registers::MASK_V_VM,
+                inst::MATCH_VMSBC_VXM,
+                decode_v_vxm,
+            ),
+            InstructionFilter::new(
+                "vmsbc.vx",
+                inst::MASK_VMSBC_VXM | registers::MASK_V_VM,
+                inst::MATCH_VMSBC_VXM | registers::MATCH_V_VM_UNMASKED,
+                decode_v_vx,
+            ),
+            InstructionFilter::new(
+                "vand.vv",
+                inst::MASK_VAND_VV,
+                inst::MATCH_VAND_VV,
+                decode_v_vv,
+            ),
+            InstructionFilter::new(
+                "vand.vx",
+                inst::MASK_VAND_VX,
+                inst::MATCH_VAND_VX,
+                decode_v_vx,
+            ),
+            InstructionFilter::new(
+                "vand.vi",
+                inst::MASK_VAND_VI,
+                inst::MATCH_VAND_VI,
+                decode_v_vi,
+            ),
+            InstructionFilter::new("vor.vv", inst::MASK_VOR_VV, inst::MATCH_VOR_VV, decode_v_vv),
+            InstructionFilter::new("vor.vx", inst::MASK_VOR_VX, inst::MATCH_VOR_VX, decode_v_vx),
+            InstructionFilter::new("vor.vi", inst::MASK_VOR_VI, inst::MATCH_VOR_VI, decode_v_vi),
+            InstructionFilter::new(
+                "vxor.vv",
+                inst::MASK_VXOR_VV,
+                inst::MATCH_VXOR_VV,
+                decode_v_vv,
+            ),
+            InstructionFilter::new(
+                "vxor.vx",
+                inst::MASK_VXOR_VX,
+                inst::MATCH_VXOR_VX,
+                decode_v_vx,
+            ),
+            InstructionFilter::new(
+                "vxor.vi",
+                inst::MASK_VXOR_VI,
+                inst::MATCH_VXOR_VI,
+                decode_v_vi,
+            ),
+            InstructionFilter::new(
+                "vsll.vv",
+                inst::MASK_VSLL_VV,
+                inst::MATCH_VSLL_VV,
+                decode_v_vv,
+            ),
+            InstructionFilter::new(
+                "vsll.vx",
+                inst::MASK_VSLL_VX,
+                inst::MATCH_VSLL_VX,
+                decode_v_vx,
+            ),
+            InstructionFilter::new(
+                "vsll.vi",
+                inst::MASK_VSLL_VI,
+                inst::MATCH_VSLL_VI,
+                decode_v_vi_unsigned,
+            ),
+            InstructionFilter::new(
+                "vsrl.vv",
+                inst::MASK_VSRL_VV,
+                inst::MATCH_VSRL_VV,
+                decode_v_vv,
+            ),
+            InstructionFilter::new(
+                "vsrl.vx",
+                inst::MASK_VSRL_VX,
+                inst::MATCH_VSRL_VX,
+                decode_v_vx,
+            ),
+            InstructionFilter::new(
+                "vsrl.vi",
+                inst::MASK_VSRL_VI,
+                inst::MATCH_VSRL_VI,
+                decode_v_vi_unsigned,
+            ),
+            InstructionFilter::new(
+                "vsra.vv",
+                inst::MASK_VSRA_VV,
+                inst::MATCH_VSRA_VV,
+                decode_v_vv,
+            ),
+            InstructionFilter::new(
+                "vsra.vx",
+                inst::MASK_VSRA_VX,
+                inst::MATCH_VSRA_VX,
+                decode_v_vx,
+            ),
+            InstructionFilter::new(
+                "vsra.vi",
+                inst::MASK_VSRA_VI,
+                inst::MATCH_VSRA_VI,
+                decode_v_vi_unsigned,
+            ),
+            InstructionFilter::new(
+                "vnsrl.wv",
+                inst::MASK_VNSRL_WV,
+                inst::MATCH_VNSRL_WV,
+                decode_v_vv,
+            ),
+            InstructionFilter::new(
+                "vnsrl.wx",
+                inst::MASK_VNSRL_WX,
+                inst::MATCH_VNSRL_WX,
+                decode_v_vx,
+            ),
+            InstructionFilter::new(
+                "vnsrl.wi",
+                inst::MASK_VNSRL_WI,
+                inst::MATCH_VNSRL_WI,
+                decode_v_vi_unsigned,
+            ),
+            InstructionFilter::new(
+                "vnsra.wv",
+                inst::MASK_VNSRA_WV,
+                inst::MATCH_VNSRA_WV,
+                decode_v_vv,
+            ),
+            InstructionFilter::new(
+                "vnsra.wx",
+                inst::MASK_VNSRA_WX,
+                inst::MATCH_VNSRA_WX,
+                decode_v_vx,
+            ),
+            InstructionFilter::new(
+                "vnsra.wi",
+                inst::MASK_VNSRA_WI,
+                inst::MATCH_VNSRA_WI,
+                decode_v_vi_unsigned,
+            ),
+            InstructionFilter::new(
+                "vmseq.vv",
+                inst::MASK_VMSEQ_VV,
+                inst::MATCH_VMSEQ_VV,
+                decode_v_vv,
+            ),
+            InstructionFilter::new(
+                "vmseq.vx",
+                inst::MASK_VMSEQ_VX,
+                inst::MATCH_VMSEQ_VX,
+                decode_v_vx,
+            ),
+            InstructionFilter::new(
+                "vmseq.vi",
+                inst::MASK_VMSEQ_VI,
+                inst::MATCH_VMSEQ_VI,
+                decode_v_vi,
+            ),
+            InstructionFilter::new(
+                "vmsne.vv",
+                inst::MASK_VMSNE_VV,
+                inst::MATCH_VMSNE_VV,
+                decode_v_vv,
+            ),
+            InstructionFilter::new(
+                "vmsne.vx",
+                inst::MASK_VMSNE_VX,
+                inst::MATCH_VMSNE_VX,
+                decode_v_vx,
+            ),
+            InstructionFilter::new(
+                "vmsne.vi",
+                inst::MASK_VMSNE_VI,
+                inst::MATCH_VMSNE_VI,
+                decode_v_vi,
+            ),
+            InstructionFilter::new(
+                "vmsltu.vv",
+                inst::MASK_VMSLTU_VV,
+                inst::MATCH_VMSLTU_VV,
+                decode_v_vv,
+            ),
+            InstructionFilter::new(
+                "vmsltu.vx",
+                inst::MASK_VMSLTU_VX,
+                inst::MATCH_VMSLTU_VX,
+                decode_v_vx,
+            ),
+            InstructionFilter::new(
+                "vmslt.vv",
+                inst::MASK_VMSLT_VV,
+                inst::MATCH_VMSLT_VV,
+                decode_v_vv,
+            ),
+            InstructionFilter::new(
+                "vmslt.vx",
+                inst::MASK_VMSLT_VX,
+                inst::MATCH_VMSLT_VX,
+                decode_v_vx,
+            ),
+            InstructionFilter::new(
+                "vmsleu.vv",
+                inst::MASK_VMSLEU_VV,
+                inst::MATCH_VMSLEU_VV,
+                decode_v_vv,
+            ),
+            InstructionFilter::new(
+                "vmsleu.vx",
+                inst::MASK_VMSLEU_VX,
+                inst::MATCH_VMSLEU_VX,
+                decode_v_vx,
+            ),
+            InstructionFilter::new(
+                "vmsleu.vi",
+                inst::MASK_VMSLEU_VI,
+                inst::MATCH_VMSLEU_VI,
+                decode_v_vi,
+            ),
+            InstructionFilter::new(
+                "vmsle.vv",
+                inst::MASK_VMSLE_VV,
+                inst::MATCH_VMSLE_VV,
+                decode_v_vv,
+            ),
+            InstructionFilter::new(
+                "vmsle.vx",
+                inst::MASK_VMSLE_VX,
+                inst::MATCH_VMSLE_VX,
+                decode_v_vx,
+            ),
+            InstructionFilter::new(
+                "vmsle.vi",
+                inst::MASK_VMSLE_VI,
+                inst::MATCH_VMSLE_VI,
+                decode_v_vi,
+            ),
+            InstructionFilter::new(
+                "vmsgtu.vx",
+                inst::MASK_VMSGTU_VX,
+                inst::MATCH_VMSGTU_VX,
+                decode_v_vx,
+            ),
+            InstructionFilter::new(
+                "vmsgtu.vi",
+                inst::MASK_VMSGTU_VI,
+                inst::MATCH_VMSGTU_VI,
+                decode_v_vi,
+            ),
+            InstructionFilter::new(
+                "vmsgt.vx",
+                inst::MASK_VMSGT_VX,
+                inst::MATCH_VMSGT_VX,
+                decode_v_vx,
+            ),
+            InstructionFilter::new(
+                "vmsgt.vi",
+                inst::MASK_VMSGT_VI,
+                inst::MATCH_VMSGT_VI,
+                decode_v_vi,
+            ),
+            InstructionFilter::new(
+                "vminu.vv",
+                inst::MASK_VMINU_VV,
+                inst::MATCH_VMINU_VV,
+                decode_v_vv,
+            ),
+            InstructionFilter::new(
+                "vminu.vx",
+                inst::MASK_VMINU_VX,
+                inst::MATCH_VMINU_VX,
+                decode_v_vx,
+            ),
+            InstructionFilter::new(
+                "vmin.vv",
+                inst::MASK_VMIN_VV,
+                inst::MATCH_VMIN_VV,
+                decode_v_vv,
+            ),
+            InstructionFilter::new(
+                "vmin.vx",
+                inst::MASK_VMIN_VX,
+                inst::MATCH_VMIN_VX,
+                decode_v_vx,
+            ),
+            InstructionFilter::new(
+                "vmaxu.vv",
+                inst::MASK_VMAXU_VV,
+                inst::MATCH_VMAXU_VV,
+                decode_v_vv,
+            ),
+            InstructionFilter::new(
+                "vmaxu.vx",
+                inst::MASK_VMAXU_VX,
+                inst::MATCH_VMAXU_VX,
+                decode_v_vx,
+            ),
+            InstructionFilter::new(
+                "vmax.vv",
+                inst::MASK_VMAX_VV,
+                inst::MATCH_VMAX_VV,
+                decode_v_vv,
+            ),
+            InstructionFilter::new(
+                "vmax.vx",
+                inst::MASK_VMAX_VX,
+                inst::MATCH_VMAX_VX,
+                decode_v_vx,
+            ),
+            InstructionFilter::new(
+                "vmul.vv",
+                inst::MASK_VMUL_VV,
+                inst::MATCH_VMUL_VV,
+                decode_v_vv,
+            ),
+            InstructionFilter::new(
+                "vmul.vx",
+                inst::MASK_VMUL_VX,
+                inst::MATCH_VMUL_VX,
+                decode_v_vx,
+            ),
+            InstructionFilter::new(
+                "vmulh.vv",
+                inst::MASK_VMULH_VV,
+                inst::MATCH_VMULH_VV,
+                decode_v_vv,
+            ),
+            InstructionFilter::new(
+                "vmulh.vx",
+                inst::MASK_VMULH_VX,
+                inst::MATCH_VMULH_VX,
+                decode_v_vx,
+            ),
+            InstructionFilter::new(
+                "vmulhu.vv",
+                inst::MASK_VMULHU_VV,
+                inst::MATCH_VMULHU_VV,
+                decode_v_vv,
+            ),
+            InstructionFilter::new(
+                "vmulhu.vx",
+                inst::MASK_VMULHU_VX,
+                inst::MATCH_VMULHU_VX,
+                decode_v_vx,
+            ),
+            InstructionFilter::new(
+                "vmulhsu.vv",
+                inst::MASK_VMULHSU_VV,
+                inst::MATCH_VMULHSU_VV,
+                decode_v_vv,
+            ),
+            InstructionFilter::new(
+                "vmulhsu.vx",
+                inst::MASK_VMULHSU_VX,
+                inst::MATCH_VMULHSU_VX,
+                decode_v_vx,
+            ),
+            InstructionFilter::new(
+                "vdivu.vv",
+                inst::MASK_VDIVU_VV,
+                inst::MATCH_VDIVU_VV,
+                decode_v_vv,
+            ),
+            InstructionFilter::new(
+                "vdivu.vx",
+                inst::MASK_VDIVU_VX,
+                inst::MATCH_VDIVU_VX,
+                decode_v_vx,
+            ),
+            InstructionFilter::new(
+                "vdiv.vv",
+                inst::MASK_VDIV_VV,
+                inst::MATCH_VDIV_VV,
+                decode_v_vv,
+            ),
+            InstructionFilter::new(
+                "vdiv.vx",
+                inst::MASK_VDIV_VX,
+                inst::MATCH_VDIV_VX,
+                decode_v_vx,
+            ),
+            InstructionFilter::new(
+                "vremu.vv",
+                inst::MASK_VREMU_VV,
+                inst::MATCH_VREMU_VV,
+                decode_v_vv,
+            ),
+            InstructionFilter::new(
+                "vremu.vx",
+                inst::MASK_VREMU_VX,
+                inst::MATCH_VREMU_VX,
+                decode_v_vx,
+            ),
+            InstructionFilter::new(
+                "vrem.vv",
+                inst::MASK_VREM_VV,
+                inst::MATCH_VREM_VV,
+                decode_v_vv,
+            ),
+            InstructionFilter::new(
+                "vrem.vx",
+                inst::MASK_VREM_VX,
+                inst::MATCH_VREM_VX,
+                decode_v_vx,
+            ),
+            InstructionFilter::new(
+                "vwmul.vv",
+                inst::MASK_VWMUL_VV,
+                inst::MATCH_VWMUL_VV,
+                decode_v_vv,
+            ),
+            InstructionFilter::new(
+                "vwmul.vx",
+                inst::MASK_VWMUL_VX,
+                inst::MATCH_VWMUL_VX,
+                decode_v_vx,
+            ),
+            InstructionFilter::new(
+                "vwmulu.vv",
+                inst::MASK_VWMULU_VV,
+                inst::MATCH_VWMULU_VV,
+                decode_v_vv,
+            ),
+            InstructionFilter::new(
+                "vwmulu.vx",
+                inst::MASK_VWMULU_VX,
+                inst::MATCH_VWMULU_VX,
+                decode_v_vx,
+            ),
+            InstructionFilter::new(
+                "vwmulsu.vv",
+                inst::MASK_VWMULSU_VV,
+                inst::MATCH_VWMULSU_VV,
+                decode_v_vv,
+            ),
+            InstructionFilter::new(
+                "vwmulsu.vx",
+                inst::MASK_VWMULSU_VX,
+                inst::MATCH_VWMULSU_VX,
+                decode_v_vx,
+            ),
+            InstructionFilter::new(
+                "vmacc.vv",
+                inst::MASK_VMACC_VV,
+                inst::MATCH_VMACC_VV,
+                decode_v_vv_madd,
+            ),
+            InstructionFilter::new(
+                "vmacc.vx",
+                inst::MASK_VMACC_VX,
+                inst::MATCH_VMACC_VX,
+                decode_v_vx_madd,
+            ),
+            InstructionFilter::new(
+                "vnmsac.vv",
+                inst::MASK_VNMSAC_VV,
+                inst::MATCH_VNMSAC_VV,
+                decode_v_vv_madd,
+            ),
+            InstructionFilter::new(
+                "vnmsac.vx",
+                inst::MASK_VNMSAC_VX,
+                inst::MATCH_VNMSAC_VX,
+                decode_v_vx_madd,
+            ),
+            InstructionFilter::new(
+                "vmadd.vv",
+                inst::MASK_VMADD_VV,
+                inst::MATCH_VMADD_VV,
+                decode_v_vv_madd,
+            ),
+            InstructionFilter::new(
+                "vmadd.vx",
+                inst::MASK_VMADD_VX,
+                inst::MATCH_VMADD_VX,
+                decode_v_vx_madd,
+            ),
+            InstructionFilter::new(
+                "vnmsub.vv",
+                inst::MASK_VNMSUB_VV,
+                inst::MATCH_VNMSUB_VV,
+                decode_v_vv_madd,
+            ),
+            InstructionFilter::new(
+                "vnmsub.vx",
+                inst::MASK_VNMSUB_VX,
+                inst::MATCH_VNMSUB_VX,
+                decode_v_vx_madd,
+            ),
+            InstructionFilter::new(
+                "vwmaccu.vv",
+                inst::MASK_VWMACCU_VV,
+                inst::MATCH_VWMACCU_VV,
+                decode_v_vv_madd,
+            ),
+            InstructionFilter::new(
+                "vwmaccu.vx",
+                inst::MASK_VWMACCU_VX,
+                inst::MATCH_VWMACCU_VX,
+                decode_v_vx_madd,
+            ),
+            InstructionFilter::new(
+                "vwmacc.vv",
+                inst::MASK_VWMACC_VV,
+                inst::MATCH_VWMACC_VV,
+                decode_v_vv_madd,
+            ),
+            InstructionFilter::new(
+                "vwmacc.vx",
+                inst::MASK_VWMACC_VX,
+                inst::MATCH_VWMACC_VX,
+                decode_v_vx_madd,
+            ),
+            InstructionFilter::new(
+                "vwmaccsu.vv",
+                inst::MASK_VWMACCSU_VV,
+                inst::MATCH_VWMACCSU_VV,
+                decode_v_vv_madd,
+            ),
+            InstructionFilter::new(
+                "vwmaccsu.vx",
+                inst::MASK_VWMACCSU_VX,
+                inst::MATCH_VWMACCSU_VX,
+                decode_v_vx_madd,
+            ),
+            InstructionFilter::new(
+                "vwmaccus.vx",
+                inst::MASK_VWMACCUS_VX,
+                inst::MATCH_VWMACCUS_VX,
+                decode_v_vx_madd,
+            ),
+            InstructionFilter::new(
+                "vmerge.vvm",
+                inst::MASK_VMERGE_VVM,
+                inst::MATCH_VMERGE_VVM,
+                decode_v_vvm,
+            ),
+            InstructionFilter::new(
+                "vmerge.vxm",
+                inst::MASK_VMERGE_VXM,
+                inst::MATCH_VMERGE_VXM,
+                decode_v_vxm,
+            ),
+            InstructionFilter::new(
+                "vmerge.vim",
+                inst::MASK_VMERGE_VIM,
+                inst::MATCH_VMERGE_VIM,
+                decode_v_vim,
+            ),
+            InstructionFilter::new(
+                "vmv.v.v",
+                inst::MASK_VMV_V_V,
+                inst::MATCH_VMV_V_V,
+                decode_v_mv_v,
+            ),
+            InstructionFilter::new(
+                "vmv.v.x",
+                inst::MASK_VMV_V_X,
+                inst::MATCH_VMV_V_X,
+                decode_v_mv_x,
+            ),
+            InstructionFilter::new(
+                "vmv.v.i",
+                inst::MASK_VMV_V_I,
+                inst::MATCH_VMV_V_I,
+                decode_v_mv_i,
+            ),
+            // Fixed-point arithmetic
+            InstructionFilter::new(
+                "vsaddu.vv",
+                inst::MASK_VSADDU_VV,
+                inst::MATCH_VSADDU_VV,
+                decode_v_vv,
+            ),
+            InstructionFilter::new(
+                "vsaddu.vx",
+                inst::MASK_VSADDU_VX,
+                inst::MATCH_VSADDU_VX,
+                decode_v_vx,
+            ),
+            InstructionFilter::new(
+                "vsaddu.vi",
+                inst::MASK_VSADDU_VI,
+                inst::MATCH_VSADDU_VI,
+                decode_v_vi,
+            ),
+            InstructionFilter::new(
+                "vsadd.vv",
+                inst::MASK_VSADD_VV,
+                inst::MATCH_VSADD_VV,
+                decode_v_vv,
+            ),
+            InstructionFilter::new(
+                "vsadd.vx",
+                inst::MASK_VSADD_VX,
+                inst::MATCH_VSADD_VX,
+                decode_v_vx,
+            ),
+            InstructionFilter::new(
+                "vsadd.vi",
+                inst::MASK_VSADD_VI,
+                inst::MATCH_VSADD_VI,
+                decode_v_vi,
+            ),
+            InstructionFilter::new(
+                "vssubu.vv",
+                inst::MASK_VSSUBU_VV,
+                inst::MATCH_VSSUBU_VV,
+                decode_v_vv,
+            ),
+            InstructionFilter::new(
+                "vssubu.vx",
+                inst::MASK_VSSUBU_VX,
+                inst::MATCH_VSSUBU_VX,
+                decode_v_vx,
+            ),
+            InstructionFilter::new(
+                "vssub.vv",
+                inst::MASK_VSSUB_VV,
+                inst::MATCH_VSSUB_VV,
+                decode_v_vv,
+            ),
+            InstructionFilter::new(
+                "vssub.vx",
+                inst::MASK_VSSUB_VX,
+                inst::MATCH_VSSUB_VX,
+                decode_v_vx,
+            ),
+            InstructionFilter::new(
+                "vaaddu.vv",
+                inst::MASK_VAADDU_VV,
+                inst::MATCH_VAADDU_VV,
+                decode_v_vv,
+            ),
+            InstructionFilter::new(
+                "vaaddu.vx",
+                inst::MASK_VAADDU_VX,
+                inst::MATCH_VAADDU_VX,
+                decode_v_vx,
+            ),
+            InstructionFilter::new(
+                "vaadd.vv",
+                inst::MASK_VAADD_VV,
+                inst::MATCH_VAADD_VV,
+                decode_v_vv,
+            ),
+            InstructionFilter::new(
+                "vaadd.vx",
+                inst::MASK_VAADD_VX,
+                inst::MATCH_VAADD_VX,
+                decode_v_vx,
+            ),
+            InstructionFilter::new(
+                "vasubu.vv",
+                inst::MASK_VASUBU_VV,
+                inst::MATCH_VASUBU_VV,
+                decode_v_vv,
+            ),
+            InstructionFilter::new(
+                "vasubu.vx",
+                inst::MASK_VASUBU_VX,
+                inst::MATCH_VASUBU_VX,
+                decode_v_vx,
+            ),
+            InstructionFilter::new(
+                "vasub.vv",
+                inst::MASK_VASUB_VV,
+                inst::MATCH_VASUB_VV,
+                decode_v_vv,
+            ),
+            InstructionFilter::new(
+                "vasub.vx",
+                inst::MASK_VASUB_VX,
+                inst::MATCH_VASUB_VX,
+                decode_v_vx,
+            ),
+            InstructionFilter::new(
+                "vsmul.vv",
+                inst::MASK_VSMUL_VV,
+                inst::MATCH_VSMUL_VV,
+                decode_v_vv,
+            ),
+            InstructionFilter::new(
+                "vsmul.vx",
+                inst::MASK_VSMUL_VX,
+                inst::MATCH_VSMUL_VX,
+                decode_v_vx,
+            ),
+            InstructionFilter::new(
+                "vssrl.vv",
+                inst::MASK_VSSRL_VV,
+                inst::MATCH_VSSRL_VV,
+                decode_v_vv,
+            ),
+            InstructionFilter::new(
+                "vssrl.vx",
+                inst::MASK_VSSRL_VX,
+                inst::MATCH_VSSRL_VX,
+                decode_v_vx,
+            ),
+            InstructionFilter::new(
+                "vssrl.vi",
+                inst::MASK_VSSRL_VI,
+                inst::MATCH_VSSRL_VI,
+                decode_v_vi_unsigned,
+            ),
+            InstructionFilter::new(
+                "vssra.vv",
+                inst::MASK_VSSRA_VV,
+                inst::MATCH_VSSRA_VV,
+                decode_v_vv,
+            ),
+            InstructionFilter::new(
+                "vssra.vx",
+                inst::MASK_VSSRA_VX,
+                inst::MATCH_VSSRA_VX,
+                decode_v_vx,
+            ),
+            InstructionFilter::new(
+                "vssra.vi",
+                inst::MASK_VSSRA_VI,
+                inst::MATCH_VSSRA_VI,
+                decode_v_vi_unsigned,
+            ),
+            InstructionFilter::new(
+                "vnclipu.wv",
+                inst::MASK_VNCLIPU_WV,
+                inst::MATCH_VNCLIPU_WV,
+                decode_v_vv,
+            ),
+            InstructionFilter::new(
+                "vnclipu.wx",
+                inst::MASK_VNCLIPU_WX,
+                inst::MATCH_VNCLIPU_WX,
+                decode_v_vx,
+            ),
+            InstructionFilter::new(
+                "vnclipu.wi",
+                inst::MASK_VNCLIPU_WI,
+                inst::MATCH_VNCLIPU_WI,
+                decode_v_vi_unsigned,
+            ),
+            InstructionFilter::new(
+                "vnclip.wv",
+                inst::MASK_VNCLIP_WV,
+                inst::MATCH_VNCLIP_WV,
+                decode_v_vv,
+            ),
+            InstructionFilter::new(
+                "vnclip.wx",
+                inst::MASK_VNCLIP_WX,
+                inst::MATCH_VNCLIP_WX,
+                decode_v_vx,
+            ),
+            InstructionFilter::new(
+                "vnclip.wi",
+                inst::MASK_VNCLIP_WI,
+                inst::MATCH_VNCLIP_WI,
+                decode_v_vi_unsigned,
+            ),
+            // Floating-point arithmetic
+            InstructionFilter::new(
+                "vfadd.vv",
+                inst::MASK_VFADD_VV,
+                inst::MATCH_VFADD_VV,
+                decode_v_vv,
+            ),
+            InstructionFilter::new(
+                "vfadd.vf",
+                inst::MASK_VFADD_VF,
+                inst::MATCH_VFADD_VF,
+                decode_v_vf,
+            ),
+            InstructionFilter::new(
+                "vfsub.vv",
+                inst::MASK_VFSUB_VV,
+                inst::MATCH_VFSUB_VV,
+                decode_v_vv,
+            ),
+            InstructionFilter::new(
+                "vfsub.vf",
+                inst::MASK_VFSUB_VF,
+                inst::MATCH_VFSUB_VF,
+                decode_v_vf,
+            ),
+            InstructionFilter::new(
+                "vfrsub.vf",
+                inst::MASK_VFRSUB_VF,
+                inst::MATCH_VFRSUB_VF,
+                decode_v_vf,
+            ),
+            InstructionFilter::new(
+                "vfwadd.vv",
+                inst::MASK_VFWADD_VV,
+                inst::MATCH_VFWADD_VV,
+                decode_v_vv,
+            ),
+            InstructionFilter::new(
+                "vfwadd.vf",
+                inst::MASK_VFWADD_VF,
+                inst::MATCH_VFWADD_VF,
+                decode_v_vf,
+            ),
+            InstructionFilter::new(
+                "vfwadd.wv",
+                inst::MASK_VFWADD_WV,
+                inst::MATCH_VFWADD_WV,
+                decode_v_vv,
+            ),
+            InstructionFilter::new(
+                "vfwadd.wf",
+                inst::MASK_VFWADD_WF,
+                inst::MATCH_VFWADD_WF,
+                decode_v_vf,
+            ),
+            InstructionFilter::new(
+                "vfwsub.vv",
+                inst::MASK_VFWSUB_VV,
+                inst::MATCH_VFWSUB_VV,
+                decode_v_vv,
+            ),
+            InstructionFilter::new(
+                "vfwsub.vf",
+                inst::MASK_VFWSUB_VF,
+                inst::MATCH_VFWSUB_VF,
+                decode_v_vf,
+            ),
+            InstructionFilter::new(
+                "vfwsub.wv",
+                inst::MASK_VFWSUB_WV,
+                inst::MATCH_VFWSUB_WV,
+                decode_v_vv,
+            ),
+            InstructionFilter::new(
+                "vfwsub.wf",
+                inst::MASK_VFWSUB_WF,
+                inst::MATCH_VFWSUB_WF,
+                decode_v_vf,
+            ),
+            InstructionFilter::new(
+                "vfmul.vv",
+                inst::MASK_VFMUL_VV,
+                inst::MATCH_VFMUL_VV,
+                decode_v_vv,
+            ),
+            InstructionFilter::new(
+                "vfmul.vf",
+                inst::MASK_VFMUL_VF,
+                inst::MATCH_VFMUL_VF,
+                decode_v_vf,
+            ),
+            InstructionFilter::new(
+                "vfdiv.vv",
+                inst::MASK_VFDIV_VV,
+                inst::MATCH_VFDIV_VV,
+                decode_v_vv,
+            ),
+            InstructionFilter::new(
+                "vfdiv.vf",
+                inst::MASK_VFDIV_VF,
+                inst::MATCH_VFDIV_VF,
+                decode_v_vf,
+            ),
+            InstructionFilter::new(
+                "vfrdiv.vf",
+                inst::MASK_VFRDIV_VF,
+                inst::MATCH_VFRDIV_VF,
+                decode_v_vf,
+            ),
+            InstructionFilter::new(
+                "vfwmul.vv",
+                inst::MASK_VFWMUL_VV,
+                inst::MATCH_VFWMUL_VV,
+                decode_v_vv,
+            ),
+            InstructionFilter::new(
+                "vfwmul.vf",
+                inst::MASK_VFWMUL_VF,
+                inst::MATCH_VFWMUL_VF,
+                decode_v_vf,
+            ),
+            InstructionFilter::new(
+                "vfmacc.vv",
+                inst::MASK_VFMACC_VV,
+                inst::MATCH_VFMACC_VV,
+                decode_v_vv_madd,
+            ),
+            InstructionFilter::new(
+                "vfmacc.vf",
+                inst::MASK_VFMACC_VF,
+                inst::MATCH_VFMACC_VF,
+                decode_v_vf_madd,
+            ),
+            InstructionFilter::new(
+                "vfnmacc.vv",
+                inst::MASK_VFNMACC_VV,
+                inst::MATCH_VFNMACC_VV,
+                decode_v_vv_madd,
+            ),
+            InstructionFilter::new(
+                "vfnmacc.vf",
+                inst::MASK_VFNMACC_VF,
+                inst::MATCH_VFNMACC_VF,
+                decode_v_vf_madd,
+            ),
+            InstructionFilter::new(
+                "vfmsac.vv",
+                inst::MASK_VFMSAC_VV,
+                inst::MATCH_VFMSAC_VV,
+                decode_v_vv_madd,
+            ),
+            InstructionFilter::new(
+                "vfmsac.vf",
+                inst::MASK_VFMSAC_VF,
+                inst::MATCH_VFMSAC_VF,
+                decode_v_vf_madd,
+            ),
+            InstructionFilter::new(
+                "vfnmsac.vv",
+                inst::MASK_VFNMSAC_VV,
+                inst::MATCH_VFNMSAC_VV,
+                decode_v_vv_madd,
+            ),
+            InstructionFilter::new(
+                "vfnmsac.vf",
+                inst::MASK_VFNMSAC_VF,
+                inst::MATCH_VFNMSAC_VF,
+                decode_v_vf_madd,
+            ),
+            InstructionFilter::new(
+                "vfmadd.vv",
+                inst::MASK_VFMADD_VV,
+                inst::MATCH_VFMADD_VV,
+                decode_v_vv_madd,
+            ),
+            InstructionFilter::new(
+                "vfmadd.vf",
+                inst::MASK_VFMADD_VF,
+                inst::MATCH_VFMADD_VF,
+                decode_v_vf_madd,
             ),
             InstructionFilter::new(
-                "c.swsp",
-                inst::MASK_C_SWSP,
-                inst::MATCH_C_SWSP,
-                fmt_css_type_swsp,
+                "vfnmadd.vv",
+                inst::MASK_VFNMADD_VV,
+                inst::MATCH_VFNMADD_VV,
+                decode_v_vv_madd,
             ),
-            InstructionFilter::new("c.lw", inst::MASK_C_LW, inst::MATCH_C_LW, fmt_cl_type_lw),
-            InstructionFilter::new("c.sw", inst::MASK_C_SW, inst::MATCH_C_SW, fmt_cl_type_lw),
             InstructionFilter::new(
-                "c.ebreak",
-                inst::MASK_C_EBREAK,
-                inst::MATCH_C_EBREAK,
-                fmt_no_args,
+                "vfnmadd.vf",
+                inst::MASK_VFNMADD_VF,
+                inst::MATCH_VFNMADD_VF,
+                decode_v_vf_madd,
             ),
-            InstructionFilter::new("c.j", inst::MASK_C_J, inst::MATCH_C_J, fmt_cj_type),
             InstructionFilter::new(
-                "c.jr",
-                inst::MASK_C_JR,
-                inst::MATCH_C_JR,
-                fmt_cr_type_no_rs2,
+                "vfmsub.vv",
+                inst::MASK_VFMSUB_VV,
+                inst::MATCH_VFMSUB_VV,
+                decode_v_vv_madd,
             ),
-            // `c.jalr` must come after `c.ebreak`, otherwise the `c.jalr` filter will match any
-            // `c.ebreak` instructions.
             InstructionFilter::new(
-                "c.jalr",
-                inst::MASK_C_JALR,
-                inst::MATCH_C_JALR,
-                fmt_cr_type_no_rs2,
+                "vfmsub.vf",
+                inst::MASK_VFMSUB_VF,
+                inst::MATCH_VFMSUB_VF,
+                decode_v_vf_madd,
             ),
-            InstructionFilter::new("c.beqz", inst::MASK_C_BEQZ, inst::MATCH_C_BEQZ, fmt_cb_type),
-            InstructionFilter::new("c.bnez", inst::MASK_C_BNEZ, inst::MATCH_C_BNEZ, fmt_cb_type),
-            InstructionFilter::new("c.li", inst::MASK_C_LI, inst::MATCH_C_LI, fmt_ci_type),
-            InstructionFilter::new("c.nop", inst::MASK_C_NOP, inst::MATCH_C_NOP, fmt_no_args),
-            // `c.addi` must come after `c.nop`, otherwise the `c.addi` filter will match any
-            // `c.nop` instructions.
-            InstructionFilter::new("c.addi", inst::MASK_C_ADDI, inst::MATCH_C_ADDI, fmt_ci_type),
             InstructionFilter::new(
-                "c.addi16sp",
-                inst::MASK_C_ADDI16SP,
-                inst::MATCH_C_ADDI16SP,
-                fmt_ci_type_addi16sp,
+                "vfnmsub.vv",
+                inst::MASK_VFNMSUB_VV,
+                inst::MATCH_VFNMSUB_VV,
+                decode_v_vv_madd,
             ),
             InstructionFilter::new(
-                "c.addi4spn",
-                inst::MASK_C_ADDI4SPN,
-                inst::MATCH_C_ADDI4SPN,
-                fmt_ciw_type_addi4spn,
+                "vfnmsub.vf",
+                inst::MASK_VFNMSUB_VF,
+                inst::MATCH_VFNMSUB_VF,
+                decode_v_vf_madd,
             ),
             InstructionFilter::new(
-                "c.slli",
-                inst::MASK_C_SLLI,
-                inst::MATCH_C_SLLI,
-                fmt_ci_type_shift,
+                "vfwmacc.vv",
+                inst::MASK_VFWMACC_VV,
+                inst::MATCH_VFWMACC_VV,
+                decode_v_vv_madd,
             ),
             InstructionFilter::new(
-                "c.srli",
-                inst::MASK_C_SRLI,
-                inst::MATCH_C_SRLI,
-                fmt_cb_type_shift,
+                "vfwmacc.vf",
+                inst::MASK_VFWMACC_VF,
+                inst::MATCH_VFWMACC_VF,
+                decode_v_vf_madd,
             ),
             InstructionFilter::new(
-                "c.srai",
-                inst::MASK_C_SRAI,
-                inst::MATCH_C_SRAI,
-                fmt_cb_type_shift,
+                "vfwnmacc.vv",
+                inst::MASK_VFWNMACC_VV,
+                inst::MATCH_VFWNMACC_VV,
+                decode_v_vv_madd,
             ),
             InstructionFilter::new(
-                "c.andi",
-                inst::MASK_C_ANDI,
-                inst::MATCH_C_ANDI,
-                fmt_cb_type_andi,
+                "vfwnmacc.vf",
+                inst::MASK_VFWNMACC_VF,
+                inst::MATCH_VFWNMACC_VF,
+                decode_v_vf_madd,
             ),
-            // `c.mv` must come after `c.jr`, otherwise the `c.mv` filter will match any `c.jr`
-            // instructions.
-            InstructionFilter::new("c.mv", inst::MASK_C_MV, inst::MATCH_C_MV, fmt_cr_type),
-            // `c.add` must come after `c.jalr` and `c.ebreak`, otherwise the `c.add` filter will
-            // match any `c.jalr`/`c.ebreak` instructions.
-            InstructionFilter::new("c.add", inst::MASK_C_ADD, inst::MATCH_C_ADD, fmt_cr_type),
-            InstructionFilter::new("c.and", inst::MASK_C_AND, inst::MATCH_C_AND, fmt_ca_type),
-            InstructionFilter::new("c.or", inst::MASK_C_OR, inst::MATCH_C_OR, fmt_ca_type),
-            InstructionFilter::new("c.xor", inst::MASK_C_XOR, inst::MATCH_C_XOR, fmt_ca_type),
-            InstructionFilter::new("c.sub", inst::MASK_C_SUB, inst::MATCH_C_SUB, fmt_ca_type),
-            // `c.lui` must come after `c.addi16sp`, otherwise the `c.lui` filter will match any
-            // `c.addi16sp` instructions.
             InstructionFilter::new(
-                "c.lui",
-                inst::MASK_C_LUI,
-                inst::MATCH_C_LUI,
-                fmt_ci_type_lui,
+                "vfwmsac.vv",
+                inst::MASK_VFWMSAC_VV,
+                inst::MATCH_VFWMSAC_VV,
+                decode_v_vv_madd,
             ),
-        ];
-
-        pseudo_instruction_filters.extend(xlen_filters);
-        pseudo_instruction_filters.extend(d_filters);
-        pseudo_instruction_filters.extend(global_filters);
-        pseudo_instruction_filters
+            InstructionFilter::new(
+                "vfwmsac.vf",
+                inst::MASK_VFWMSAC_VF,
+                inst::MATCH_VFWMSAC_VF,
+                decode_v_vf_madd,
+            ),
+            InstructionFilter::new(
+                "vfwnmsac.vv",
+                inst::MASK_VFWNMSAC_VV,
+                inst::MATCH_VFWNMSAC_VV,
+                decode_v_vv_madd,
+            ),
+            InstructionFilter::new(
+                "vfwnmsac.vf",
+                inst::MASK_VFWNMSAC_VF,
+                inst::MATCH_VFWNMSAC_VF,
+                decode_v_vf_madd,
+            ),
+            InstructionFilter::new(
+                "vfsqrt.v",
+                inst::MASK_VFSQRT_V,
+                inst::MATCH_VFSQRT_V,
+                decode_v_unary,
+            ),
+            InstructionFilter::new(
+                "vfrsqrt7.v",
+                inst::MASK_VFRSQRTE7_V,
+                inst::MATCH_VFRSQRTE7_V,
+                decode_v_unary,
+            ),
+            InstructionFilter::new(
+                "vfrec7.v",
+                inst::MASK_VFRECE7_V,
+                inst::MATCH_VFRECE7_V,
+                decode_v_unary,
+            ),
+            InstructionFilter::new(
+                "vfmin.vv",
+                inst::MASK_VFMIN_VV,
+                inst::MATCH_VFMIN_VV,
+                decode_v_vv,
+            ),
+            InstructionFilter::new(
+                "vfmin.vf",
+                inst::MASK_VFMIN_VF,
+                inst::MATCH_VFMIN_VF,
+                decode_v_vf,
+            ),
+            InstructionFilter::new(
+                "vfmax.vv",
+                inst::MASK_VFMAX_VV,
+                inst::MATCH_VFMAX_VV,
+                decode_v_vv,
+            ),
+            InstructionFilter::new(
+                "vfmax.vf",
+                inst::MASK_VFMAX_VF,
+                inst::MATCH_VFMAX_VF,
+                decode_v_vf,
+            ),
+            InstructionFilter::new(
+                "vfsgnj.vv",
+                inst::MASK_VFSGNJ_VV,
+                inst::MATCH_VFSGNJ_VV,
+                decode_v_vv,
+            ),
+            InstructionFilter::new(
+                "vfsgnj.vf",
+                inst::MASK_VFSGNJ_VF,
+                inst::MATCH_VFSGNJ_VF,
+                decode_v_vf,
+            ),
+            InstructionFilter::new(
+                "vfsgnjn.vv",
+                inst::MASK_VFSGNJN_VV,
+                inst::MATCH_VFSGNJN_VV,
+                decode_v_vv,
+            ),
+            InstructionFilter::new(
+                "vfsgnjn.vf",
+                inst::MASK_VFSGNJN_VF,
+                inst::MATCH_VFSGNJN_VF,
+                decode_v_vf,
+            ),
+            InstructionFilter::new(
+                "vfsgnjx.vv",
+                inst::MASK_VFSGNJX_VV,
+                inst::MATCH_VFSGNJX_VV,
+                decode_v_vv,
+            ),
+            InstructionFilter::new(
+                "vfsgnjx.vf",
+                inst::MASK_VFSGNJX_VF,
+                inst::MATCH_VFSGNJX_VF,
+                decode_v_vf,
+            ),
+            InstructionFilter::new(
+                "vmfeq.vv",
+                inst::MASK_VMFEQ_VV,
+                inst::MATCH_VMFEQ_VV,
+                decode_v_vv,
+            ),
+            InstructionFilter::new(
+                "vmfeq.vf",
+                inst::MASK_VMFEQ_VF,
+                inst::MATCH_VMFEQ_VF,
+                decode_v_vf,
+            ),
+            InstructionFilter::new(
+                "vmfne.vv",
+                inst::MASK_VMFNE_VV,
+                inst::MATCH_VMFNE_VV,
+                decode_v_vv,
+            ),
+            InstructionFilter::new(
+                "vmfne.vf",
+                inst::MASK_VMFNE_VF,
+                inst::MATCH_VMFNE_VF,
+                decode_v_vf,
+            ),
+            InstructionFilter::new(
+                "vmflt.vv",
+                inst::MASK_VMFLT_VV,
+                inst::MATCH_VMFLT_VV,
+                decode_v_vv,
+            ),
+            InstructionFilter::new(
+                "vmflt.vf",
+                inst::MASK_VMFLT_VF,
+                inst::MATCH_VMFLT_VF,
+                decode_v_vf,
+            ),
+            InstructionFilter::new(
+                "vmfle.vv",
+                inst::MASK_VMFLE_VV,
+                inst::MATCH_VMFLE_VV,
+                decode_v_vv,
+            ),
+            InstructionFilter::new(
+                "vmfle.vf",
+                inst::MASK_VMFLE_VF,
+                inst::MATCH_VMFLE_VF,
+                decode_v_vf,
+            ),
+            InstructionFilter::new(
+                "vmfgt.vf",
+                inst::MASK_VMFGT_VF,
+                inst::MATCH_VMFGT_VF,
+                decode_v_vf,
+            ),
+            InstructionFilter::new(
+                "vmfge.vf",
+                inst::MASK_VMFGE_VF,
+                inst::MATCH_VMFGE_VF,
+                decode_v_vf,
+            ),
+            InstructionFilter::new(
+                "vfclass.v",
+                inst::MASK_VFCLASS_V,
+                inst::MATCH_VFCLASS_V,
+                decode_v_unary,
+            ),
+            InstructionFilter::new(
+                "vfmerge.vfm",
+                inst::MASK_VFMERGE_VFM,
+                inst::MATCH_VFMERGE_VFM,
+                decode_v_vfm,
+            ),
+            InstructionFilter::new(
+                "vfmv.v.f",
+                inst::MASK_VFMV_V_F,
+                inst::MATCH_VFMV_V_F,
+                decode_v_mv_f,
+            ),
+            InstructionFilter::new(
+                "vfcvt.xu.f.v",
+                inst::MASK_VFCVT_XU_F_V,
+                inst::MATCH_VFCVT_XU_F_V,
+                decode_v_unary,
+            ),
+            InstructionFilter::new(
+                "vfcvt.x.f.v",
+                inst::MASK_VFCVT_X_F_V,
+                inst::MATCH_VFCVT_X_F_V,
+                decode_v_unary,
+            ),
+            InstructionFilter::new(
+                "vfcvt.f.xu.v",
+                inst::MASK_VFCVT_F_XU_V,
+                inst::MATCH_VFCVT_F_XU_V,
+                decode_v_unary,
+            ),
+            InstructionFilter::new(
+                "vfcvt.f.x.v",
+                inst::MASK_VFCVT_F_X_V,
+                inst::MATCH_VFCVT_F_X_V,
+                decode_v_unary,
+            ),
+            InstructionFilter::new(
+                "vfcvt.rtz.xu.f.v",
+                inst::MASK_VFCVT_RTZ_XU_F_V,
+                inst::MATCH_VFCVT_RTZ_XU_F_V,
+                decode_v_unary,
+            ),
+            InstructionFilter::new(
+                "vfcvt.rtz.x.f.v",
+                inst::MASK_VFCVT_RTZ_X_F_V,
+                inst::MATCH_VFCVT_RTZ_X_F_V,
+                decode_v_unary,
+            ),
+            InstructionFilter::new(
+                "vfwcvt.xu.f.v",
+                inst::MASK_VFWCVT_XU_F_V,
+                inst::MATCH_VFWCVT_XU_F_V,
+                decode_v_unary,
+            ),
+            InstructionFilter::new(
+                "vfwcvt.x.f.v",
+                inst::MASK_VFWCVT_X_F_V,
+                inst::MATCH_VFWCVT_X_F_V,
+                decode_v_unary,
+            ),
+            InstructionFilter::new(
+                "vfwcvt.f.xu.v",
+                inst::MASK_VFWCVT_F_XU_V,
+                inst::MATCH_VFWCVT_F_XU_V,
+                decode_v_unary,
+            ),
+            InstructionFilter::new(
+                "vfwcvt.f.x.v",
+                inst::MASK_VFWCVT_F_X_V,
+                inst::MATCH_VFWCVT_F_X_V,
+                decode_v_unary,
+            ),
+            InstructionFilter::new(
+                "vfwcvt.f.f.v",
+                inst::MASK_VFWCVT_F_F_V,
+                inst::MATCH_VFWCVT_F_F_V,
+                decode_v_unary,
+            ),
+            InstructionFilter::new(
+                "vfwcvt.rtz.xu.f.v",
+                inst::MASK_VFWCVT_RTZ_XU_F_V,
+                inst::MATCH_VFWCVT_RTZ_XU_F_V,
+                decode_v_unary,
+            ),
+            InstructionFilter::new(
+                "vfwcvt.rtz.x.f.v",
+                inst::MASK_VFWCVT_RTZ_X_F_V,
+                inst::MATCH_VFWCVT_RTZ_X_F_V,
+                decode_v_unary,
+            ),
+            InstructionFilter::new(
+                "vfncvt.xu.f.w",
+                inst::MASK_VFNCVT_XU_F_W,
+                inst::MATCH_VFNCVT_XU_F_W,
+                decode_v_unary,
+            ),
+            InstructionFilter::new(
+                "vfncvt.x.f.w",
+                inst::MASK_VFNCVT_X_F_W,
+                inst::MATCH_VFNCVT_X_F_W,
+                decode_v_unary,
+            ),
+            InstructionFilter::new(
+                "vfncvt.f.xu.w",
+                inst::MASK_VFNCVT_F_XU_W,
+                inst::MATCH_VFNCVT_F_XU_W,
+                decode_v_unary,
+            ),
+            InstructionFilter::new(
+                "vfncvt.f.x.w",
+                inst::MASK_VFNCVT_F_X_W,
+                inst::MATCH_VFNCVT_F_X_W,
+                decode_v_unary,
+            ),
+            InstructionFilter::new(
+                "vfncvt.f.f.w",
+                inst::MASK_VFNCVT_F_F_W,
+                inst::MATCH_VFNCVT_F_F_W,
+                decode_v_unary,
+            ),
+            InstructionFilter::new(
+                "vfncvt.rod.f.f.w",
+                inst::MASK_VFNCVT_ROD_F_F_W,
+                inst::MATCH_VFNCVT_ROD_F_F_W,
+                decode_v_unary,
+            ),
+            InstructionFilter::new(
+                "vfncvt.rtz.xu.f.w",
+                inst::MASK_VFNCVT_RTZ_XU_F_W,
+                inst::MATCH_VFNCVT_RTZ_XU_F_W,
+                decode_v_unary,
+            ),
+            InstructionFilter::new(
+                "vfncvt.rtz.x.f.w",
+                inst::MASK_VFNCVT_RTZ_X_F_W,
+                inst::MATCH_VFNCVT_RTZ_X_F_W,
+                decode_v_unary,
+            ),
+            // Reductions
+            InstructionFilter::new(
+                "vredsum.vs",
+                inst::MASK_VREDSUM_VS,
+                inst::MATCH_VREDSUM_VS,
+                decode_v_vv,
+            ),
+            InstructionFilter::new(
+                "vredand.vs",
+                inst::MASK_VREDAND_VS,
+                inst::MATCH_VREDAND_VS,
+                decode_v_vv,
+            ),
+            InstructionFilter::new(
+                "vredor.vs",
+                inst::MASK_VREDOR_VS,
+                inst::MATCH_VREDOR_VS,
+                decode_v_vv,
+            ),
+            InstructionFilter::new(
+                "vredxor.vs",
+                inst::MASK_VREDXOR_VS,
+                inst::MATCH_VREDXOR_VS,
+                decode_v_vv,
+            ),
+            InstructionFilter::new(
+                "vredminu.vs",
+                inst::MASK_VREDMINU_VS,
+                inst::MATCH_VREDMINU_VS,
+                decode_v_vv,
+            ),
+            InstructionFilter::new(
+                "vredmin.vs",
+                inst::MASK_VREDMIN_VS,
+                inst::MATCH_VREDMIN_VS,
+                decode_v_vv,
+            ),
+            InstructionFilter::new(
+                "vredmaxu.vs",
+                inst::MASK_VREDMAXU_VS,
+                inst::MATCH_VREDMAXU_VS,
+                decode_v_vv,
+            ),
+            InstructionFilter::new(
+                "vredmax.vs",
+                inst::MASK_VREDMAX_VS,
+                inst::MATCH_VREDMAX_VS,
+                decode_v_vv,
+            ),
+            InstructionFilter::new(
+                "vwredsumu.vs",
+                inst::MASK_VWREDSUMU_VS,
+                inst::MATCH_VWREDSUMU_VS,
+                decode_v_vv,
+            ),
+            InstructionFilter::new(
+                "vwredsum.vs",
+                inst::MASK_VWREDSUM_VS,
+                inst::MATCH_VWREDSUM_VS,
+                decode_v_vv,
+            ),
+            InstructionFilter::new(
+                "vfredosum.vs",
+                inst::MASK_VFREDOSUM_VS,
+                inst::MATCH_VFREDOSUM_VS,
+                decode_v_vv,
+            ),
+            InstructionFilter::new(
+                "vfredusum.vs",
+                inst::MASK_VFREDSUM_VS,
+                inst::MATCH_VFREDSUM_VS,
+                decode_v_vv,
+            ),
+            InstructionFilter::new(
+                "vfredmin.vs",
+                inst::MASK_VFREDMIN_VS,
+                inst::MATCH_VFREDMIN_VS,
+                decode_v_vv,
+            ),
+            InstructionFilter::new(
+                "vfredmax.vs",
+                inst::MASK_VFREDMAX_VS,
+                inst::MATCH_VFREDMAX_VS,
+                decode_v_vv,
+            ),
+            InstructionFilter::new(
+                "vfwredosum.vs",
+                inst::MASK_VFWREDOSUM_VS,
+                inst::MATCH_VFWREDOSUM_VS,
+                decode_v_vv,
+            ),
+            InstructionFilter::new(
+                "vfwredusum.vs",
+                inst::MASK_VFWREDSUM_VS,
+                inst::MATCH_VFWREDSUM_VS,
+                decode_v_vv,
+            ),
+            // Mask instructions
+            InstructionFilter::new(
+                "vmandn.mm",
+                inst::MASK_VMANDNOT_MM,
+                inst::MATCH_VMANDNOT_MM,
+                decode_v_mm,
+            ),
+            InstructionFilter::new(
+                "vmand.mm",
+                inst::MASK_VMAND_MM,
+                inst::MATCH_VMAND_MM,
+                decode_v_mm,
+            ),
+            InstructionFilter::new(
+                "vmor.mm",
+                inst::MASK_VMOR_MM,
+                inst::MATCH_VMOR_MM,
+                decode_v_mm,
+            ),
+            InstructionFilter::new(
+                "vmxor.mm",
+                inst::MASK_VMXOR_MM,
+                inst::MATCH_VMXOR_MM,
+                decode_v_mm,
+            ),
+            InstructionFilter::new(
+                "vmorn.mm",
+                inst::MASK_VMORNOT_MM,
+                inst::MATCH_VMORNOT_MM,
+                decode_v_mm,
+            ),
+            InstructionFilter::new(
+                "vmnand.mm",
+                inst::MASK_VMNAND_MM,
+                inst::MATCH_VMNAND_MM,
+                decode_v_mm,
+            ),
+            InstructionFilter::new(
+                "vmnor.mm",
+                inst::MASK_VMNOR_MM,
+                inst::MATCH_VMNOR_MM,
+                decode_v_mm,
+            ),
+            InstructionFilter::new(
+                "vmxnor.mm",
+                inst::MASK_VMXNOR_MM,
+                inst::MATCH_VMXNOR_MM,
+                decode_v_mm,
+            ),
+            InstructionFilter::new(
+                "vcpop.m",
+                inst::MASK_VPOPC_M,
+                inst::MATCH_VPOPC_M,
+                decode_v_x_rd,
+            ),
+            InstructionFilter::new(
+                "vfirst.m",
+                inst::MASK_VFIRST_M,
+                inst::MATCH_VFIRST_M,
+                decode_v_x_rd,
+            ),
+            InstructionFilter::new(
+                "vmsbf.m",
+                inst::MASK_VMSBF_M,
+                inst::MATCH_VMSBF_M,
+                decode_v_unary,
+            ),
+            InstructionFilter::new(
+                "vmsif.m",
+                inst::MASK_VMSIF_M,
+                inst::MATCH_VMSIF_M,
+                decode_v_unary,
+            ),
+            InstructionFilter::new(
+                "vmsof.m",
+                inst::MASK_VMSOF_M,
+                inst::MATCH_VMSOF_M,
+                decode_v_unary,
+            ),
+            InstructionFilter::new(
+                "viota.m",
+                inst::MASK_VIOTA_M,
+                inst::MATCH_VIOTA_M,
+                decode_v_unary,
+            ),
+            InstructionFilter::new(
+                "vid.v",
+                inst::MASK_VID_V,
+                inst::MATCH_VID_V,
+                decode_v_vd_only,
+            ),
+            // Permutations
+            InstructionFilter::new(
+                "vmv.x.s",
+                inst::MASK_VMV_X_S,
+                inst::MATCH_VMV_X_S,
+                decode_v_x_rd,
+            ),
+            InstructionFilter::new(
+                "vmv.s.x",
+                inst::MASK_VMV_S_X,
+                inst::MATCH_VMV_S_X,
+                decode_v_mv_x,
+            ),
+            InstructionFilter::new(
+                "vfmv.f.s",
+                inst::MASK_VFMV_F_S,
+                inst::MATCH_VFMV_F_S,
+                decode_v_f_rd,
+            ),
+            InstructionFilter::new(
+                "vfmv.s.f",
+                inst::MASK_VFMV_S_F,
+                inst::MATCH_VFMV_S_F,
+                decode_v_mv_f,
+            ),
+            InstructionFilter::new(
+                "vslideup.vx",
+                inst::MASK_VSLIDEUP_VX,
+                inst::MATCH_VSLIDEUP_VX,
+                decode_v_vx,
+            ),
+            InstructionFilter::new(
+                "vslideup.vi",
+                inst::MASK_VSLIDEUP_VI,
+                inst::MATCH_VSLIDEUP_VI,
+                decode_v_vi_unsigned,
+            ),
+            InstructionFilter::new(
+                "vslidedown.vx",
+                inst::MASK_VSLIDEDOWN_VX,
+                inst::MATCH_VSLIDEDOWN_VX,
+                decode_v_vx,
+            ),
+            InstructionFilter::new(
+                "vslidedown.vi",
+                inst::MASK_VSLIDEDOWN_VI,
+                inst::MATCH_VSLIDEDOWN_VI,
+                decode_v_vi_unsigned,
+            ),
+            InstructionFilter::new(
+                "vslide1up.vx",
+                inst::MASK_VSLIDE1UP_VX,
+                inst::MATCH_VSLIDE1UP_VX,
+                decode_v_vx,
+            ),
+            InstructionFilter::new(
+                "vslide1down.vx",
+                inst::MASK_VSLIDE1DOWN_VX,
+                inst::MATCH_VSLIDE1DOWN_VX,
+                decode_v_vx,
+            ),
+            InstructionFilter::new(
+                "vfslide1up.vf",
+                inst::MASK_VFSLIDE1UP_VF,
+                inst::MATCH_VFSLIDE1UP_VF,
+                decode_v_vf,
+            ),
+            InstructionFilter::new(
+                "vfslide1down.vf",
+                inst::MASK_VFSLIDE1DOWN_VF,
+                inst::MATCH_VFSLIDE1DOWN_VF,
+                decode_v_vf,
+            ),
+            InstructionFilter::new(
+                "vrgather.vv",
+                inst::MASK_VRGATHER_VV,
+                inst::MATCH_VRGATHER_VV,
+                decode_v_vv,
+            ),
+            InstructionFilter::new(
+                "vrgather.vx",
+                inst::MASK_VRGATHER_VX,
+                inst::MATCH_VRGATHER_VX,
+                decode_v_vx,
+            ),
+            InstructionFilter::new(
+                "vrgather.vi",
+                inst::MASK_VRGATHER_VI,
+                inst::MATCH_VRGATHER_VI,
+                decode_v_vi_unsigned,
+            ),
+            InstructionFilter::new(
+                "vrgatherei16.vv",
+                inst::MASK_VRGATHEREI16_VV,
+                inst::MATCH_VRGATHEREI16_VV,
+                decode_v_vv,
+            ),
+            InstructionFilter::new(
+                "vcompress.vm",
+                inst::MASK_VCOMPRESS_VM,
+                inst::MATCH_VCOMPRESS_VM,
+                decode_v_mm,
+            ),
+            InstructionFilter::new(
+                "vmv1r.v",
+                inst::MASK_VMV1R_V,
+                inst::MATCH_VMV1R_V,
+                decode_v_unary_no_mask,
+            ),
+            InstructionFilter::new(
+                "vmv2r.v",
+                inst::MASK_VMV2R_V,
+                inst::MATCH_VMV2R_V,
+                decode_v_unary_no_mask,
+            ),
+            InstructionFilter::new(
+                "vmv4r.v",
+                inst::MASK_VMV4R_V,
+                inst::MATCH_VMV4R_V,
+                decode_v_unary_no_mask,
+            ),
+            InstructionFilter::new(
+                "vmv8r.v",
+                inst::MASK_VMV8R_V,
+                inst::MATCH_VMV8R_V,
+                decode_v_unary_no_mask,
+            ),
+        ]
     } else {
         vec![]
     };
 
-    i_pseudo_instruction_inst_filters.extend(i_inst_filters);
-    i_pseudo_instruction_inst_filters.extend(m_inst_filters);
-    i_pseudo_instruction_inst_filters.extend(a_inst_filters);
-    i_pseudo_instruction_inst_filters.extend(f_inst_filters);
-    i_pseudo_instruction_inst_filters.extend(d_inst_filters);
-    i_pseudo_instruction_inst_filters.extend(c_inst_filters);
-    i_pseudo_instruction_inst_filters
+    let mut inst_filters = with_extension(i_pseudo_instruction_inst_filters, Extensions::I);
+    inst_filters.extend(with_extension(i_inst_filters, Extensions::I));
+    inst_filters.extend(with_extension(zicsr_inst_filters, Extensions::ZICSR));
+    inst_filters.extend(with_extension(zifencei_inst_filters, Extensions::ZIFENCEI));
+    inst_filters.extend(with_extension(mul_inst_filters, mul_extension));
+    inst_filters.extend(with_extension(div_inst_filters, Extensions::M));
+    inst_filters.extend(with_extension(a_inst_filters, Extensions::A));
+    inst_filters.extend(with_extension(f_inst_filters, Extensions::F));
+    inst_filters.extend(with_extension(d_inst_filters, Extensions::D));
+    inst_filters.extend(with_extension(q_inst_filters, Extensions::Q));
+    inst_filters.extend(with_extension(c_inst_filters, Extensions::C));
+    inst_filters.extend(with_extension(zba_inst_filters, Extensions::ZBA));
+    inst_filters.extend(with_extension(zbb_inst_filters, Extensions::ZBB));
+    inst_filters.extend(with_extension(zbc_inst_filters, Extensions::ZBC));
+    inst_filters.extend(with_extension(zbkb_inst_filters, Extensions::ZBKB));
+    inst_filters.extend(with_extension(zbkc_inst_filters, Extensions::ZBKC));
+    inst_filters.extend(with_extension(zbs_inst_filters, Extensions::ZBS));
+    inst_filters.extend(with_extension(h_inst_filters, Extensions::H));
+    inst_filters.extend(with_extension(v_inst_filters, Extensions::V));
+    inst_filters
 }
 
 #[cfg(test)]
@@ -2011,7 +5926,34 @@ mod test {
         assert_eq!(inst_4byte.length, InstructionLen::FourByte);
         assert_eq!(inst_2byte.length, InstructionLen::TwoByte);
 
-        assert!(InstructionBits::new(0x0000_001f).is_err());
+        let inst_6byte = InstructionBits::new(0xffff_1234_5678_001f).unwrap();
+        let inst_8byte = InstructionBits::new(0x1234_5678_9abc_003f).unwrap();
+        assert_eq!(inst_6byte.length, InstructionLen::SixByte);
+        assert_eq!(inst_6byte.bits, 0x1234_5678_001f);
+        assert_eq!(inst_8byte.length, InstructionLen::EightByte);
+        assert_eq!(inst_8byte.bits, 0x1234_5678_9abc_003f);
+
+        // A 32-bit instruction discards the upper bits.
+        assert_eq!(
+            InstructionBits::new(0xffff_ffff_0000_0003).unwrap().bits,
+            0x3
+        );
+
+        assert_eq!(
+            InstructionBits::new(0x0000_007f),
+            Err(DecodeError::BadLength(0x0000_007f))
+        );
+    }
+
+    #[test]
+    fn encoded_lengths() {
+        assert_eq!(encoded_len(0x0001), Some(2));
+        assert_eq!(encoded_len(0x0003), Some(4));
+        assert_eq!(encoded_len(0x001f), Some(6));
+        assert_eq!(encoded_len(0x003f), Some(8));
+        assert_eq!(encoded_len(0x007f), Some(10));
+        assert_eq!(encoded_len(0x607f), Some(22));
+        assert_eq!(encoded_len(0x707f), None);
     }
 
     #[test]
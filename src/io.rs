@@ -1,31 +1,193 @@
-use std::io::{self, BufRead, Write};
+use std::io::{self, BufRead, Read, Write};
+use std::str::FromStr;
 
+use super::decode::DecodeError;
 use super::disasm::Disassembler;
 use super::instruction::InstructionBits;
-use super::parser;
+use super::parser::{self, RegAccess};
+use super::registers::RegisterNames;
+use super::trace::{self, TraceFormat, TraceInst};
+use super::Xlen;
 
+/// Writes the text shown in place of an instruction that couldn't be decoded, as spike does:
+/// `unknown`, or an error for instructions of unsupported lengths.
+pub fn write_decode_error<W: Write>(ostream: &mut W, e: DecodeError) -> io::Result<()> {
+    match e {
+        DecodeError::BadLength(_) => write!(ostream, "(error interpreting instruction: {})", e),
+        _ => ostream.write_all(b"unknown"),
+    }
+}
+
+/// Like `write_decode_error`, but follows `unknown` with the reason for reserved encodings and
+/// instructions from disabled extensions, for output that doesn't need to match spike's.
+pub fn write_decode_error_reason<W: Write>(ostream: &mut W, e: DecodeError) -> io::Result<()> {
+    write_decode_error(ostream, e)?;
+    match e {
+        DecodeError::BadLength(_) | DecodeError::IllegalInstruction(_) => Ok(()),
+        DecodeError::ReservedEncoding(_, xlen) => {
+            let xlen = match xlen {
+                Xlen::Rv32 => "RV32",
+                Xlen::Rv64 => "RV64",
+            };
+            write!(ostream, " (reserved on {})", xlen)
+        }
+        DecodeError::ExtensionNotEnabled(_, extensions) => {
+            ostream.write_all(b" (requires ")?;
+            for (idx, name) in extensions.names().enumerate() {
+                if idx != 0 {
+                    ostream.write_all(b", ")?;
+                }
+                ostream.write_all(name.as_bytes())?;
+            }
+            ostream.write_all(b")")
+        }
+    }
+}
+
+/// How `process_trace` shows the `W[...]` and `R[...]` register fields of Rocket traces.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RegisterFields {
+    /// Replace the register number and value, e.g. `W[r10=0000000000010040][1]` becomes
+    /// `W[a0=0x10040][1]`.
+    Rewrite,
+    /// Follow each field with the register name and value, e.g.
+    /// `W[r10=0000000000010040][1] a0=0x10040`.
+    Annotate,
+}
+
+impl FromStr for RegisterFields {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "rewrite" => Ok(Self::Rewrite),
+            "annotate" => Ok(Self::Annotate),
+            _ => Err(format!("unknown register field style '{}'", s)),
+        }
+    }
+}
+
+/// Options for rewriting the rest of a trace line, beyond the disassembly itself.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TraceOptions {
+    /// How to show register fields, which are left as they are if this is `None`.
+    pub register_fields: Option<RegisterFields>,
+    /// Follow the disassembly of each load, store and AMO with the address it accesses, computed
+    /// from the value of its base register in the `R[...]` fields, e.g. `ld a0, 8(sp) # 0x1008`.
+    pub effective_addresses: bool,
+}
+
+/// How many lines `process_trace` looks through to detect the trace format.
+const DETECT_LINES: usize = 64;
+
+/// Returns `format`, or otherwise the first built-in format to find an instruction in the first
+/// lines of `istream`, falling back on Rocket traces if none do. The lines read in doing so are
+/// returned too.
+fn detect_format<'a, I: BufRead>(
+    istream: &mut I,
+    format: Option<&'a dyn TraceFormat>,
+) -> io::Result<(String, &'a dyn TraceFormat)> {
+    let mut head = String::new();
+    if let Some(format) = format {
+        return Ok((head, format));
+    }
+
+    for _ in 0..DETECT_LINES {
+        let start = head.len();
+        if istream.read_line(&mut head)? == 0 {
+            break;
+        }
+        if let Some(format) = trace::detect(&[&head[start..]]) {
+            return Ok((head, format));
+        }
+    }
+    Ok((head, &trace::Rocket))
+}
+
+/// Calls `f` on every instruction that `format` finds in `istream`, as `process_trace` would
+/// disassemble them.
+pub fn for_each_trace_inst<I: BufRead>(
+    mut istream: I,
+    format: Option<&dyn TraceFormat>,
+    mut f: impl FnMut(&TraceInst),
+) -> io::Result<()> {
+    let (head, format) = detect_format(&mut istream, format)?;
+    let mut istream = head.as_bytes().chain(istream);
+
+    let mut buf = String::new();
+    loop {
+        buf.clear();
+        if istream.read_line(&mut buf)? == 0 {
+            break;
+        }
+        let line = buf.strip_suffix('\n').unwrap_or(&buf);
+        let line = line.strip_suffix('\r').unwrap_or(line);
+
+        let mut rest = line;
+        while let Some(inst) = format.find(rest) {
+            f(&inst);
+            rest = inst.rest;
+            if !format.multiple_per_line() {
+                break;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Like `process_trace`, for a trace in any of the built-in formats, with the default options.
 pub fn process_streaming_input<I: BufRead, O: Write>(
     istream: I,
     ostream: &mut O,
     disasm: Disassembler,
 ) -> io::Result<()> {
-    for line in istream.lines() {
-        let line = line?;
-        match parser::parse_value(&line) {
-            Ok((end, (begin, x))) => {
-                let s = match InstructionBits::new(x) {
-                    Err(e) => format!("(error interpreting instruction: {})", e),
-                    Ok(inst_bits) => disasm
-                        .fmt_inst(inst_bits)
-                        .unwrap_or_else(|| "unknown".to_string()),
-                };
-                writeln!(ostream, "{}{}{}", begin, s, end)?;
-            }
+    process_trace(istream, ostream, disasm, None, TraceOptions::default())
+}
+
+/// Copies `istream` to `ostream` line by line, splicing in the disassembly of the instructions that
+/// `format` finds in each line. For example, Rocket traces have their `DASM(...)` markers
+/// replaced, while spike commit logs get the disassembly inserted after the raw instruction, ahead
+/// of the register and memory writes.
+///
+/// Without a `format`, it's the first built-in format to find an instruction in the first lines of
+/// `istream`, falling back on Rocket traces if none do.
+///
+/// If the disassembler renders absolute branch/jump targets or has symbols, the PC comes from the
+/// line too. With symbols, the PC field is also followed by the nearest symbol, e.g.
+/// `pc=[0000000080000024] <main+0x24>`.
+///
+/// Lines are read into a single reused buffer and the disassembly is written straight into
+/// `ostream`, so this doesn't allocate per line.
+pub fn process_trace<I: BufRead, O: Write>(
+    mut istream: I,
+    ostream: &mut O,
+    disasm: Disassembler,
+    format: Option<&dyn TraceFormat>,
+    options: TraceOptions,
+) -> io::Result<()> {
+    let (head, format) = detect_format(&mut istream, format)?;
+    // Process the lines read while detecting the format first.
+    let mut istream = head.as_bytes().chain(istream);
+
+    let mut buf = String::new();
+    loop {
+        buf.clear();
+        if istream.read_line(&mut buf)? == 0 {
+            break;
+        }
+        let line = buf.strip_suffix('\n').unwrap_or(&buf);
+        let line = line.strip_suffix('\r').unwrap_or(line);
 
-            // If the parse fails, that means this line doesn't contain anything to disassemble, so
-            // fall back to printing the original line.
-            Err(_) => writeln!(ostream, "{}", line)?,
+        // If this line doesn't contain anything to disassemble, it's printed as it is.
+        let mut rest = line;
+        while let Some(inst) = format.find(rest) {
+            write_trace_inst(ostream, &disasm, &inst, options)?;
+            rest = inst.rest;
+            if !format.multiple_per_line() {
+                break;
+            }
         }
+        writeln!(ostream, "{}", rest)?;
     }
 
     // Technically not necessary, as `ostream` will flush when it's dropped, but this will
@@ -34,3 +196,346 @@ pub fn process_streaming_input<I: BufRead, O: Write>(
 
     Ok(())
 }
+
+/// Writes the prefix of `inst`, with the nearest symbol to its PC (if the disassembler has
+/// symbols) after the PC field, followed by its disassembly.
+fn write_trace_inst<O: Write>(
+    ostream: &mut O,
+    disasm: &Disassembler,
+    inst: &TraceInst,
+    options: TraceOptions,
+) -> io::Result<()> {
+    let pc = inst.pc.map(|(pc, _)| pc);
+    let decoded = InstructionBits::new(inst.bits).and_then(|x| disasm.decode_at(x, pc));
+
+    let symbol = inst
+        .pc
+        .and_then(|(pc, pc_end)| Some((pc_end, disasm.lookup_symbol(pc)?)));
+    let (pc_field, rest) = match symbol {
+        Some((pc_end, _)) => inst.prefix.split_at(pc_end),
+        None => (inst.prefix, ""),
+    };
+    let write_fields = |ostream: &mut O, text: &str| match options.register_fields {
+        Some(style) => {
+            let fp_write = decoded.as_ref().is_ok_and(|d| d.writes_fp_reg());
+            write_reg_fields(ostream, text, style, disasm.register_names(), fp_write)
+        }
+        None => ostream.write_all(text.as_bytes()),
+    };
+    write_fields(ostream, pc_field)?;
+    if let Some((_, symbol)) = symbol {
+        write!(ostream, " {}", symbol)?;
+    }
+    write_fields(ostream, rest)?;
+    ostream.write_all(inst.separator.as_bytes())?;
+
+    let decoded = match decoded {
+        Ok(decoded) => decoded,
+        Err(e) => return write_decode_error(ostream, e),
+    };
+    write!(ostream, "{}", disasm.display(&decoded))?;
+
+    if options.effective_addresses {
+        let address = decoded.memory_operand().and_then(|(base, offset)| {
+            let base_value = read_reg_value(inst.prefix, base)?;
+            Some(base_value.wrapping_add(offset as u64))
+        });
+        if let Some(address) = address {
+            let address = match disasm.isa().map(|isa| isa.xlen) {
+                Some(Xlen::Rv32) => address as u32 as u64,
+                _ => address,
+            };
+            write!(ostream, " # {:#x}", address)?;
+        }
+    }
+    Ok(())
+}
+
+/// Returns the value of integer register `idx` from the first `R[...]` field of `text` that reads
+/// it.
+fn read_reg_value(mut text: &str, idx: u8) -> Option<u64> {
+    while let Ok((rest, (_, field))) = parser::parse_reg_field(text) {
+        if field.access == RegAccess::Read && !field.fp && field.idx == idx {
+            return Some(field.value);
+        }
+        text = rest;
+    }
+    None
+}
+
+/// Writes `text` with its register fields shown in the given `style`.
+///
+/// The fields hold register numbers, which are named as integer registers, except for writes
+/// that are marked as floating-point or come from an instruction that writes a floating-point
+/// register (`fp_write`).
+fn write_reg_fields<O: Write>(
+    ostream: &mut O,
+    mut text: &str,
+    style: RegisterFields,
+    names: RegisterNames,
+    fp_write: bool,
+) -> io::Result<()> {
+    while let Ok((rest, (begin, field))) = parser::parse_reg_field(text) {
+        let field_text = &text[begin.len()..text.len() - rest.len()];
+        let fp = field.fp || (fp_write && matches!(field.access, RegAccess::Write { .. }));
+        let name = if fp {
+            names.fp(field.idx)
+        } else {
+            names.int(field.idx)
+        };
+
+        ostream.write_all(begin.as_bytes())?;
+        match style {
+            RegisterFields::Rewrite => {
+                // Keep the `W`/`R` and everything from the end of the value, such as the `[1]`
+                // write-enable field.
+                let value_end = field_text.find(']').unwrap();
+                write!(
+                    ostream,
+                    "{}[{}={:#x}{}",
+                    &field_text[..1],
+                    name,
+                    field.value,
+                    &field_text[value_end..]
+                )?;
+            }
+            RegisterFields::Annotate => {
+                write!(ostream, "{} {}={:#x}", field_text, name, field.value)?
+            }
+        }
+        text = rest;
+    }
+    ostream.write_all(text.as_bytes())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::decode::TargetStyle;
+    use crate::instruction;
+    use crate::symbols::{Symbol, SymbolTable};
+    use crate::{Extensions, Xlen};
+
+    fn process(input: &str, disasm: Disassembler) -> String {
+        let mut output = Vec::new();
+        process_streaming_input(input.as_bytes(), &mut output, disasm).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn annotate_symbols() {
+        let symbols = SymbolTable::new(vec![
+            Symbol {
+                name: "main".to_string(),
+                address: 0x80000000,
+                size: 0x20,
+            },
+            Symbol {
+                name: "square".to_string(),
+                address: 0x80000020,
+                size: 0,
+            },
+        ]);
+        let instructions = instruction::gen_instructions(Xlen::Rv64, Extensions::IMAFDC, true);
+        let disasm = Disassembler::new(instructions).with_symbols(symbols);
+
+        let input = "\
+C0: 3 [1] pc=[0000000080000014] inst=[00c000ef] DASM(00c000ef)
+C0: 4 [1] pc=[0000000070000000] inst=[00c000ef] DASM(00c000ef)
+no pc here DASM(00008082)
+";
+        let expected = "\
+C0: 3 [1] pc=[0000000080000014] <main+0x14> inst=[00c000ef] jal     pc + 0xc <square>
+C0: 4 [1] pc=[0000000070000000] inst=[00c000ef] jal     pc + 0xc
+no pc here ret
+";
+        assert_eq!(process(input, disasm), expected);
+    }
+
+    #[test]
+    fn commit_log() {
+        let symbols = SymbolTable::new(vec![Symbol {
+            name: "_start".to_string(),
+            address: 0x80000000,
+            size: 0,
+        }]);
+        let instructions = instruction::gen_instructions(Xlen::Rv64, Extensions::IMAFDC, true);
+        let disasm = Disassembler::new(instructions)
+            .with_target_style(TargetStyle::Absolute)
+            .with_symbols(symbols);
+
+        let input = "\
+core   0: 3 0x0000000080000000 (0x00000297) x5  0x0000000080000000
+core   0: 3 0x0000000080000004 (0x4505) x10 0x0000000000000001
+core   0: 3 0x0000000080000006 (0x00a2b023) mem 0x0000000080000000 0x0000000000000001
+core   0: 3 0x000000008000000a (0xff7ff06f)
+core   0: exception trap_illegal_instruction, epc 0x000000008000000a
+";
+        let expected = "\
+core   0: 3 0x0000000080000000 <_start> (0x00000297) auipc   t0, 0x0 x5  0x0000000080000000
+core   0: 3 0x0000000080000004 <_start+0x4> (0x4505) c.li    a0, 1 x10 0x0000000000000001
+core   0: 3 0x0000000080000006 <_start+0x6> (0x00a2b023) sd      a0, 0(t0) mem 0x0000000080000000 0x0000000000000001
+core   0: 3 0x000000008000000a <_start+0xa> (0xff7ff06f) j       0x80000000 <_start>
+core   0: exception trap_illegal_instruction, epc 0x000000008000000a
+";
+        assert_eq!(process(input, disasm), expected);
+    }
+
+    #[test]
+    fn register_fields() {
+        let input = "\
+C0: 19 [1] pc=[0000000000010040] W[r10=0000000000010040][1] R[r 0=0000000000000000] R[r 0=0000000000000000] inst=[00000517] DASM(00000517)
+C0: 20 [1] pc=[0000000000010044] W[r10=3ff0000000000000][1] R[r11=0000000000001000] R[r 0=0000000000000000] inst=[0005b507] DASM(0005b507)
+";
+        let process_fields = |style, names| {
+            let instructions = instruction::gen_instructions(Xlen::Rv64, Extensions::IMAFDC, true);
+            let disasm = Disassembler::new(instructions).with_register_names(names);
+            let options = TraceOptions {
+                register_fields: Some(style),
+                ..TraceOptions::default()
+            };
+            let mut output = Vec::new();
+            process_trace(input.as_bytes(), &mut output, disasm, None, options).unwrap();
+            String::from_utf8(output).unwrap()
+        };
+
+        // The write of `fld` is to a floating-point register.
+        let expected = "\
+C0: 19 [1] pc=[0000000000010040] W[a0=0x10040][1] R[zero=0x0] R[zero=0x0] inst=[00000517] auipc   a0, 0x0
+C0: 20 [1] pc=[0000000000010044] W[fa0=0x3ff0000000000000][1] R[a1=0x1000] R[zero=0x0] inst=[0005b507] fld     fa0, 0(a1)
+";
+        assert_eq!(
+            process_fields(RegisterFields::Rewrite, RegisterNames::Abi),
+            expected
+        );
+
+        let expected = "\
+C0: 19 [1] pc=[0000000000010040] W[r10=0000000000010040][1] x10=0x10040 R[r 0=0000000000000000] x0=0x0 R[r 0=0000000000000000] x0=0x0 inst=[00000517] auipc   x10, 0x0
+C0: 20 [1] pc=[0000000000010044] W[r10=3ff0000000000000][1] f10=0x3ff0000000000000 R[r11=0000000000001000] x11=0x1000 R[r 0=0000000000000000] x0=0x0 inst=[0005b507] fld     f10, 0(x11)
+";
+        assert_eq!(
+            process_fields(RegisterFields::Annotate, RegisterNames::Numeric),
+            expected
+        );
+    }
+
+    #[test]
+    fn effective_addresses() {
+        let input = "\
+C0: 1 [1] pc=[0000000080000000] W[r10=0000000000000005][1] R[r 2=0000000080001000] R[r 0=0000000000000000] inst=[00813503] DASM(00813503)
+C0: 2 [1] pc=[0000000080000004] W[r 0=0000000000000000][0] R[r 8=0000000080002000] R[r10=0000000000000005] inst=[fea43c23] DASM(fea43c23)
+C0: 3 [1] pc=[0000000080000008] W[r10=0000000000000000][1] R[r 9=ffffffff80002000] R[r10=0000000000000005] inst=[c088] DASM(c088)
+C0: 4 [1] pc=[000000008000000a] W[r11=0000000000000000][1] R[r10=0000000080003000] R[r12=0000000000000001] inst=[00c525af] DASM(00c525af)
+C0: 5 [1] pc=[000000008000000e] W[r10=0000000000000000][1] R[r 0=0000000000000000] R[r 0=0000000000000000] inst=[00000513] DASM(00000513)
+C0: 6 [1] pc=[0000000080000012] W[r10=0000000000000000][1] inst=[00053503] DASM(00053503)
+";
+        let instructions = instruction::gen_instructions(Xlen::Rv64, Extensions::IMAFDC, true);
+        let disasm = Disassembler::new(instructions);
+        let options = TraceOptions {
+            effective_addresses: true,
+            ..TraceOptions::default()
+        };
+        let mut output = Vec::new();
+        process_trace(input.as_bytes(), &mut output, disasm, None, options).unwrap();
+
+        // Loads, stores (including compressed ones) and AMOs, but not other instructions, nor
+        // lines that don't show the base register.
+        let expected = "\
+C0: 1 [1] pc=[0000000080000000] W[r10=0000000000000005][1] R[r 2=0000000080001000] R[r 0=0000000000000000] inst=[00813503] ld      a0, 8(sp) # 0x80001008
+C0: 2 [1] pc=[0000000080000004] W[r 0=0000000000000000][0] R[r 8=0000000080002000] R[r10=0000000000000005] inst=[fea43c23] sd      a0, -8(s0) # 0x80001ff8
+C0: 3 [1] pc=[0000000080000008] W[r10=0000000000000000][1] R[r 9=ffffffff80002000] R[r10=0000000000000005] inst=[c088] c.sw    a0, 0(s1) # 0xffffffff80002000
+C0: 4 [1] pc=[000000008000000a] W[r11=0000000000000000][1] R[r10=0000000080003000] R[r12=0000000000000001] inst=[00c525af] amoadd.w a1, a2, (a0) # 0x80003000
+C0: 5 [1] pc=[000000008000000e] W[r10=0000000000000000][1] R[r 0=0000000000000000] R[r 0=0000000000000000] inst=[00000513] li      a0, 0
+C0: 6 [1] pc=[0000000080000012] W[r10=0000000000000000][1] inst=[00053503] ld      a0, 0(a0)
+";
+        assert_eq!(String::from_utf8(output).unwrap(), expected);
+
+        // RV32 addresses wrap around at 32 bits.
+        let disasm = Disassembler::for_isa("rv32imac".parse().unwrap(), true);
+        let mut output = Vec::new();
+        let input = "C0: 3 [1] pc=[80000008] R[r 9=fffffffc] inst=[00a4a423] DASM(00a4a423)\n";
+        process_trace(input.as_bytes(), &mut output, disasm, None, options).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "C0: 3 [1] pc=[80000008] R[r 9=fffffffc] inst=[00a4a423] sw      a0, 8(s1) # 0x4\n"
+        );
+    }
+
+    #[test]
+    fn multiple_markers() {
+        let instructions = instruction::gen_instructions(Xlen::Rv64, Extensions::IMAFDC, true);
+        let disasm = Disassembler::new(instructions).with_target_style(TargetStyle::Absolute);
+
+        let input = "\
+C0: 5 [1] pc=[80000000] DASM(00c0006f) [1] pc=[80000004] DASM(0x00c0006f) DASM(4505)
+C0: 6 DASM() DASM(0xzz) DASM(8082] DASM(00000517
+";
+        let expected = "\
+C0: 5 [1] pc=[80000000] j       0x8000000c [1] pc=[80000004] j       0x80000010 c.li    a0, 1
+C0: 6 DASM() DASM(0xzz) ret auipc   a0, 0x0
+";
+        assert_eq!(process(input, disasm), expected);
+    }
+
+    #[test]
+    fn detect_format() {
+        let instructions = instruction::gen_instructions(Xlen::Rv64, Extensions::IMAFDC, true);
+        let disasm = Disassembler::new(instructions);
+
+        // The BOOM format is detected on the second line, and the lines before it are kept.
+        let input = "\
+bbl loader
+3 0x0000000080000000 (0x00000297)
+3 0x0000000080000004 (0x4505) x10 0x0000000000000001
+Not a DASM(00000297) line
+";
+        let expected = "\
+bbl loader
+3 0x0000000080000000 (0x00000297) auipc   t0, 0x0
+3 0x0000000080000004 (0x4505) c.li    a0, 1 x10 0x0000000000000001
+Not a DASM(00000297) line
+";
+        assert_eq!(process(input, disasm), expected);
+    }
+
+    #[test]
+    fn decode_errors() {
+        let disasm = Disassembler::for_isa("rv32imc".parse().unwrap(), true);
+
+        let input = "\
+C0: 1 inst=[0000000b] DASM(0000000b)
+C0: 2 inst=[0015051b] DASM(0015051b)
+C0: 3 inst=[100525af] DASM(100525af)
+C0: 4 inst=[00000000001f] DASM(00000000001f)
+C0: 5 inst=[0000007f] DASM(0000007f)
+";
+        let expected = "\
+C0: 1 inst=[0000000b] unknown
+C0: 2 inst=[0015051b] unknown
+C0: 3 inst=[100525af] unknown
+C0: 4 inst=[00000000001f] unknown
+C0: 5 inst=[0000007f] (error interpreting instruction: unsupported instruction length for 7f)
+";
+        assert_eq!(process(input, disasm), expected);
+
+        // Listings and reports follow `unknown` with the reason.
+        let disasm = Disassembler::for_isa("rv32imc".parse().unwrap(), true);
+        let reasons: Vec<_> = [0x0000000b, 0x0015051b, 0x100525af]
+            .iter()
+            .map(|&x| {
+                let e = disasm.decode(InstructionBits::new(x).unwrap()).unwrap_err();
+                let mut output = Vec::new();
+                write_decode_error_reason(&mut output, e).unwrap();
+                String::from_utf8(output).unwrap()
+            })
+            .collect();
+        assert_eq!(
+            reasons,
+            [
+                "unknown",
+                "unknown (reserved on RV32)",
+                "unknown (requires a)"
+            ]
+        );
+    }
+}
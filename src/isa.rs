@@ -0,0 +1,742 @@
+use std::error::Error;
+use std::fmt;
+use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, Not, Sub, SubAssign};
+use std::str::FromStr;
+
+use super::Xlen;
+
+/// A set of RISC-V ISA extensions, stored as one bit per extension.
+///
+/// Sets are built by combining the single-extension constants, e.g. `Extensions::I |
+/// Extensions::M | Extensions::ZICSR`, and queried with the `has_*()` methods.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Extensions(u64);
+
+macro_rules! extensions {
+    ($($(#[$doc:meta])* ($bit:literal, $konst:ident, $has:ident, $name:literal),)*) => {
+        impl Extensions {
+            $(
+                $(#[$doc])*
+                pub const $konst: Extensions = Extensions(1 << $bit);
+            )*
+
+            $(
+                pub const fn $has(self) -> bool {
+                    self.contains(Self::$konst)
+                }
+            )*
+
+            /// Every extension known to the disassembler, paired with its canonical lowercase
+            /// name, in canonical ISA-string order.
+            pub const ALL: &'static [(Extensions, &'static str)] = &[$((Self::$konst, $name),)*];
+        }
+    };
+}
+
+extensions! {
+    /// Base integer instruction set.
+    (0, I, has_i, "i"),
+    /// Reduced base integer instruction set, with only 16 integer registers.
+    (1, E, has_e, "e"),
+    /// Integer multiplication and division.
+    (2, M, has_m, "m"),
+    /// Atomic instructions.
+    (3, A, has_a, "a"),
+    /// Single-precision floating-point.
+    (4, F, has_f, "f"),
+    /// Double-precision floating-point.
+    (5, D, has_d, "d"),
+    /// Quad-precision floating-point.
+    (6, Q, has_q, "q"),
+    /// Compressed instructions.
+    (7, C, has_c, "c"),
+    /// Vector operations.
+    (8, V, has_v, "v"),
+    /// Hypervisor.
+    (9, H, has_h, "h"),
+    /// Control and status register instructions.
+    (10, ZICSR, has_zicsr, "zicsr"),
+    /// Instruction-fetch fence.
+    (11, ZIFENCEI, has_zifencei, "zifencei"),
+    /// Pause hint.
+    (12, ZIHINTPAUSE, has_zihintpause, "zihintpause"),
+    /// Cache-block management instructions.
+    (13, ZICBOM, has_zicbom, "zicbom"),
+    /// Cache-block prefetch instructions.
+    (14, ZICBOP, has_zicbop, "zicbop"),
+    /// Cache-block zero instructions.
+    (15, ZICBOZ, has_zicboz, "zicboz"),
+    /// Multiplication without division.
+    (16, ZMMUL, has_zmmul, "zmmul"),
+    /// Half-precision floating-point.
+    (17, ZFH, has_zfh, "zfh"),
+    /// Minimal half-precision floating-point.
+    (18, ZFHMIN, has_zfhmin, "zfhmin"),
+    /// Address generation.
+    (19, ZBA, has_zba, "zba"),
+    /// Basic bit-manipulation.
+    (20, ZBB, has_zbb, "zbb"),
+    /// Carry-less multiplication.
+    (21, ZBC, has_zbc, "zbc"),
+    /// Single-bit instructions.
+    (22, ZBS, has_zbs, "zbs"),
+    /// Bit-manipulation for cryptography.
+    (23, ZBKB, has_zbkb, "zbkb"),
+    /// Carry-less multiplication for cryptography.
+    (24, ZBKC, has_zbkc, "zbkc"),
+    /// Crossbar permutations.
+    (25, ZBKX, has_zbkx, "zbkx"),
+    /// NIST AES decryption.
+    (26, ZKND, has_zknd, "zknd"),
+    /// NIST AES encryption.
+    (27, ZKNE, has_zkne, "zkne"),
+    /// NIST hash functions.
+    (28, ZKNH, has_zknh, "zknh"),
+    /// ShangMi block cipher.
+    (29, ZKSED, has_zksed, "zksed"),
+    /// ShangMi hash function.
+    (30, ZKSH, has_zksh, "zksh"),
+    /// Entropy source.
+    (31, ZKR, has_zkr, "zkr"),
+    /// Data-independent execution latency.
+    (32, ZKT, has_zkt, "zkt"),
+    /// State-enable CSRs.
+    (33, SMSTATEEN, has_smstateen, "smstateen"),
+    /// Count overflow and mode-based filtering.
+    (34, SSCOFPMF, has_sscofpmf, "sscofpmf"),
+    /// Supervisor-mode timer interrupts.
+    (35, SSTC, has_sstc, "sstc"),
+    /// Fine-grained address-translation cache invalidation.
+    (36, SVINVAL, has_svinval, "svinval"),
+    /// NAPOT translation contiguity.
+    (37, SVNAPOT, has_svnapot, "svnapot"),
+    /// Page-based memory types.
+    (38, SVPBMT, has_svpbmt, "svpbmt"),
+    /// T-Head address generation.
+    (39, XTHEADBA, has_xtheadba, "xtheadba"),
+    /// T-Head basic bit-manipulation.
+    (40, XTHEADBB, has_xtheadbb, "xtheadbb"),
+    /// T-Head single-bit instructions.
+    (41, XTHEADBS, has_xtheadbs, "xtheadbs"),
+    /// Ventana integer conditional operations.
+    (42, XVENTANACONDOPS, has_xventanacondops, "xventanacondops"),
+}
+
+impl Extensions {
+    /// `IMC`, plus Zicsr and Zifencei.
+    pub const IMC: Extensions = Self::I
+        .union(Self::M)
+        .union(Self::C)
+        .union(Self::ZICSR)
+        .union(Self::ZIFENCEI);
+    /// `IMAC`, plus Zicsr and Zifencei.
+    pub const IMAC: Extensions = Self::IMC.union(Self::A);
+    /// `IMAFDC`, plus Zicsr and Zifencei (i.e. `GC`).
+    pub const IMAFDC: Extensions = Self::IMAC.union(Self::F).union(Self::D);
+
+    /// The set containing no extensions.
+    pub const fn empty() -> Self {
+        Extensions(0)
+    }
+
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Returns `true` if every extension in `other` is also in `self`.
+    pub const fn contains(self, other: Extensions) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    pub const fn union(self, other: Extensions) -> Self {
+        Extensions(self.0 | other.0)
+    }
+
+    pub const fn intersection(self, other: Extensions) -> Self {
+        Extensions(self.0 & other.0)
+    }
+
+    pub const fn difference(self, other: Extensions) -> Self {
+        Extensions(self.0 & !other.0)
+    }
+
+    pub fn insert(&mut self, other: Extensions) {
+        self.0 |= other.0;
+    }
+
+    pub fn remove(&mut self, other: Extensions) {
+        self.0 &= !other.0;
+    }
+
+    /// Looks up a single extension by its (lowercase) name.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .find(|(_, ext_name)| *ext_name == name)
+            .map(|(ext, _)| *ext)
+    }
+
+    /// Adds the extensions implied by the ones already in the set, e.g. F for D.
+    pub fn with_implied(self) -> Self {
+        const IMPLICATIONS: &[(Extensions, Extensions)] = &[
+            (Extensions::Q, Extensions::D),
+            (Extensions::V, Extensions::D),
+            (Extensions::D, Extensions::F),
+            (Extensions::ZFH, Extensions::ZFHMIN),
+            (Extensions::ZFHMIN, Extensions::F),
+            (Extensions::F, Extensions::ZICSR),
+            (Extensions::H, Extensions::ZICSR),
+            (Extensions::M, Extensions::ZMMUL),
+        ];
+
+        // The table is ordered so that a single pass reaches every transitive implication.
+        IMPLICATIONS.iter().fold(self, |acc, &(ext, implied)| {
+            if acc.contains(ext) {
+                acc | implied
+            } else {
+                acc
+            }
+        })
+    }
+
+    /// Iterates over the canonical names of the extensions in this set.
+    pub fn names(self) -> impl Iterator<Item = &'static str> {
+        Self::ALL
+            .iter()
+            .filter(move |(ext, _)| self.contains(*ext))
+            .map(|(_, name)| *name)
+    }
+}
+
+impl BitOr for Extensions {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        self.union(rhs)
+    }
+}
+
+impl BitOrAssign for Extensions {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.insert(rhs);
+    }
+}
+
+impl BitAnd for Extensions {
+    type Output = Self;
+
+    fn bitand(self, rhs: Self) -> Self {
+        self.intersection(rhs)
+    }
+}
+
+impl BitAndAssign for Extensions {
+    fn bitand_assign(&mut self, rhs: Self) {
+        self.0 &= rhs.0;
+    }
+}
+
+impl Sub for Extensions {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        self.difference(rhs)
+    }
+}
+
+impl SubAssign for Extensions {
+    fn sub_assign(&mut self, rhs: Self) {
+        self.remove(rhs);
+    }
+}
+
+impl Not for Extensions {
+    type Output = Self;
+
+    fn not(self) -> Self {
+        Self::ALL
+            .iter()
+            .fold(Self::empty(), |acc, (ext, _)| acc | *ext)
+            .difference(self)
+    }
+}
+
+impl fmt::Debug for Extensions {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_set().entries(self.names()).finish()
+    }
+}
+
+/// A RISC-V ISA description: the register width plus the set of supported extensions.
+///
+/// This is parsed from the ISA strings used by `gcc -march`, `spike --isa` or the device-tree
+/// `riscv,isa` property, e.g. `rv64imafdc_zicsr_zifencei_zba_zbb`. Parsing follows the rules from
+/// the "ISA Extension Naming Conventions" chapter of the unprivileged spec:
+///
+/// * The string starts with `rv32` or `rv64`, followed by the base ISA `i`, `e` or `g`. `g` is
+///   shorthand for `imafd_zicsr_zifencei`.
+/// * Single-letter extensions follow directly, and multi-letter `z*`, `s*` and `x*` extensions
+///   are separated by underscores.
+/// * Any extension may carry a version suffix (`m2p0`, `zba1p0`), which is ignored.
+/// * Extensions implied by others (e.g. F by D) are added automatically. Like spike, a base `i`
+///   also implies Zicsr and Zifencei, which were part of the base ISA before they were split out.
+/// * Ratified extensions without instructions of their own (e.g. `zicntr`, `zvl128b`) are
+///   accepted, subsets of a larger extension (e.g. `zaamo`, `zca`, `zve32f`) are decoded as the
+///   whole of it, and `b` as Zba, Zbb and Zbs.
+///
+/// Zbkb and Zbkc only decode the instructions they share with Zbb and Zbc (e.g. `andn`, `clmul`),
+/// not `pack`, `brev8` or `zip`. Some extensions are accepted but none of their instructions are
+/// decoded yet: Zicbom, Zicbop, Zicboz, Zfh, Zfhmin, Zbkx, the Zk* scalar crypto extensions,
+/// Svinval, and the vendor X* extensions. Zihintpause's `pause` is decoded as the `fence` it's
+/// encoded as.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Isa {
+    pub xlen: Xlen,
+    pub extensions: Extensions,
+}
+
+/// Errors from parsing an ISA string into an `Isa`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum IsaParseError {
+    /// The string doesn't start with `rv32` or `rv64`.
+    MissingXlen,
+    /// The register width isn't one the disassembler supports (e.g. `rv128`).
+    UnsupportedXlen(String),
+    /// The register width isn't followed by a base ISA (`i`, `e` or `g`).
+    MissingBase,
+    /// The named extension isn't known.
+    UnknownExtension(String),
+    /// The named extension appears more than once.
+    DuplicateExtension(String),
+    /// The two named extensions can't be used together.
+    ConflictingExtensions(&'static str, &'static str),
+}
+
+impl fmt::Display for IsaParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            IsaParseError::MissingXlen => {
+                write!(f, "ISA string must start with 'rv32' or 'rv64'")
+            }
+            IsaParseError::UnsupportedXlen(xlen) => {
+                write!(
+                    f,
+                    "unsupported XLEN 'rv{}', expected 'rv32' or 'rv64'",
+                    xlen
+                )
+            }
+            IsaParseError::MissingBase => {
+                write!(
+                    f,
+                    "ISA string must name a base ISA ('i', 'e' or 'g') after the XLEN"
+                )
+            }
+            IsaParseError::UnknownExtension(name) => write!(f, "unknown extension '{}'", name),
+            IsaParseError::DuplicateExtension(name) => {
+                write!(f, "extension '{}' appears more than once", name)
+            }
+            IsaParseError::ConflictingExtensions(a, b) => {
+                write!(f, "extensions '{}' and '{}' can't be used together", a, b)
+            }
+        }
+    }
+}
+
+impl Error for IsaParseError {}
+
+/// Ratified extensions that ISA strings may name, but that have no `Extensions` constant of
+/// their own, with the extensions they're decoded as.
+///
+/// Some only add CSRs or architectural guarantees, so there's nothing more to decode. Others are
+/// subsets of a larger extension, and are decoded as the whole of it (but without the extensions
+/// that it implies, so `zve32x` doesn't bring in D as `v` does). `b` stands for Zba, Zbb and Zbs,
+/// which toolchains name alongside it.
+const EXTENSION_ALIASES: &[(&str, Extensions)] = &[
+    (
+        "b",
+        Extensions::ZBA
+            .union(Extensions::ZBB)
+            .union(Extensions::ZBS),
+    ),
+    ("zicntr", Extensions::ZICSR),
+    ("zihpm", Extensions::ZICSR),
+    ("zihintntl", Extensions::empty()),
+    ("zicclsm", Extensions::empty()),
+    ("ziccif", Extensions::empty()),
+    ("ziccrse", Extensions::empty()),
+    ("ziccamoa", Extensions::empty()),
+    ("zic64b", Extensions::empty()),
+    ("za64rs", Extensions::empty()),
+    ("za128rs", Extensions::empty()),
+    ("zama16b", Extensions::empty()),
+    ("zaamo", Extensions::A),
+    ("zalrsc", Extensions::A),
+    ("zca", Extensions::C),
+    ("zcf", Extensions::C),
+    ("zcd", Extensions::C),
+    ("zve32x", Extensions::V),
+    ("zve32f", Extensions::V),
+    ("zve64x", Extensions::V),
+    ("zve64f", Extensions::V),
+    ("zve64d", Extensions::V),
+    ("smaia", Extensions::empty()),
+    ("ssaia", Extensions::empty()),
+    ("smepmp", Extensions::empty()),
+    ("ssccptr", Extensions::empty()),
+    ("sscounterenw", Extensions::empty()),
+    ("ssstateen", Extensions::empty()),
+    ("sstvala", Extensions::empty()),
+    ("sstvecd", Extensions::empty()),
+    ("ssu64xl", Extensions::empty()),
+    ("shcounterenw", Extensions::empty()),
+    ("shgatpa", Extensions::empty()),
+    ("shtvala", Extensions::empty()),
+    ("shvsatpa", Extensions::empty()),
+    ("shvstvala", Extensions::empty()),
+    ("shvstvecd", Extensions::empty()),
+    ("svade", Extensions::empty()),
+    ("svadu", Extensions::empty()),
+    ("svbare", Extensions::empty()),
+];
+
+/// Returns the extensions that `name` is decoded as, if it's one of `EXTENSION_ALIASES` or a
+/// minimum vector length (`zvl128b`).
+fn alias_extensions(name: &str) -> Option<Extensions> {
+    let is_vector_length = name
+        .strip_prefix("zvl")
+        .and_then(|rest| rest.strip_suffix('b'))
+        .is_some_and(|bits| !bits.is_empty() && bits.bytes().all(|b| b.is_ascii_digit()));
+    if is_vector_length {
+        return Some(Extensions::empty());
+    }
+    EXTENSION_ALIASES
+        .iter()
+        .find(|(alias, _)| *alias == name)
+        .map(|(_, ext)| *ext)
+}
+
+/// Splits a `<major>p<minor>` version suffix off the end of `s`, if there is one.
+fn strip_version(s: &str) -> &str {
+    let without_minor = match s.rfind('p') {
+        Some(pos)
+            if pos + 1 < s.len()
+                && s[pos + 1..].bytes().all(|b| b.is_ascii_digit())
+                && s[..pos].ends_with(|c: char| c.is_ascii_digit()) =>
+        {
+            &s[..pos]
+        }
+        _ => s,
+    };
+    without_minor.trim_end_matches(|c: char| c.is_ascii_digit())
+}
+
+/// Returns the length of the `<major>[p<minor>]` version at the start of `s`.
+fn version_len(s: &str) -> usize {
+    let major = s.bytes().take_while(|b| b.is_ascii_digit()).count();
+    if major == 0 {
+        return 0;
+    }
+    let rest = &s[major..];
+    if let Some(minor) = rest.strip_prefix('p') {
+        let minor = minor.bytes().take_while(|b| b.is_ascii_digit()).count();
+        if minor > 0 {
+            return major + 1 + minor;
+        }
+    }
+    major
+}
+
+impl Isa {
+    /// Parses `s` like `from_str`, but skips multi-letter extensions that aren't known instead of
+    /// failing on them, for ISA strings written by toolchains that know more extensions than we
+    /// do (such as an ELF file's `Tag_RISCV_arch`).
+    pub fn parse_lenient(s: &str) -> Result<Self, IsaParseError> {
+        Self::parse(s, true)
+    }
+
+    fn parse(s: &str, lenient: bool) -> Result<Self, IsaParseError> {
+        let s = s.trim().to_ascii_lowercase();
+        let rest = s.strip_prefix("rv").ok_or(IsaParseError::MissingXlen)?;
+        let xlen_digits = rest.bytes().take_while(|b| b.is_ascii_digit()).count();
+        let xlen = match &rest[..xlen_digits] {
+            "32" => Xlen::Rv32,
+            "64" => Xlen::Rv64,
+            "" => return Err(IsaParseError::MissingXlen),
+            other => return Err(IsaParseError::UnsupportedXlen(other.to_string())),
+        };
+        let rest = &rest[xlen_digits..];
+
+        // Everything named so far, including expansions, and the subset that was named directly
+        // (rather than through the Zicsr/Zifencei implied by `i` and `g`), to catch duplicates.
+        let implicit = Extensions::ZICSR | Extensions::ZIFENCEI;
+        let mut extensions = match rest.chars().next() {
+            Some('i') => Extensions::I | implicit,
+            Some('e') => Extensions::E,
+            Some('g') => Extensions::IMAFDC - Extensions::C,
+            _ => return Err(IsaParseError::MissingBase),
+        };
+        let mut named = extensions - implicit;
+        // Aliases are added after the implications, and aren't extensions named directly.
+        let mut aliases = Extensions::empty();
+        let mut named_aliases = Vec::new();
+        let mut rest = &rest[1..];
+        rest = &rest[version_len(rest)..];
+
+        while !rest.is_empty() {
+            let name = if let Some(stripped) = rest.strip_prefix('_') {
+                rest = stripped;
+                continue;
+            } else if rest.starts_with(['z', 's', 'x']) {
+                let token_len = rest.find('_').unwrap_or(rest.len());
+                let name = strip_version(&rest[..token_len]);
+                rest = &rest[token_len..];
+                name
+            } else {
+                let name = &rest[..rest.chars().next().map_or(1, char::len_utf8)];
+                rest = &rest[name.len()..];
+                rest = &rest[version_len(rest)..];
+                name
+            };
+
+            if let Some(ext) = alias_extensions(name) {
+                if named_aliases.contains(&name) {
+                    return Err(IsaParseError::DuplicateExtension(name.to_string()));
+                }
+                named_aliases.push(name);
+                aliases |= ext;
+                continue;
+            }
+
+            let ext = match name {
+                "g" => Extensions::IMAFDC - Extensions::C - Extensions::I,
+                "i" if extensions.has_e() => {
+                    return Err(IsaParseError::ConflictingExtensions("e", "i"))
+                }
+                "e" if extensions.has_i() => {
+                    return Err(IsaParseError::ConflictingExtensions("i", "e"))
+                }
+                "i" | "e" => return Err(IsaParseError::DuplicateExtension(name.to_string())),
+                _ => match Extensions::from_name(name) {
+                    Some(ext) => ext,
+                    None if lenient && name.len() > 1 => continue,
+                    None => return Err(IsaParseError::UnknownExtension(name.to_string())),
+                },
+            };
+            let direct = if name == "g" { ext - implicit } else { ext };
+            if !(named & direct).is_empty() {
+                return Err(IsaParseError::DuplicateExtension(name.to_string()));
+            }
+            named |= direct;
+            extensions |= ext;
+        }
+
+        let extensions = extensions.with_implied() | aliases;
+        if extensions.has_e() && extensions.has_h() {
+            return Err(IsaParseError::ConflictingExtensions("e", "h"));
+        }
+
+        Ok(Isa { xlen, extensions })
+    }
+}
+
+impl FromStr for Isa {
+    type Err = IsaParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s, false)
+    }
+}
+
+impl fmt::Display for Isa {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let xlen = match self.xlen {
+            Xlen::Rv32 => 32,
+            Xlen::Rv64 => 64,
+        };
+        write!(f, "rv{}", xlen)?;
+        let mut names = self.extensions.names().peekable();
+        while let Some(name) = names.next_if(|name| name.len() == 1) {
+            write!(f, "{}", name)?;
+        }
+        for name in names {
+            write!(f, "_{}", name)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn set_operations() {
+        let imac = Extensions::I | Extensions::M | Extensions::A | Extensions::C;
+        assert!(imac.has_i() && imac.has_m() && imac.has_a() && imac.has_c());
+        assert!(!imac.has_f() && !imac.has_zicsr());
+        assert!(Extensions::IMAC.contains(imac));
+        assert_eq!(
+            Extensions::IMAC - imac,
+            Extensions::ZICSR | Extensions::ZIFENCEI
+        );
+        assert_eq!(imac & Extensions::IMC, imac - Extensions::A);
+        assert_eq!(!Extensions::empty() & imac, imac);
+        assert!((imac & Extensions::ZBA).is_empty());
+
+        let mut ext = Extensions::IMAFDC;
+        ext -= Extensions::D;
+        ext |= Extensions::ZBB;
+        assert!(ext.has_f() && !ext.has_d() && ext.has_zbb());
+        assert_eq!(
+            ext.names().collect::<Vec<_>>(),
+            vec!["i", "m", "a", "f", "c", "zicsr", "zifencei", "zbb"]
+        );
+    }
+
+    #[test]
+    fn parse_isa_strings() {
+        let isa: Isa = "rv64imafdc".parse().unwrap();
+        assert_eq!(isa.xlen, Xlen::Rv64);
+        assert_eq!(isa.extensions, Extensions::IMAFDC | Extensions::ZMMUL);
+
+        let isa: Isa = "rv64gc".parse().unwrap();
+        assert_eq!(isa.extensions, Extensions::IMAFDC | Extensions::ZMMUL);
+
+        let isa: Isa = "RV32IMAC_Zicsr_Zifencei".parse().unwrap();
+        assert_eq!(isa.xlen, Xlen::Rv32);
+        assert_eq!(isa.extensions, Extensions::IMAC | Extensions::ZMMUL);
+
+        let isa: Isa = "rv64i2p1m2p0a2p1f2p2d2p2c2p0_zicsr2p0_zifencei2p0_zba1p0_zbb1p0"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            isa.extensions,
+            Extensions::IMAFDC | Extensions::ZMMUL | Extensions::ZBA | Extensions::ZBB
+        );
+
+        let isa: Isa = "rv64iqv_sstc_xtheadba".parse().unwrap();
+        assert!(isa.extensions.has_q() && isa.extensions.has_d() && isa.extensions.has_f());
+        assert!(isa.extensions.has_v() && isa.extensions.has_sstc());
+        assert!(isa.extensions.has_xtheadba());
+        assert!(!isa.extensions.has_m());
+
+        let isa: Isa = "rv32e_zicsr".parse().unwrap();
+        assert_eq!(isa.extensions, Extensions::E | Extensions::ZICSR);
+    }
+
+    #[test]
+    fn parse_extension_aliases() {
+        // Spike's default ISA, and a current `gcc -march` string.
+        let isa: Isa = "RV64IMAFDC_zicntr_zihpm".parse().unwrap();
+        assert_eq!(isa.extensions, Extensions::IMAFDC | Extensions::ZMMUL);
+        let isa: Isa = "rv64gc_zaamo_zalrsc".parse().unwrap();
+        assert_eq!(isa.extensions, Extensions::IMAFDC | Extensions::ZMMUL);
+
+        let isa: Isa = "rv32i_zaamo_zca_zcf".parse().unwrap();
+        assert_eq!(
+            isa.extensions,
+            Extensions::I
+                | Extensions::ZICSR
+                | Extensions::ZIFENCEI
+                | Extensions::A
+                | Extensions::C
+        );
+
+        // Subsets of V are decoded as V, without the D that V implies.
+        let isa: Isa = "rv32imac_zve32x_zvl128b".parse().unwrap();
+        assert!(isa.extensions.has_v() && !isa.extensions.has_d());
+
+        // `b` is Zba, Zbb and Zbs, which may be named too.
+        for s in ["rv64ib", "rv64i2p1_b1p0_zba1p0_zbb1p0_zbs1p0"] {
+            let isa: Isa = s.parse().unwrap();
+            assert_eq!(
+                isa.extensions,
+                Extensions::I
+                    | Extensions::ZICSR
+                    | Extensions::ZIFENCEI
+                    | Extensions::ZBA
+                    | Extensions::ZBB
+                    | Extensions::ZBS
+            );
+        }
+
+        assert_eq!(
+            "rv64i_zicntr_zicntr".parse::<Isa>().unwrap_err(),
+            IsaParseError::DuplicateExtension("zicntr".to_string())
+        );
+        assert_eq!(
+            "rv64i_zvlb".parse::<Isa>().unwrap_err(),
+            IsaParseError::UnknownExtension("zvlb".to_string())
+        );
+    }
+
+    #[test]
+    fn parse_isa_string_errors() {
+        let err = |s: &str| s.parse::<Isa>().unwrap_err();
+
+        assert_eq!(err("imafdc"), IsaParseError::MissingXlen);
+        assert_eq!(
+            err("rv128i"),
+            IsaParseError::UnsupportedXlen("128".to_string())
+        );
+        assert_eq!(err("rv64mafdc"), IsaParseError::MissingBase);
+        assert_eq!(
+            err("rv64imafdcy"),
+            IsaParseError::UnknownExtension("y".to_string())
+        );
+        assert_eq!(
+            err("rv64i_zfoo"),
+            IsaParseError::UnknownExtension("zfoo".to_string())
+        );
+        assert_eq!(
+            err("rv64imm"),
+            IsaParseError::DuplicateExtension("m".to_string())
+        );
+        assert_eq!(
+            err("rv64gd"),
+            IsaParseError::DuplicateExtension("d".to_string())
+        );
+        assert_eq!(
+            err("rv64i_zba_zba1p0"),
+            IsaParseError::DuplicateExtension("zba".to_string())
+        );
+        assert_eq!(
+            err("rv32ie"),
+            IsaParseError::ConflictingExtensions("i", "e")
+        );
+        assert_eq!(
+            err("rv32eh"),
+            IsaParseError::ConflictingExtensions("e", "h")
+        );
+        assert_eq!(err("rv64i_zfoo").to_string(), "unknown extension 'zfoo'");
+    }
+
+    #[test]
+    fn parse_lenient_isa_strings() {
+        let isa = Isa::parse_lenient("rv64i_zfoo1p0_zba_xbar").unwrap();
+        assert_eq!(
+            isa.extensions,
+            Extensions::I | Extensions::ZICSR | Extensions::ZIFENCEI | Extensions::ZBA
+        );
+        // Only multi-letter extensions are skipped.
+        assert_eq!(
+            Isa::parse_lenient("rv64imafdcy").unwrap_err(),
+            IsaParseError::UnknownExtension("y".to_string())
+        );
+        let isa = Isa::parse_lenient("rv64imafdcb").unwrap();
+        assert!(isa
+            .extensions
+            .contains(Extensions::ZBA | Extensions::ZBB | Extensions::ZBS));
+        assert_eq!(
+            Isa::parse_lenient("imafdc"),
+            Err(IsaParseError::MissingXlen)
+        );
+    }
+
+    #[test]
+    fn display_isa() {
+        let isa: Isa = "rv64gc_zbb_sstc".parse().unwrap();
+        assert_eq!(isa.to_string(), "rv64imafdc_zicsr_zifencei_zmmul_zbb_sstc");
+        assert_eq!(isa.to_string().parse::<Isa>().unwrap(), isa);
+    }
+}
@@ -1,67 +1,28 @@
+pub mod cfg;
 pub mod csrs;
+pub mod decode;
 pub mod disasm;
+pub mod elf;
+pub mod image;
 #[allow(dead_code)]
 pub mod inst;
 pub mod instruction;
 pub mod io;
+pub mod isa;
+pub mod listing;
 pub mod parser;
+pub mod profile;
 pub mod registers;
+pub mod stats;
+pub mod symbols;
+#[cfg(test)]
+mod test_util;
+pub mod trace;
 
+pub use isa::{Extensions, Isa};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Xlen {
     Rv32,
     Rv64,
 }
-
-pub enum Extensions {
-    I,
-    IMC,
-    IMAC,
-    IMAFDC,
-}
-
-impl Extensions {
-    pub fn has_m(&self) -> bool {
-        match self {
-            Extensions::I => false,
-            Extensions::IMC => true,
-            Extensions::IMAC => true,
-            Extensions::IMAFDC => true,
-        }
-    }
-
-    pub fn has_a(&self) -> bool {
-        match self {
-            Extensions::I => false,
-            Extensions::IMC => false,
-            Extensions::IMAC => true,
-            Extensions::IMAFDC => true,
-        }
-    }
-
-    pub fn has_f(&self) -> bool {
-        match self {
-            Extensions::I => false,
-            Extensions::IMC => false,
-            Extensions::IMAC => false,
-            Extensions::IMAFDC => true,
-        }
-    }
-
-    pub fn has_d(&self) -> bool {
-        match self {
-            Extensions::I => false,
-            Extensions::IMC => false,
-            Extensions::IMAC => false,
-            Extensions::IMAFDC => true,
-        }
-    }
-
-    pub fn has_c(&self) -> bool {
-        match self {
-            Extensions::I => false,
-            Extensions::IMC => true,
-            Extensions::IMAC => true,
-            Extensions::IMAFDC => true,
-        }
-    }
-}
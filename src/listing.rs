@@ -0,0 +1,88 @@
+use std::io::{self, Write};
+
+use super::disasm::Disassembler;
+use super::instruction::{self, InstructionBits};
+use super::io::write_decode_error_reason;
+
+/// Writes the address and raw instruction bits that start each line of a listing. `raw` holds the
+/// instruction's bytes in memory order, which are shown as one little-endian number.
+fn write_raw<W: Write>(ostream: &mut W, address: u64, raw: &[u8]) -> io::Result<()> {
+    write!(ostream, "{:>8x}:\t", address)?;
+    for byte in raw.iter().rev() {
+        write!(ostream, "{:02x}", byte)?;
+    }
+    write!(
+        ostream,
+        "{:pad$}\t",
+        "",
+        pad = 8usize.saturating_sub(2 * raw.len())
+    )
+}
+
+/// Writes an objdump-style linear listing of the instructions in `data`, which is located at
+/// `address`: one line per instruction, with its address, raw bits and disassembly.
+///
+/// Instruction lengths follow the standard encoding in their low bits, so compressed instructions
+/// are only found where `disasm` decodes the C extension. Instructions of 80 bits and longer
+/// aren't decoded and are shown as `unknown`, but are still skipped over as a whole. If `disasm`
+/// has symbols, each symbol starts a new block with a `<name>:` header, showing its address with
+/// `address_width` digits.
+pub fn write_listing<W: Write>(
+    ostream: &mut W,
+    address: u64,
+    data: &[u8],
+    disasm: &Disassembler,
+    address_width: usize,
+) -> io::Result<()> {
+    let mut offset = 0;
+    while offset < data.len() {
+        let address = address + offset as u64;
+        if let Some(symbol) = disasm.lookup_symbol(address).filter(|s| s.offset == 0) {
+            writeln!(
+                ostream,
+                "\n{:0width$x} {}:",
+                address,
+                symbol,
+                width = address_width
+            )?;
+        }
+
+        let remaining = &data[offset..];
+        let parcel = match remaining {
+            [lo, hi, ..] => u16::from_le_bytes([*lo, *hi]),
+            _ => u16::from(remaining[0]),
+        };
+
+        let len = match instruction::encoded_len(parcel) {
+            Some(len) if len <= remaining.len() => len,
+            Some(_) => {
+                // The data ends partway through an instruction.
+                write_raw(ostream, address, remaining)?;
+                writeln!(ostream, "(truncated instruction)")?;
+                offset += remaining.len();
+                continue;
+            }
+            None => remaining.len().min(2),
+        };
+
+        let raw = &remaining[..len];
+        let mut bytes = [0; 8];
+        let available = len.min(8);
+        bytes[..available].copy_from_slice(&raw[..available]);
+        write_raw(ostream, address, raw)?;
+        match InstructionBits::new(u64::from_le_bytes(bytes)) {
+            Ok(inst_bits) => {
+                if let Err(e) = disasm.write_inst_at(ostream, inst_bits, Some(address))? {
+                    write_decode_error_reason(ostream, e)?;
+                }
+            }
+            // Instructions longer than 64 bits, and the reserved lengths beyond those, of which
+            // `bytes` only holds the start.
+            Err(_) => ostream.write_all(b"unknown")?,
+        }
+        writeln!(ostream)?;
+        offset += len;
+    }
+
+    Ok(())
+}
@@ -1,19 +1,279 @@
-use std::io::{self, BufWriter};
+use std::error::Error;
+use std::fmt::Display;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::process;
 
+use clap::Parser;
+
+use spike_dasm_rs::decode::TargetStyle;
 use spike_dasm_rs::disasm::Disassembler;
-use spike_dasm_rs::instruction;
-use spike_dasm_rs::{Extensions, Xlen};
+use spike_dasm_rs::elf;
+use spike_dasm_rs::image::MemoryImage;
+use spike_dasm_rs::io::{RegisterFields, TraceOptions};
+use spike_dasm_rs::profile::Profile;
+use spike_dasm_rs::registers::RegisterNames;
+use spike_dasm_rs::stats::InstructionStats;
+use spike_dasm_rs::symbols::SymbolTable;
+use spike_dasm_rs::trace::{self, RegexFormat, TraceFormat};
+use spike_dasm_rs::Isa;
+
+/// Disassembles the RISC-V instructions in `DASM(...)` markers of an instruction trace or in a
+/// spike commit log, or the executable sections of an ELF file.
+#[derive(Parser)]
+#[clap(version)]
+struct Args {
+    /// RISC-V ISA string, e.g. rv64gc or rv32imac_zicsr_zba_zbb [default: rv64imafdc, or the ISA
+    /// recorded in the ELF file with --disassemble]
+    #[clap(long)]
+    isa: Option<Isa>,
+
+    /// Print the base instructions instead of pseudo-instructions (e.g. `addi a0, zero, 1`
+    /// instead of `li a0, 1`)
+    #[clap(long)]
+    no_aliases: bool,
+
+    /// Register naming style: `abi` (e.g. `a0`, `fa0`) or `numeric` (e.g. `x10`, `f10`)
+    #[clap(long, default_value = "abi", possible_values = &["abi", "numeric"])]
+    registers: RegisterNames,
+
+    /// How to show branch and jump targets: `relative` to the PC, `absolute` addresses computed
+    /// from each trace line's `pc=[...]`, or `both` [default: relative, or absolute with
+    /// --disassemble]
+    #[clap(long, possible_values = &["relative", "absolute", "both"])]
+    targets: Option<TargetStyle>,
+
+    /// Annotate PCs and branch/jump targets with the nearest symbol from this ELF file's
+    /// .symtab and .dynsym sections, e.g. `<main+0x24>`
+    #[clap(long)]
+    elf: Option<PathBuf>,
+
+    /// Format of the input trace: Rocket `DASM(...)` markers, spike or BOOM commit logs, CVA6
+    /// traces, or `auto` to detect it from the first lines
+    #[clap(
+        long,
+        default_value = "rocket",
+        possible_values = &["auto", "rocket", "spike", "boom", "cva6"]
+    )]
+    trace_format: String,
+
+    /// Read a trace whose instructions are matched by this regex instead, with the instruction in
+    /// hex in an `inst` group, and optionally the PC in a `pc` group. The disassembly replaces the
+    /// text of a `dasm` group, or otherwise follows the match
+    #[clap(long, conflicts_with = "trace-format")]
+    trace_regex: Option<RegexFormat>,
+
+    /// Show the `W[...]` and `R[...]` register fields of Rocket traces with register names and
+    /// hex values, either in place of the register numbers (`rewrite`) or after each field
+    /// (`annotate`). Writes to floating-point registers are told apart by the instruction
+    #[clap(long, possible_values = &["rewrite", "annotate"])]
+    register_fields: Option<RegisterFields>,
+
+    /// Follow each load, store and AMO in a Rocket trace with the address it accesses, computed
+    /// from the base register value in its `R[...]` fields
+    #[clap(long)]
+    effective_addresses: bool,
+
+    /// Instead of disassembling the trace, report how many instructions of each mnemonic,
+    /// extension, class (branch, load, store, ALU, CSR, ...) and size it executes, as a `text`
+    /// table or as `csv`
+    #[clap(long, arg_enum, value_name = "FORMAT", conflicts_with = "disassemble")]
+    stats: Option<StatsFormat>,
+
+    /// Instead of disassembling the trace, profile it by the PC of each instruction: report the
+    /// hottest addresses with their disassembly, and with --elf, how many instructions were
+    /// executed in each function
+    #[clap(long, conflicts_with_all = &["disassemble", "stats"])]
+    profile: bool,
+
+    /// Number of hottest addresses reported by --profile
+    #[clap(long, value_name = "N", default_value = "20", requires = "profile")]
+    top: usize,
+
+    /// Also write the call stacks seen by --profile to this file, in the folded format read by
+    /// flamegraph tools. Stacks follow calls (`jal ra`, `jalr ra`) and returns (`ret`)
+    #[clap(long, value_name = "FILE", requires = "profile")]
+    folded_stacks: Option<PathBuf>,
+
+    /// Print an objdump-style listing of the executable sections of the input ELF file (or of a
+    /// raw memory image, with --format), instead of processing a trace
+    #[clap(short, long, requires = "input", conflicts_with = "elf")]
+    disassemble: bool,
+
+    /// Format of the input file to disassemble: an ELF file, a raw binary, or a Verilog
+    /// `$readmemh` hex image
+    #[clap(long, arg_enum, default_value = "elf", requires = "disassemble")]
+    format: ImageFormat,
+
+    /// Address at which a raw binary or hex image is loaded
+    #[clap(long, default_value = "0", parse(try_from_str = parse_address))]
+    base_address: u64,
+
+    /// Width in bytes of each word of a hex image [default: the width of its first word]
+    #[clap(long, possible_values = &["1", "2", "4", "8"])]
+    word_bytes: Option<usize>,
+
+    /// Write the output to this file instead of stdout
+    #[clap(short, long)]
+    output: Option<PathBuf>,
+
+    /// Trace file to read, or stdin if omitted (or the file to disassemble, with --disassemble)
+    input: Option<PathBuf>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, clap::ArgEnum)]
+enum ImageFormat {
+    Elf,
+    Bin,
+    Hex,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, clap::ArgEnum)]
+enum StatsFormat {
+    Text,
+    Csv,
+}
+
+/// Parses an address in hex with a `0x` prefix, or in decimal.
+fn parse_address(s: &str) -> Result<u64, String> {
+    let result = match s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+        Some(hex) => u64::from_str_radix(hex, 16),
+        None => s.parse(),
+    };
+    result.map_err(|e| format!("invalid address '{}': {}", s, e))
+}
+
+fn open_or_exit<T>(path: &Path, result: io::Result<T>) -> T {
+    result.unwrap_or_else(|e| {
+        eprintln!("error: couldn't open '{}': {}", path.display(), e);
+        process::exit(1);
+    })
+}
+
+fn read_elf_or_exit<T, E: Display>(path: &Path, result: Result<T, E>) -> T {
+    result.unwrap_or_else(|e| {
+        eprintln!("error: couldn't read ELF file '{}': {}", path.display(), e);
+        process::exit(1);
+    })
+}
+
+fn build_disassembler(args: &Args, isa: Isa, default_targets: TargetStyle) -> Disassembler {
+    Disassembler::for_isa(isa, !args.no_aliases)
+        .with_register_names(args.registers)
+        .with_target_style(args.targets.unwrap_or(default_targets))
+}
 
 fn main() {
-    let instructions = instruction::gen_instructions(Xlen::Rv64, Extensions::IMAFDC, true);
-    let disasm = Disassembler::new(instructions);
+    let args = Args::parse();
 
-    // Lock stdin and stdout to improve repeated read/write performance.
-    let stdin = io::stdin();
-    let locked_stdin = stdin.lock();
+    // Lock stdout to improve repeated write performance.
     let stdout = io::stdout();
-    let locked_stdout = stdout.lock();
-    let mut buffered_stdout = BufWriter::new(locked_stdout);
+    let ostream: Box<dyn Write> = match &args.output {
+        Some(path) => Box::new(open_or_exit(path, File::create(path))),
+        None => Box::new(stdout.lock()),
+    };
+    let mut buffered_ostream = BufWriter::new(ostream);
+
+    let result = if args.disassemble {
+        match args.format {
+            ImageFormat::Elf => disassemble_elf(&args, &mut buffered_ostream),
+            ImageFormat::Bin | ImageFormat::Hex => disassemble_image(&args, &mut buffered_ostream),
+        }
+    } else {
+        process_trace(&args, &mut buffered_ostream)
+    };
+    if let Err(e) = result {
+        eprintln!("error: {}", e);
+        process::exit(1);
+    }
+}
+
+fn disassemble_elf<W: Write>(args: &Args, ostream: &mut W) -> Result<(), Box<dyn Error>> {
+    // `--disassemble` requires an input file.
+    let path = args.input.as_deref().unwrap();
+    let data = open_or_exit(path, fs::read(path));
+    let file = read_elf_or_exit(path, elf::parse(&data));
+
+    let isa = args.isa.unwrap_or_else(|| elf::isa(&file));
+    let disasm = build_disassembler(args, isa, TargetStyle::Absolute)
+        .with_symbols(SymbolTable::from_file(&file));
+
+    elf::write_disassembly(ostream, &file, &disasm)?;
+    ostream.flush()?;
+    Ok(())
+}
+
+fn disassemble_image<W: Write>(args: &Args, ostream: &mut W) -> Result<(), Box<dyn Error>> {
+    // `--disassemble` requires an input file.
+    let path = args.input.as_deref().unwrap();
+    let image = match args.format {
+        ImageFormat::Bin => {
+            MemoryImage::from_bin(open_or_exit(path, fs::read(path)), args.base_address)
+        }
+        _ => {
+            let input = open_or_exit(path, fs::read_to_string(path));
+            MemoryImage::from_readmemh(&input, args.base_address, args.word_bytes).unwrap_or_else(
+                |e| {
+                    eprintln!("error: couldn't read hex file '{}': {}", path.display(), e);
+                    process::exit(1);
+                },
+            )
+        }
+    };
+
+    let isa = args.isa.unwrap_or_else(|| "rv64imafdc".parse().unwrap());
+    let disasm = build_disassembler(args, isa, TargetStyle::Absolute);
+
+    image.write_disassembly(ostream, &disasm)?;
+    ostream.flush()?;
+    Ok(())
+}
+
+fn process_trace<W: Write>(args: &Args, ostream: &mut W) -> Result<(), Box<dyn Error>> {
+    let isa = args.isa.unwrap_or_else(|| "rv64imafdc".parse().unwrap());
+    let mut disasm = build_disassembler(args, isa, TargetStyle::Relative);
+    if let Some(path) = &args.elf {
+        let data = open_or_exit(path, fs::read(path));
+        disasm = disasm.with_symbols(read_elf_or_exit(path, SymbolTable::from_elf(&data)));
+    }
+
+    // Lock stdin to improve repeated read performance.
+    let stdin = io::stdin();
+    let istream: Box<dyn BufRead> = match &args.input {
+        Some(path) => Box::new(BufReader::new(open_or_exit(path, File::open(path)))),
+        None => Box::new(stdin.lock()),
+    };
+
+    let format: Option<&dyn TraceFormat> = match &args.trace_regex {
+        Some(format) => Some(format),
+        None => trace::builtin(&args.trace_format),
+    };
+    if let Some(stats_format) = args.stats {
+        let stats = InstructionStats::from_trace(istream, &disasm, format)?;
+        match stats_format {
+            StatsFormat::Text => stats.write_text(ostream)?,
+            StatsFormat::Csv => stats.write_csv(ostream)?,
+        }
+        ostream.flush()?;
+        return Ok(());
+    }
+    if args.profile {
+        let profile = Profile::from_trace(istream, &disasm, format)?;
+        profile.write_report(ostream, &disasm, args.top)?;
+        ostream.flush()?;
+        if let Some(path) = &args.folded_stacks {
+            let mut folded = BufWriter::new(open_or_exit(path, File::create(path)));
+            profile.write_folded(&mut folded, &disasm)?;
+            folded.flush()?;
+        }
+        return Ok(());
+    }
 
-    spike_dasm_rs::io::process_streaming_input(locked_stdin, &mut buffered_stdout, disasm).unwrap();
+    let options = TraceOptions {
+        register_fields: args.register_fields,
+        effective_addresses: args.effective_addresses,
+    };
+    spike_dasm_rs::io::process_trace(istream, ostream, disasm, format, options)?;
+    Ok(())
 }
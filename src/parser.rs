@@ -1,35 +1,59 @@
 use nom::{
-    bytes::complete::{tag, take_until, take_while_m_n},
-    character::complete::char,
-    combinator::map_res,
-    sequence::{delimited, pair},
+    branch::alt,
+    bytes::complete::{tag, take_until, take_while1, take_while_m_n},
+    character::complete::{char, digit1, one_of, space0, space1},
+    combinator::{consumed, map, map_res, opt, peek, verify},
+    sequence::{delimited, pair, terminated, tuple},
     IResult,
 };
 
 const SIGNPOST: &str = "DASM(";
+const PC_SIGNPOST: &str = "pc=[";
+const COMMIT_SIGNPOST: &str = "core";
 
 fn is_hex_digit(c: char) -> bool {
-    c.is_digit(16)
+    c.is_ascii_hexdigit()
 }
 
-/// Parses an 8-character string as a hexadecimal number into a `u32`.
+/// Parses a hexadecimal number of any number of digits into a `u64`, like `strtoull`: inputs of
+/// more than 64 bits saturate to `u64::MAX`. Like spike-dasm, short payloads are sign-extended
+/// from their top digit: those of fewer than 8 digits fill a 32-bit word, so `DASM(8082)` gives
+/// the same bits as `DASM(ffff8082)`.
 ///
 /// The input can't contain underscores or a leading '0x'/'0X', but mixed-case characters are fine.
 ///
 /// # Examples
 ///
 /// ```ignore
-/// assert_eq!(hex_u32("0123abCD").unwrap(), ("", 0x123abcd));
+/// assert_eq!(hex_u64("0123abCD").unwrap(), ("", 0x123abcd));
 /// ```
-fn hex_u32(input: &str) -> IResult<&str, u32> {
-    map_res(take_while_m_n(8, 8, is_hex_digit), |out: &str| {
-        u32::from_str_radix(&str::replace(&out, "_", ""), 16)
+fn hex_u64(input: &str) -> IResult<&str, u64> {
+    map(take_while1(is_hex_digit), |out: &str| {
+        let value = out.chars().try_fold(0u64, |value, c| {
+            value
+                .checked_mul(16)
+                .map(|value| value | u64::from(c.to_digit(16).unwrap()))
+        });
+        match value {
+            Some(value) if out.len() < 8 => {
+                let shift = 32 - 4 * out.len() as u32;
+                (((value as u32) << shift) as i32 >> shift) as u32 as u64
+            }
+            Some(value) => value,
+            None => u64::MAX,
+        }
     })(input)
 }
 
-/// Parses a single hex-formatted u32 from within the "DASM(...)" signpost.
+/// Parses the first well-formed instruction marker, such as "DASM(fc050513)", in exactly the way
+/// spike-dasm does:
 ///
-/// Leading and trailing characters are preserved, but the signpost itself is discarded.
+/// - The hex digits may follow a '0x' or '0X' prefix, and there can be any number of them (see
+///   `hex_u64`).
+/// - Markers without any hex digits are skipped, and left as they are.
+/// - The character after the digits is taken as the closing parenthesis, whatever it is.
+///
+/// Leading and trailing characters are preserved, but the marker itself is discarded.
 ///
 /// # Examples
 ///
@@ -39,15 +63,302 @@ fn hex_u32(input: &str) -> IResult<&str, u32> {
 ///     parser::parse_value("leading[DASM(0123def0)]trailing"),
 ///     Ok(("]trailing", ("leading[", 0x123def0)))
 /// );
+/// assert_eq!(
+///     parser::parse_value("DASM() DASM(0x4505) DASM(00000000001f)"),
+///     Ok((" DASM(00000000001f)", ("DASM() ", 0x4505)))
+/// );
 /// assert!(parser::parse_value("nothing to see here").is_err());
 /// ```
-pub fn parse_value(input: &str) -> IResult<&str, (&str, u32)> {
-    pair(
-        take_until(SIGNPOST),
-        delimited(tag(SIGNPOST), hex_u32, char(')')),
+pub fn parse_value(input: &str) -> IResult<&str, (&str, u64)> {
+    let mut search = input;
+    loop {
+        let (after_begin, _) = take_until(SIGNPOST)(search)?;
+        let (after_signpost, _) = tag(SIGNPOST)(after_begin)?;
+        let (payload, _) = opt(alt((tag("0x"), tag("0X"))))(after_signpost)?;
+        match hex_u64(payload) {
+            Ok((end, x)) => {
+                let begin = &input[..input.len() - after_begin.len()];
+                let mut end_chars = end.chars();
+                end_chars.next();
+                return Ok((end_chars.as_str(), (begin, x)));
+            }
+            Err(_) => search = payload,
+        }
+    }
+}
+
+/// Parses the hex-formatted PC from within the first "pc=[...]" field of a trace line.
+///
+/// # Examples
+///
+/// ```
+/// use spike_dasm_rs::parser;
+/// assert_eq!(
+///     parser::parse_pc("C0: 19 [1] pc=[0000000000010040] W[r10=0000000000010040][1]"),
+///     Ok(("] W[r10=0000000000010040][1]", 0x10040))
+/// );
+/// assert!(parser::parse_pc("nothing to see here").is_err());
+/// ```
+pub fn parse_pc(input: &str) -> IResult<&str, u64> {
+    let (input, _) = take_until(PC_SIGNPOST)(input)?;
+    let (input, _) = tag(PC_SIGNPOST)(input)?;
+    map_res(take_while_m_n(1, 16, is_hex_digit), |out: &str| {
+        u64::from_str_radix(out, 16)
+    })(input)
+}
+
+/// Whether a register field of a Rocket trace shows a register write or a register read.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RegAccess {
+    /// `W[r10=0000000000010040][1]`, where the last field says whether the write happened.
+    Write { enabled: bool },
+    /// `R[r 0=0000000000000000]`.
+    Read,
+}
+
+/// A register field of a Rocket trace, such as `W[r10=0000000000010040][1]`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RegField {
+    pub access: RegAccess,
+    /// Whether the register is marked as floating-point (`f10`) rather than integer (`r10`).
+    pub fp: bool,
+    pub idx: u8,
+    pub value: u64,
+}
+
+/// Parses a register field at the start of `input`.
+fn reg_field(input: &str) -> IResult<&str, RegField> {
+    let idx = map_res(digit1, |out: &str| out.parse::<u8>());
+    let value = map_res(take_while_m_n(1, 16, is_hex_digit), |out: &str| {
+        u64::from_str_radix(out, 16)
+    });
+    let (rest, (access, _, kind, _, idx, _, value, _)) = tuple((
+        one_of("WR"),
+        char('['),
+        one_of("rf"),
+        space0,
+        verify(idx, |&idx| idx < 32),
+        char('='),
+        value,
+        char(']'),
+    ))(input)?;
+    let (rest, access) = match access {
+        'W' => map(
+            opt(delimited(char('['), one_of("01"), char(']'))),
+            |enabled| RegAccess::Write {
+                enabled: enabled != Some('0'),
+            },
+        )(rest)?,
+        _ => (rest, RegAccess::Read),
+    };
+
+    Ok((
+        rest,
+        RegField {
+            access,
+            fp: kind == 'f',
+            idx,
+            value,
+        },
+    ))
+}
+
+/// Parses the first register field, such as `W[r10=0000000000010040][1]` or
+/// `R[r 0=0000000000000000]`, from a line of a Rocket trace.
+///
+/// Leading and trailing characters are preserved, but the field itself is discarded.
+///
+/// # Examples
+///
+/// ```
+/// use spike_dasm_rs::parser::{self, RegAccess, RegField};
+/// assert_eq!(
+///     parser::parse_reg_field("pc=[00010040] W[r10=0000000000010040][1] R[r 0=0]"),
+///     Ok((
+///         " R[r 0=0]",
+///         (
+///             "pc=[00010040] ",
+///             RegField {
+///                 access: RegAccess::Write { enabled: true },
+///                 fp: false,
+///                 idx: 10,
+///                 value: 0x10040
+///             }
+///         )
+///     ))
+/// );
+/// assert!(parser::parse_reg_field("nothing to see here").is_err());
+/// ```
+pub fn parse_reg_field(input: &str) -> IResult<&str, (&str, RegField)> {
+    for (idx, _) in input.match_indices(['W', 'R']) {
+        if let Ok((rest, field)) = reg_field(&input[idx..]) {
+            return Ok((rest, (&input[..idx], field)));
+        }
+    }
+    Err(nom::Err::Error(nom::error::Error::new(
+        input,
+        nom::error::ErrorKind::TakeUntil,
+    )))
+}
+
+/// The fields at the start of a line of a commit log or similar trace, up to the raw instruction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CommitLine<'a> {
+    /// Everything up to and including the PC.
+    pub pc_field: &'a str,
+    pub pc: u64,
+    /// The raw instruction after the PC, with the whitespace before it, e.g. ` (0x00000297)`.
+    pub inst_field: &'a str,
+    pub bits: u64,
+}
+
+/// Parses the instruction from within the parentheses of a spike commit log, which has as many
+/// digits as the instruction has nibbles: 4 for compressed instructions, otherwise 8, 12 or 16.
+fn commit_inst(input: &str) -> IResult<&str, u64> {
+    let digits = verify(take_while_m_n(4, 16, is_hex_digit), |out: &str| {
+        matches!(out.len(), 4 | 8 | 12 | 16)
+    });
+    delimited(
+        tag("(0x"),
+        map_res(digits, |out: &str| u64::from_str_radix(out, 16)),
+        char(')'),
     )(input)
 }
 
+/// Parses the core, privilege level, PC and instruction at the start of a line logged by spike's
+/// `--log-commits`, leaving the register and memory writes that follow them.
+///
+/// The privilege level is optional, as in the `-l` instruction log.
+///
+/// # Examples
+///
+/// ```
+/// use spike_dasm_rs::parser::{self, CommitLine};
+/// assert_eq!(
+///     parser::parse_commit("core   0: 3 0x0000000080000000 (0x00000297) x5  0x0000000080000000"),
+///     Ok((
+///         " x5  0x0000000080000000",
+///         CommitLine {
+///             pc_field: "core   0: 3 0x0000000080000000",
+///             pc: 0x80000000,
+///             inst_field: " (0x00000297)",
+///             bits: 0x297,
+///         }
+///     ))
+/// );
+/// assert!(parser::parse_commit("core   0: exception trap_illegal_instruction").is_err());
+/// ```
+pub fn parse_commit(input: &str) -> IResult<&str, CommitLine<'_>> {
+    commit_line(tuple((
+        space0,
+        tag(COMMIT_SIGNPOST),
+        space0,
+        digit1,
+        char(':'),
+        space1,
+    )))(input)
+}
+
+/// Parses the privilege level, PC and instruction at the start of a line of a BOOM commit log,
+/// which is a spike commit log without the core number.
+///
+/// # Examples
+///
+/// ```
+/// use spike_dasm_rs::parser;
+/// let (rest, commit) = parser::parse_boom_commit("3 0x0000000080000000 (0x00000297) x 5 0x0000000080000000").unwrap();
+/// assert_eq!(rest, " x 5 0x0000000080000000");
+/// assert_eq!((commit.pc, commit.bits), (0x80000000, 0x297));
+/// ```
+pub fn parse_boom_commit(input: &str) -> IResult<&str, CommitLine<'_>> {
+    commit_line(pair(space0, peek(terminated(digit1, space1))))(input)
+}
+
+/// Parses a spike-style commit log line: `start`, then an optional privilege level, the PC and
+/// the instruction.
+fn commit_line<'a, O>(
+    start: impl FnMut(&'a str) -> IResult<&'a str, O>,
+) -> impl FnMut(&'a str) -> IResult<&'a str, CommitLine<'a>> {
+    let pc = map_res(take_while_m_n(1, 16, is_hex_digit), |out: &str| {
+        u64::from_str_radix(out, 16)
+    });
+    let mut pc_field = consumed(map(
+        tuple((start, opt(terminated(digit1, space1)), tag("0x"), pc)),
+        |fields| fields.3,
+    ));
+    move |input: &'a str| {
+        let (rest, (pc_field, pc)) = pc_field(input)?;
+        let (rest, (inst_field, (_, bits))) = consumed(pair(space1, commit_inst))(rest)?;
+        Ok((
+            rest,
+            CommitLine {
+                pc_field,
+                pc,
+                inst_field,
+                bits,
+            },
+        ))
+    }
+}
+
+/// Parses the time, cycle, privilege level, PC and instruction at the start of a line of a CVA6
+/// instruction trace, such as `  3000ns  3 M 0000000080000000 0 00000297 auipc t0, 0x0`, leaving
+/// whatever follows them.
+///
+/// The instruction has 8 digits, or 4 for a compressed instruction.
+///
+/// # Examples
+///
+/// ```
+/// use spike_dasm_rs::parser;
+/// let (rest, line) = parser::parse_cva6("  3000ns  3 M 0000000080000000 0 00000297 auipc").unwrap();
+/// assert_eq!(rest, " auipc");
+/// assert_eq!(line.pc_field, "  3000ns  3 M 0000000080000000");
+/// assert_eq!(line.inst_field, " 0 00000297");
+/// assert_eq!((line.pc, line.bits), (0x80000000, 0x297));
+/// ```
+pub fn parse_cva6(input: &str) -> IResult<&str, CommitLine<'_>> {
+    let pc = map_res(take_while_m_n(1, 16, is_hex_digit), |out: &str| {
+        u64::from_str_radix(out, 16)
+    });
+    let (rest, (pc_field, pc)) = consumed(map(
+        tuple((
+            space0,
+            digit1,
+            tag("ns"),
+            space1,
+            digit1,
+            space1,
+            one_of("MSUH"),
+            space1,
+            pc,
+        )),
+        |fields| fields.8,
+    ))(input)?;
+    let inst = verify(take_while_m_n(4, 8, is_hex_digit), |out: &str| {
+        matches!(out.len(), 4 | 8)
+    });
+    let (rest, (inst_field, bits)) = consumed(map(
+        tuple((
+            space1,
+            one_of("01"),
+            space1,
+            map_res(inst, |out: &str| u64::from_str_radix(out, 16)),
+        )),
+        |fields| fields.3,
+    ))(rest)?;
+
+    Ok((
+        rest,
+        CommitLine {
+            pc_field,
+            pc,
+            inst_field,
+            bits,
+        },
+    ))
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -57,28 +368,42 @@ mod test {
         use nom::combinator::all_consuming;
 
         // Not only is this input valid...
-        assert!(hex_u32("12345678").is_ok());
+        assert!(hex_u64("12345678").is_ok());
         // ...but there's nothing left to parse after we're done.
-        assert!(all_consuming(hex_u32)("12345678").is_ok());
+        assert!(all_consuming(hex_u64)("12345678").is_ok());
 
         // On the other hand, this input is invalid...
-        assert!(hex_u32("xyz").is_err());
+        assert!(hex_u64("xyz").is_err());
 
         // ...and this input has some trailing characters that don't match.
-        assert_eq!(hex_u32("1234abcdxyz456").unwrap(), ("xyz456", 0x1234abcd));
+        assert_eq!(hex_u64("1234abcdxyz456").unwrap(), ("xyz456", 0x1234abcd));
 
         // We support mixed case.
-        assert!(all_consuming(hex_u32)("12aBcDeF").is_ok());
+        assert!(all_consuming(hex_u64)("12aBcDeF").is_ok());
 
         // We don't support underscores...
-        assert!(all_consuming(hex_u32)("12ab_cdef").is_err());
+        assert!(all_consuming(hex_u64)("12ab_cdef").is_err());
 
         // ...or leading '0x'/'0X'.
-        assert!(all_consuming(hex_u32)("0x12ABCDEF").is_err());
-        assert!(all_consuming(hex_u32)("0X12abcdef").is_err());
+        assert!(all_consuming(hex_u64)("0x12ABCDEF").is_err());
+        assert!(all_consuming(hex_u64)("0X12abcdef").is_err());
+
+        // Any number of digits works, sign-extended from the top one to 32 bits if there are
+        // fewer than 8...
+        assert_eq!(hex_u64("4505").unwrap(), ("", 0x4505));
+        assert_eq!(hex_u64("8082").unwrap(), ("", 0xffff_8082));
+        assert_eq!(hex_u64("fc050513").unwrap(), ("", 0xfc05_0513));
+        assert_eq!(hex_u64("123456789abc").unwrap(), ("", 0x1234_5678_9abc));
+        assert_eq!(hex_u64("f23456789").unwrap(), ("", 0xf_2345_6789));
+        assert_eq!(
+            hex_u64("f23456789abcdef0").unwrap(),
+            ("", 0xf234_5678_9abc_def0)
+        );
+        // ...and more than 64 bits saturate.
+        assert_eq!(hex_u64("123456789abcdef01").unwrap(), ("", u64::MAX));
 
-        // Example from `hex_u32` docstring.
-        assert_eq!(hex_u32("0123abCD").unwrap(), ("", 0x123abcd));
+        // Example from `hex_u64` docstring.
+        assert_eq!(hex_u64("0123abCD").unwrap(), ("", 0x123abcd));
     }
 
     #[test]
@@ -87,5 +412,132 @@ mod test {
         let end = "bar";
         let test_str = format!("{}{}fedcab10){}", begin, SIGNPOST, end);
         assert_eq!(parse_value(&test_str), Ok((end, (begin, 0xfedcab10))));
+
+        let test_str = format!("{}{}0000fedcab10){}", begin, SIGNPOST, end);
+        assert_eq!(parse_value(&test_str), Ok((end, (begin, 0xfedcab10))));
+
+        // Short payloads are sign-extended, as spike-dasm does.
+        let test_str = format!("{}{}8082){}", begin, SIGNPOST, end);
+        assert_eq!(parse_value(&test_str), Ok((end, (begin, 0xffff8082))));
+    }
+
+    #[test]
+    fn parse_reg_fields() {
+        let field = |input| parse_reg_field(input).map(|(rest, (_, field))| (rest, field));
+
+        assert_eq!(
+            field("R[r 0=0000000000000000] x"),
+            Ok((
+                " x",
+                RegField {
+                    access: RegAccess::Read,
+                    fp: false,
+                    idx: 0,
+                    value: 0
+                }
+            ))
+        );
+        assert_eq!(
+            field("W[f31=3ff0000000000000][0]"),
+            Ok((
+                "",
+                RegField {
+                    access: RegAccess::Write { enabled: false },
+                    fp: true,
+                    idx: 31,
+                    value: 0x3ff0000000000000
+                }
+            ))
+        );
+        // Fields with an invalid register are skipped.
+        assert_eq!(
+            parse_reg_field("W[r32=0] Wx R[r1=ff]").map(|(_, (begin, field))| (begin, field.idx)),
+            Ok(("W[r32=0] Wx ", 1))
+        );
+        assert!(parse_reg_field("W[x1=0] R[r1=]").is_err());
+    }
+
+    #[test]
+    fn parse_malformed_markers() {
+        // Markers without digits are skipped.
+        assert!(parse_value("DASM() DASM(0x) DASM(xyz)").is_err());
+        assert_eq!(
+            parse_value("DASM(zz) DASM(4505)"),
+            Ok(("", ("DASM(zz) ", 0x4505)))
+        );
+        // Any character closes the marker, and the end of the line does too.
+        assert_eq!(parse_value("DASM(4505] x"), Ok((" x", ("", 0x4505))));
+        assert_eq!(parse_value("DASM(4505"), Ok(("", ("", 0x4505))));
+        assert_eq!(parse_value("DASM(4505\u{e9}x"), Ok(("x", ("", 0x4505))));
+        // Payloads of any width, with an optional prefix.
+        assert_eq!(parse_value("DASM(0X517)"), Ok(("", ("", 0x517))));
+        assert_eq!(parse_value("DASM(0)"), Ok(("", ("", 0))));
+    }
+
+    #[test]
+    fn parse_pcs() {
+        assert_eq!(parse_pc("pc=[80000000]"), Ok(("]", 0x80000000)));
+        assert_eq!(
+            parse_pc("x pc=[ffffffffffffff00] y"),
+            Ok(("] y", 0xffffffffffffff00))
+        );
+        assert!(parse_pc("pc=[]").is_err());
+        assert!(parse_pc("pc=[xyz]").is_err());
+        // Only the first 16 digits are taken, so this doesn't overflow.
+        assert_eq!(parse_pc("pc=[00000000000000001]"), Ok(("1]", 0)));
+    }
+
+    #[test]
+    fn parse_commits() {
+        let (rest, commit) =
+            parse_commit("core 0: 3 0x80000000 (0x00000297) x5 0x80000000").unwrap();
+        assert_eq!(rest, " x5 0x80000000");
+        assert_eq!(commit.pc_field, "core 0: 3 0x80000000");
+        assert_eq!(commit.pc, 0x80000000);
+        assert_eq!(commit.inst_field, " (0x00000297)");
+        assert_eq!(commit.bits, 0x297);
+
+        // Compressed instructions, with no commits and no privilege level.
+        let (rest, commit) = parse_commit("core   1: 0x0000000080000004 (0x4505)").unwrap();
+        assert_eq!(rest, "");
+        assert_eq!(commit.pc, 0x80000004);
+        assert_eq!(commit.bits, 0x4505);
+
+        // Memory writes are left as they are.
+        let (rest, commit) = parse_commit(
+            "core   0: 0 0x0000000080000010 (0x00a13423) mem 0x0000000080001008 0x0000000000000005",
+        )
+        .unwrap();
+        assert_eq!(rest, " mem 0x0000000080001008 0x0000000000000005");
+        assert_eq!(commit.bits, 0x00a13423);
+
+        assert!(parse_commit("core 0: 3 0x80000000 (0x0000297)").is_err());
+        assert!(parse_commit("core 0: 3 (0x00000297)").is_err());
+        assert!(parse_commit("C0: 19 [1] pc=[0000000000010040] DASM(00000517)").is_err());
+    }
+
+    #[test]
+    fn parse_boom_commits() {
+        let (rest, commit) = parse_boom_commit("1 0x0000000080000004 (0x4505) x10 0x1").unwrap();
+        assert_eq!(rest, " x10 0x1");
+        assert_eq!(commit.pc_field, "1 0x0000000080000004");
+        assert_eq!(commit.bits, 0x4505);
+
+        // The privilege level is required, to tell these lines apart from others.
+        assert!(parse_boom_commit("0x0000000080000004 (0x4505)").is_err());
+        assert!(parse_boom_commit("core   0: 3 0x0000000080000000 (0x00000297)").is_err());
+    }
+
+    #[test]
+    fn parse_cva6_lines() {
+        let (rest, line) =
+            parse_cva6("        3000000ns               3 U 0000000000010040 1 00004505").unwrap();
+        assert_eq!(rest, "");
+        assert_eq!(line.pc, 0x10040);
+        assert_eq!(line.inst_field, " 1 00004505");
+        assert_eq!(line.bits, 0x4505);
+
+        assert!(parse_cva6("3000ns 3 X 0000000000010040 1 00004505").is_err());
+        assert!(parse_cva6("3000ns 3 M 0000000000010040 00004505").is_err());
     }
 }
@@ -0,0 +1,284 @@
+use std::collections::{BTreeMap, HashMap};
+use std::io::{self, BufRead, Write};
+
+use super::disasm::Disassembler;
+use super::instruction::InstructionBits;
+use super::io::{for_each_trace_inst, write_decode_error_reason};
+use super::trace::TraceFormat;
+
+/// The frame name used for code outside any symbol, as in flamegraph tools.
+const UNKNOWN_FUNCTION: &str = "[unknown]";
+
+/// How many times an address was executed, and the instruction found there.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct PcCount {
+    count: u64,
+    /// The encoding last executed at the address.
+    bits: u64,
+}
+
+/// An execution profile of a trace: how many times each PC was executed, and in which call
+/// stacks.
+///
+/// Call stacks are followed through calls and returns, as told by `DecodedInstruction::is_call`
+/// and `is_return`: the first PC after a call enters a new frame, and a return leaves it. Traps
+/// and trap returns aren't followed, so trap handlers are counted in the frame they interrupt.
+#[derive(Clone, Debug, Default)]
+pub struct Profile {
+    total: u64,
+    pcs: HashMap<u64, PcCount>,
+    /// The number of instructions executed in each call stack, given by the entry address of each
+    /// frame from the outermost one.
+    stacks: HashMap<Vec<u64>, u64>,
+    /// The current call stack. Frames for code whose caller isn't in the trace, including the
+    /// first one, start at the first PC executed in them.
+    stack: Vec<u64>,
+    /// Whether the last instruction was a call, so the next PC enters a new frame.
+    in_call: bool,
+}
+
+impl Profile {
+    /// Profiles the instructions that `format` finds in `istream` (or that the detected format
+    /// finds; see `io::process_trace`). Instructions without a PC are skipped.
+    pub fn from_trace<I: BufRead>(
+        istream: I,
+        disasm: &Disassembler,
+        format: Option<&dyn TraceFormat>,
+    ) -> io::Result<Self> {
+        let mut profile = Self::default();
+        for_each_trace_inst(istream, format, |inst| {
+            if let Some((pc, _)) = inst.pc {
+                profile.add(pc, inst.bits, disasm);
+            }
+        })?;
+        Ok(profile)
+    }
+
+    /// Counts an execution of the instruction `bits` at `pc`, decoded by `disasm` to follow calls
+    /// and returns.
+    pub fn add(&mut self, pc: u64, bits: u64, disasm: &Disassembler) {
+        self.total += 1;
+        let pc_count = self.pcs.entry(pc).or_insert(PcCount { count: 0, bits });
+        pc_count.count += 1;
+        pc_count.bits = bits;
+
+        if self.in_call || self.stack.is_empty() {
+            self.stack.push(pc);
+        }
+        match self.stacks.get_mut(&self.stack[..]) {
+            Some(count) => *count += 1,
+            None => {
+                self.stacks.insert(self.stack.clone(), 1);
+            }
+        }
+
+        let decoded = InstructionBits::new(bits).and_then(|x| disasm.decode(x));
+        self.in_call = decoded.as_ref().is_ok_and(|decoded| decoded.is_call());
+        if decoded.is_ok_and(|decoded| decoded.is_return()) {
+            self.stack.pop();
+        }
+    }
+
+    /// Returns the number of instructions profiled.
+    pub fn total(&self) -> u64 {
+        self.total
+    }
+
+    /// Returns the number of times `pc` was executed.
+    pub fn count(&self, pc: u64) -> u64 {
+        self.pcs.get(&pc).map_or(0, |pc_count| pc_count.count)
+    }
+
+    /// Returns the `n` most executed addresses with their counts, from the hottest, and in
+    /// address order among equal counts.
+    pub fn hottest(&self, n: usize) -> Vec<(u64, u64)> {
+        let mut pcs: Vec<_> = self
+            .pcs
+            .iter()
+            .map(|(&pc, pc_count)| (pc, pc_count.count))
+            .collect();
+        pcs.sort_by(|(a_pc, a_count), (b_pc, b_count)| {
+            b_count.cmp(a_count).then_with(|| a_pc.cmp(b_pc))
+        });
+        pcs.truncate(n);
+        pcs
+    }
+
+    /// Returns the number of instructions executed in each function of `disasm`'s symbols, from
+    /// the hottest, and in name order among equal counts. Addresses outside any symbol are
+    /// counted as `[unknown]`.
+    pub fn functions(&self, disasm: &Disassembler) -> Vec<(String, u64)> {
+        let mut functions: HashMap<&str, u64> = HashMap::new();
+        for (&pc, pc_count) in self.pcs.iter() {
+            *functions.entry(function_name(disasm, pc)).or_default() += pc_count.count;
+        }
+        let mut functions: Vec<_> = functions
+            .into_iter()
+            .map(|(name, count)| (name.to_string(), count))
+            .collect();
+        functions.sort_by(|(a_name, a_count), (b_name, b_count)| {
+            b_count.cmp(a_count).then_with(|| a_name.cmp(b_name))
+        });
+        functions
+    }
+
+    /// Returns `count` as a percentage of the instructions profiled.
+    fn percent(&self, count: u64) -> f64 {
+        if self.total == 0 {
+            0.0
+        } else {
+            100.0 * count as f64 / self.total as f64
+        }
+    }
+
+    /// Writes a report of the `top` hottest addresses with their disassembly, and if `disasm`
+    /// has symbols, of the instructions executed in each function.
+    pub fn write_report<W: Write>(
+        &self,
+        w: &mut W,
+        disasm: &Disassembler,
+        top: usize,
+    ) -> io::Result<()> {
+        writeln!(
+            w,
+            "{} instructions at {} addresses",
+            self.total,
+            self.pcs.len()
+        )?;
+
+        writeln!(w, "\nHottest addresses:")?;
+        for (pc, count) in self.hottest(top) {
+            write!(w, "  {:>12} {:>7.2}%  {:x}", count, self.percent(count), pc)?;
+            if let Some(symbol) = disasm.lookup_symbol(pc) {
+                write!(w, " {}", symbol)?;
+            }
+            w.write_all(b":\t")?;
+            let written = match InstructionBits::new(self.pcs[&pc].bits) {
+                Ok(x) => disasm.write_inst_at(w, x, Some(pc))?,
+                Err(e) => Err(e),
+            };
+            if let Err(e) = written {
+                write_decode_error_reason(w, e)?;
+            }
+            writeln!(w)?;
+        }
+
+        let functions = self.functions(disasm);
+        if functions.iter().any(|(name, _)| name != UNKNOWN_FUNCTION) {
+            writeln!(w, "\nBy function:")?;
+            for (name, count) in functions {
+                writeln!(w, "  {:>12} {:>7.2}%  {}", count, self.percent(count), name)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes the call stacks in the folded format read by flamegraph tools: one line per stack,
+    /// with its frames from the outermost separated by `;`, then its instruction count.
+    ///
+    /// Frames are named after the function of `disasm`'s symbols that they're in, or otherwise
+    /// their entry address.
+    pub fn write_folded<W: Write>(&self, w: &mut W, disasm: &Disassembler) -> io::Result<()> {
+        let mut folded: BTreeMap<String, u64> = BTreeMap::new();
+        for (stack, &count) in self.stacks.iter() {
+            let frames: Vec<_> = stack
+                .iter()
+                .map(|&entry| match disasm.lookup_symbol(entry) {
+                    Some(symbol) => symbol.symbol.name.clone(),
+                    None => format!("{:#x}", entry),
+                })
+                .collect();
+            *folded.entry(frames.join(";")).or_default() += count;
+        }
+        for (stack, count) in folded {
+            writeln!(w, "{} {}", stack, count)?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns the name of the function of `disasm`'s symbols that `pc` is in.
+fn function_name(disasm: &Disassembler, pc: u64) -> &str {
+    disasm
+        .lookup_symbol(pc)
+        .map_or(UNKNOWN_FUNCTION, |symbol| &symbol.symbol.name)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::symbols::SymbolTable;
+    use crate::test_util::{disassembler, rocket_trace, symbol};
+
+    // `main` calls `square` twice, which returns with `ret`, then loops back to the first call.
+    const TRACE: &[(u64, &str)] = &[
+        (0x80000000, "00300513"),
+        (0x80000004, "00c000ef"),
+        (0x80000010, "02a50533"),
+        (0x80000014, "00008067"),
+        (0x80000008, "008000ef"),
+        (0x80000010, "02a50533"),
+        (0x80000014, "00008067"),
+        (0x8000000c, "ff5ff06f"),
+        (0x80000000, "00300513"),
+    ];
+
+    fn disassembler_with_symbols() -> Disassembler {
+        let symbols = SymbolTable::new(vec![
+            symbol("main", 0x80000000, 0x10),
+            symbol("square", 0x80000010, 0x8),
+        ]);
+        disassembler("rv64gc").with_symbols(symbols)
+    }
+
+    #[test]
+    fn report() {
+        let disasm = disassembler_with_symbols();
+        let profile = Profile::from_trace(rocket_trace(TRACE).as_bytes(), &disasm, None).unwrap();
+        assert_eq!(profile.total(), 9);
+        assert_eq!(
+            (profile.count(0x80000010), profile.count(0x80000018)),
+            (2, 0)
+        );
+
+        let mut output = Vec::new();
+        profile.write_report(&mut output, &disasm, 3).unwrap();
+        let expected = "\
+9 instructions at 6 addresses
+
+Hottest addresses:
+             2   22.22%  80000000 <main>:\tli      a0, 3
+             2   22.22%  80000010 <square>:\tmul     a0, a0, a0
+             2   22.22%  80000014 <square+0x4>:\tret
+
+By function:
+             5   55.56%  main
+             4   44.44%  square
+";
+        assert_eq!(String::from_utf8(output).unwrap(), expected);
+    }
+
+    #[test]
+    fn folded_stacks() {
+        let disasm = disassembler_with_symbols();
+        let profile = Profile::from_trace(rocket_trace(TRACE).as_bytes(), &disasm, None).unwrap();
+        let mut output = Vec::new();
+        profile.write_folded(&mut output, &disasm).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "main 5\nmain;square 4\n"
+        );
+
+        // Without symbols, frames are named by their entry address, and returning from the
+        // first frame starts a new one.
+        let disasm = disassembler("rv64gc");
+        let trace = rocket_trace(&TRACE[2..]);
+        let profile = Profile::from_trace(trace.as_bytes(), &disasm, None).unwrap();
+        let mut output = Vec::new();
+        profile.write_folded(&mut output, &disasm).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "0x80000008 3\n0x80000008;0x80000010 2\n0x80000010 2\n"
+        );
+    }
+}
@@ -24,6 +24,56 @@ pub const FP_REGISTER_ABI_NAMES: [&str; 32] = [
     "fs10", "fs11", "ft8", "ft9", "ft10", "ft11",
 ];
 
+/// Lookup-table for vector register names.
+pub const VEC_REGISTER_NAMES: [&str; 32] = [
+    "v0", "v1", "v2", "v3", "v4", "v5", "v6", "v7", "v8", "v9", "v10", "v11", "v12", "v13", "v14",
+    "v15", "v16", "v17", "v18", "v19", "v20", "v21", "v22", "v23", "v24", "v25", "v26", "v27",
+    "v28", "v29", "v30", "v31",
+];
+
+/// How register operands are named in the disassembly.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum RegisterNames {
+    /// ABI names, e.g. `a0` and `fa0`, as spike prints them.
+    #[default]
+    Abi,
+    /// Architectural names, e.g. `x10` and `f10`.
+    Numeric,
+}
+
+impl RegisterNames {
+    /// Returns the name of integer register `idx`.
+    pub fn int(self, idx: u8) -> &'static str {
+        match self {
+            Self::Abi => INT_REGISTER_ABI_NAMES[idx as usize],
+            Self::Numeric => INT_REGISTER_NAMES[idx as usize],
+        }
+    }
+
+    /// Returns the name of floating-point register `idx`.
+    pub fn fp(self, idx: u8) -> &'static str {
+        match self {
+            Self::Abi => FP_REGISTER_ABI_NAMES[idx as usize],
+            Self::Numeric => FP_REGISTER_NAMES[idx as usize],
+        }
+    }
+}
+
+impl std::str::FromStr for RegisterNames {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "abi" => Ok(Self::Abi),
+            "numeric" => Ok(Self::Numeric),
+            _ => Err(format!(
+                "unknown register naming style '{}' (expected 'abi' or 'numeric')",
+                s
+            )),
+        }
+    }
+}
+
 const fn gen_mask(offset: u8, mask_width: u8) -> u32 {
     let mask = (1 << mask_width) - 1;
     mask << offset
@@ -33,8 +83,10 @@ pub(crate) const MASK_RD: u32 = gen_mask(7, 5);
 pub(crate) const MASK_RS1: u32 = gen_mask(15, 5);
 pub(crate) const MASK_RS2: u32 = gen_mask(20, 5);
 pub(crate) const MASK_I_TYPE_IMM: u32 = gen_mask(20, 12);
+pub(crate) const MASK_V_VM: u32 = gen_mask(25, 1);
 
 pub(crate) const MATCH_RD_EQUALS_RA: u32 = 1 << 7;
 pub(crate) const MATCH_RS1_EQUALS_RA: u32 = 1 << 15;
 pub(crate) const MATCH_I_TYPE_IMM_EQUALS_NEG1: u32 = gen_mask(20, 12);
 pub(crate) const MATCH_I_TYPE_IMM_EQUALS_1: u32 = 1 << 20;
+pub(crate) const MATCH_V_VM_UNMASKED: u32 = 1 << 25;
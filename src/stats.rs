@@ -0,0 +1,234 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::io::{self, BufRead, Write};
+
+use super::decode::{DecodedInstruction, InstructionClass, Mnemonic};
+use super::disasm::Disassembler;
+use super::instruction::{InstructionBits, InstructionLen};
+use super::io::for_each_trace_inst;
+use super::trace::TraceFormat;
+use super::Extensions;
+
+/// The instruction mix of a trace: how many times each mnemonic, extension and class of
+/// instruction was executed, and how many instructions were compressed.
+#[derive(Clone, Debug, Default)]
+pub struct InstructionStats {
+    /// The number of instructions that were decoded.
+    total: u64,
+    /// The number of instructions that couldn't be decoded, which aren't counted anywhere else.
+    unknown: u64,
+    mnemonics: HashMap<Mnemonic, u64>,
+    extensions: HashMap<Extensions, u64>,
+    classes: HashMap<InstructionClass, u64>,
+    compressed: u64,
+}
+
+/// One breakdown of the instructions, as `(name, count)` pairs from the most to the least common.
+type Breakdown = Vec<(String, u64)>;
+
+impl InstructionStats {
+    /// Counts the instructions that `format` finds in `istream` (or that the detected format
+    /// finds; see `io::process_trace`).
+    pub fn from_trace<I: BufRead>(
+        istream: I,
+        disasm: &Disassembler,
+        format: Option<&dyn TraceFormat>,
+    ) -> io::Result<Self> {
+        let mut stats = Self::default();
+        for_each_trace_inst(istream, format, |inst| {
+            match InstructionBits::new(inst.bits).and_then(|x| disasm.decode(x)) {
+                Ok(decoded) => stats.add(&decoded),
+                Err(_) => stats.add_unknown(),
+            }
+        })?;
+        Ok(stats)
+    }
+
+    /// Counts a decoded instruction.
+    pub fn add(&mut self, decoded: &DecodedInstruction) {
+        self.total += 1;
+        *self.mnemonics.entry(decoded.mnemonic).or_default() += 1;
+        *self.extensions.entry(decoded.extension).or_default() += 1;
+        *self.classes.entry(decoded.class()).or_default() += 1;
+        if decoded.bits.length == InstructionLen::TwoByte {
+            self.compressed += 1;
+        }
+    }
+
+    /// Counts an instruction that couldn't be decoded.
+    pub fn add_unknown(&mut self) {
+        self.unknown += 1;
+    }
+
+    /// Returns the number of instructions that were decoded.
+    pub fn total(&self) -> u64 {
+        self.total
+    }
+
+    /// Returns the number of instructions that couldn't be decoded.
+    pub fn unknown(&self) -> u64 {
+        self.unknown
+    }
+
+    /// Returns the breakdowns by mnemonic, extension, class and size, with their names.
+    fn breakdowns(&self) -> [(&'static str, Breakdown); 4] {
+        let extension_name =
+            |extensions: Extensions| extensions.names().collect::<Vec<_>>().join("+");
+        [
+            ("mnemonic", sorted(&self.mnemonics, |m| m.to_string())),
+            ("extension", sorted(&self.extensions, extension_name)),
+            ("class", sorted(&self.classes, |c| c.name().to_string())),
+            (
+                "size",
+                vec![
+                    ("compressed".to_string(), self.compressed),
+                    ("uncompressed".to_string(), self.total - self.compressed),
+                ],
+            ),
+        ]
+    }
+
+    /// Returns `count` as a percentage of the decoded instructions.
+    fn percent(&self, count: u64) -> f64 {
+        if self.total == 0 {
+            0.0
+        } else {
+            100.0 * count as f64 / self.total as f64
+        }
+    }
+
+    /// Writes a report with a table for each breakdown. Percentages are of the decoded
+    /// instructions.
+    pub fn write_text<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        writeln!(
+            w,
+            "{} instructions decoded, {} unknown",
+            self.total, self.unknown
+        )?;
+        for (breakdown, counts) in self.breakdowns().iter() {
+            writeln!(w, "\nBy {}:", breakdown)?;
+            for (name, count) in counts {
+                writeln!(
+                    w,
+                    "  {:<16} {:>12} {:>7.2}%",
+                    name,
+                    count,
+                    self.percent(*count)
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes the report as CSV, with a `breakdown,name,count,percent` row for each entry of each
+    /// breakdown, after rows for the decoded and unknown instruction counts.
+    pub fn write_csv<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        writeln!(w, "breakdown,name,count,percent")?;
+        writeln!(w, "total,decoded,{},", self.total)?;
+        writeln!(w, "total,unknown,{},", self.unknown)?;
+        for (breakdown, counts) in self.breakdowns().iter() {
+            for (name, count) in counts {
+                writeln!(
+                    w,
+                    "{},{},{},{:.2}",
+                    breakdown,
+                    name,
+                    count,
+                    self.percent(*count)
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Returns `counts` named by `name`, from the most to the least common, and alphabetically among
+/// equal counts.
+fn sorted<K: Copy + Eq + Hash>(counts: &HashMap<K, u64>, name: impl Fn(K) -> String) -> Breakdown {
+    let mut counts: Breakdown = counts
+        .iter()
+        .map(|(&key, &count)| (name(key), count))
+        .collect();
+    counts.sort_by(|(a_name, a_count), (b_name, b_count)| {
+        b_count.cmp(a_count).then_with(|| a_name.cmp(b_name))
+    });
+    counts
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util::{disassembler, rocket_trace};
+
+    const TRACE: &[(u64, &str)] = &[
+        (0x80000000, "00000297"),
+        (0x80000004, "4505"),
+        (0x80000006, "00a2b023"),
+        (0x8000000a, "02a50533"),
+        (0x8000000e, "fe050ae3"),
+        (0x80000012, "34102573"),
+        (0x80000016, "4505"),
+        (0x80000018, "0000000b"),
+    ];
+
+    fn trace_stats() -> InstructionStats {
+        let disasm = disassembler("rv64imafdc");
+        InstructionStats::from_trace(rocket_trace(TRACE).as_bytes(), &disasm, None).unwrap()
+    }
+
+    #[test]
+    fn text_report() {
+        let stats = trace_stats();
+        assert_eq!((stats.total(), stats.unknown()), (7, 1));
+
+        let mut output = Vec::new();
+        stats.write_text(&mut output).unwrap();
+        let expected = "\
+7 instructions decoded, 1 unknown
+
+By mnemonic:
+  c.li                        2   28.57%
+  auipc                       1   14.29%
+  beqz                        1   14.29%
+  csrr                        1   14.29%
+  mul                         1   14.29%
+  sd                          1   14.29%
+
+By extension:
+  i                           3   42.86%
+  c                           2   28.57%
+  m                           1   14.29%
+  zicsr                       1   14.29%
+
+By class:
+  alu                         4   57.14%
+  branch                      1   14.29%
+  csr                         1   14.29%
+  store                       1   14.29%
+
+By size:
+  compressed                  2   28.57%
+  uncompressed                5   71.43%
+";
+        assert_eq!(String::from_utf8(output).unwrap(), expected);
+    }
+
+    #[test]
+    fn csv_report() {
+        let mut output = Vec::new();
+        trace_stats().write_csv(&mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        let lines: Vec<_> = output.lines().collect();
+        assert_eq!(
+            lines[..5],
+            [
+                "breakdown,name,count,percent",
+                "total,decoded,7,",
+                "total,unknown,1,",
+                "mnemonic,c.li,2,28.57",
+                "mnemonic,auipc,1,14.29",
+            ]
+        );
+        assert_eq!(lines.last(), Some(&"size,uncompressed,5,71.43"));
+    }
+}
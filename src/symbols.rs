@@ -0,0 +1,145 @@
+use std::fmt;
+
+use object::{Object, ObjectSymbol, SymbolKind};
+
+/// A named address from an ELF symbol table.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Symbol {
+    pub name: String,
+    pub address: u64,
+    /// The size of the symbol in bytes, or 0 if unknown (e.g. for plain labels).
+    pub size: u64,
+}
+
+/// A reference to an address as an offset from the nearest preceding symbol, rendered like
+/// objdump as `<main>` or `<main+0x24>`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SymbolRef<'a> {
+    pub symbol: &'a Symbol,
+    pub offset: u64,
+}
+
+impl fmt::Display for SymbolRef<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.offset == 0 {
+            write!(f, "<{}>", self.symbol.name)
+        } else {
+            write!(f, "<{}+{:#x}>", self.symbol.name, self.offset)
+        }
+    }
+}
+
+/// Symbols sorted by address, for looking up the symbol containing any given address.
+#[derive(Clone, Debug, Default)]
+pub struct SymbolTable {
+    symbols: Vec<Symbol>,
+}
+
+impl SymbolTable {
+    /// Builds a table from `symbols`, in any order.
+    ///
+    /// Where several symbols share an address, the first one given is kept.
+    pub fn new(mut symbols: Vec<Symbol>) -> Self {
+        // A stable sort keeps the first symbol at each address in front, for `dedup_by_key`.
+        symbols.sort_by_key(|symbol| symbol.address);
+        symbols.dedup_by_key(|symbol| symbol.address);
+        Self { symbols }
+    }
+
+    /// Reads the code and data symbols from both the `.symtab` and `.dynsym` sections of an ELF
+    /// file.
+    ///
+    /// Assembler-local labels (`.L*`) and mapping symbols (`$x`, `$d`) are skipped. Where several
+    /// symbols share an address, sized functions are preferred, then global symbols.
+    pub fn from_elf(data: &[u8]) -> object::Result<Self> {
+        object::File::parse(data).map(|file| Self::from_file(&file))
+    }
+
+    /// Like `from_elf`, for an already-parsed file.
+    pub fn from_file(file: &object::File) -> Self {
+        let mut symbols = Vec::new();
+        for symbol in file.symbols().chain(file.dynamic_symbols()) {
+            if !symbol.is_definition() || symbol.section_index().is_none() {
+                continue;
+            }
+            let name = match symbol.name() {
+                Ok(name) => name,
+                Err(_) => continue,
+            };
+            if name.is_empty() || name.starts_with(".L") || name.starts_with('$') {
+                continue;
+            }
+
+            let is_function = symbol.kind() == SymbolKind::Text && symbol.size() != 0;
+            let priority = (!is_function, !symbol.is_global());
+            symbols.push((
+                priority,
+                Symbol {
+                    name: name.to_string(),
+                    address: symbol.address(),
+                    size: symbol.size(),
+                },
+            ));
+        }
+        symbols.sort_by_key(|(priority, _)| *priority);
+
+        Self::new(symbols.into_iter().map(|(_, symbol)| symbol).collect())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.symbols.is_empty()
+    }
+
+    /// Returns the nearest symbol at or before `address`, unless `address` lies past the end of a
+    /// symbol with a known size.
+    pub fn lookup(&self, address: u64) -> Option<SymbolRef<'_>> {
+        let idx = self
+            .symbols
+            .partition_point(|symbol| symbol.address <= address)
+            .checked_sub(1)?;
+        let symbol = &self.symbols[idx];
+        let offset = address - symbol.address;
+        if symbol.size != 0 && offset >= symbol.size {
+            return None;
+        }
+        Some(SymbolRef { symbol, offset })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util::symbol;
+
+    #[test]
+    fn lookup_symbols() {
+        let table = SymbolTable::new(vec![
+            symbol("main", 0x1000, 0x20),
+            symbol("_start", 0x800, 0),
+            symbol("main_alias", 0x1000, 0),
+            symbol("helper", 0x1040, 0),
+        ]);
+
+        assert_eq!(table.lookup(0x7ff), None);
+        assert_eq!(table.lookup(0x800).unwrap().to_string(), "<_start>");
+        assert_eq!(table.lookup(0xffc).unwrap().to_string(), "<_start+0x7fc>");
+        assert_eq!(table.lookup(0x1000).unwrap().to_string(), "<main>");
+        assert!(table.lookup(0x1024).is_none());
+        assert_eq!(table.lookup(0x101c).unwrap().to_string(), "<main+0x1c>");
+        assert_eq!(table.lookup(0x2000).unwrap().to_string(), "<helper+0xfc0>");
+    }
+
+    #[test]
+    fn symbols_from_elf() {
+        let table = SymbolTable::from_elf(include_bytes!("../inputs/elf/symbols.o")).unwrap();
+
+        // `.text` and `.data` both start at address 0 in a relocatable object, and the function
+        // `_start` wins over the data object `counter`.
+        assert_eq!(table.lookup(0x0).unwrap().to_string(), "<_start>");
+        assert_eq!(table.lookup(0xc).unwrap().to_string(), "<_start+0xc>");
+        assert_eq!(table.lookup(0x18).unwrap().to_string(), "<main+0xa>");
+        assert_eq!(table.lookup(0x22).unwrap().to_string(), "<square+0x4>");
+
+        assert!(SymbolTable::from_elf(b"not an ELF file").is_err());
+    }
+}
@@ -0,0 +1,35 @@
+//! Fixtures shared by the unit tests.
+
+use super::disasm::Disassembler;
+use super::symbols::Symbol;
+
+/// Returns a Rocket trace with a line for each instruction executed, given as its PC and its
+/// encoding in hex, which is repeated in a `DASM(...)` marker.
+pub(crate) fn rocket_trace(insts: &[(u64, &str)]) -> String {
+    insts
+        .iter()
+        .enumerate()
+        .map(|(idx, (pc, inst))| {
+            format!(
+                "C0: {} [1] pc=[{:x}] inst=[{}] DASM({})\n",
+                idx + 1,
+                pc,
+                inst,
+                inst
+            )
+        })
+        .collect()
+}
+
+/// Returns a disassembler for the ISA string `isa`, with pseudo-instructions.
+pub(crate) fn disassembler(isa: &str) -> Disassembler {
+    Disassembler::for_isa(isa.parse().unwrap(), true)
+}
+
+pub(crate) fn symbol(name: &str, address: u64, size: u64) -> Symbol {
+    Symbol {
+        name: name.to_string(),
+        address,
+        size,
+    }
+}
@@ -0,0 +1,328 @@
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+use regex::Regex;
+
+use super::parser::{self, CommitLine};
+
+/// An instruction found in a line of a trace, and where its disassembly goes.
+///
+/// The line is rewritten as `prefix`, `separator`, the disassembly, then `rest`. Any text between
+/// `prefix` and `rest` (such as a `DASM(...)` marker) is replaced.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TraceInst<'a> {
+    /// The text before the disassembly.
+    pub prefix: &'a str,
+    /// The instruction's PC, if the line has one, and the length of `prefix` up to the end of the
+    /// PC field, which is where a symbol is placed.
+    pub pc: Option<(u64, usize)>,
+    /// The text written between `prefix` and the disassembly.
+    pub separator: &'static str,
+    pub bits: u64,
+    /// The text after the disassembly.
+    pub rest: &'a str,
+}
+
+impl<'a> TraceInst<'a> {
+    /// Returns the rewrite of a commit-log style `line`, with the disassembly inserted after the
+    /// raw instruction.
+    fn after_raw_inst(commit: CommitLine<'a>, rest: &'a str, line: &'a str) -> Self {
+        let prefix_len = commit.pc_field.len() + commit.inst_field.len();
+        Self {
+            prefix: &line[..prefix_len],
+            pc: Some((commit.pc, commit.pc_field.len())),
+            separator: " ",
+            bits: commit.bits,
+            rest,
+        }
+    }
+}
+
+/// A trace format: how to find the instruction encodings (and PCs) in its lines.
+pub trait TraceFormat {
+    /// The name of the format, as given to `--trace-format`.
+    fn name(&self) -> &str;
+
+    /// Finds the first instruction in `line`, or returns `None` if the line doesn't have one.
+    fn find<'a>(&self, line: &'a str) -> Option<TraceInst<'a>>;
+
+    /// Returns whether a line can hold several instructions, in which case the `rest` of each one
+    /// found is searched for more.
+    fn multiple_per_line(&self) -> bool {
+        false
+    }
+}
+
+/// Rocket Chip (and BOOM) traces, where every `DASM(...)` marker is replaced by its disassembly,
+/// and each instruction's PC comes from a `pc=[...]` field between it and the marker before it.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Rocket;
+
+impl TraceFormat for Rocket {
+    fn name(&self) -> &str {
+        "rocket"
+    }
+
+    fn find<'a>(&self, line: &'a str) -> Option<TraceInst<'a>> {
+        let (rest, (prefix, bits)) = parser::parse_value(line).ok()?;
+        let pc = parser::parse_pc(prefix).ok().map(|(after_pc, pc)| {
+            // Include the closing bracket of the PC field.
+            let pc_end = prefix.len() - after_pc.len() + after_pc.starts_with(']') as usize;
+            (pc, pc_end)
+        });
+        Some(TraceInst {
+            prefix,
+            pc,
+            separator: "",
+            bits,
+            rest,
+        })
+    }
+
+    fn multiple_per_line(&self) -> bool {
+        true
+    }
+}
+
+/// Spike's `--log-commits` logs, such as `core   0: 3 0x0000000080000000 (0x00000297) x5 ...`.
+/// The disassembly goes after the raw instruction, ahead of the register and memory writes.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SpikeCommitLog;
+
+impl TraceFormat for SpikeCommitLog {
+    fn name(&self) -> &str {
+        "spike"
+    }
+
+    fn find<'a>(&self, line: &'a str) -> Option<TraceInst<'a>> {
+        let (rest, commit) = parser::parse_commit(line).ok()?;
+        Some(TraceInst::after_raw_inst(commit, rest, line))
+    }
+}
+
+/// BOOM commit logs, which are spike commit logs without the core number, such as
+/// `3 0x0000000080000000 (0x00000297) x 5 0x0000000080000000`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Boom;
+
+impl TraceFormat for Boom {
+    fn name(&self) -> &str {
+        "boom"
+    }
+
+    fn find<'a>(&self, line: &'a str) -> Option<TraceInst<'a>> {
+        let (rest, commit) = parser::parse_boom_commit(line).ok()?;
+        Some(TraceInst::after_raw_inst(commit, rest, line))
+    }
+}
+
+/// CVA6 instruction traces, such as `3000ns 3 M 0000000080000000 0 00000297 auipc t0, 0x0`.
+/// The disassembly replaces CVA6's own, which runs from the raw instruction to the register
+/// writes (such as `t0  :0000000080000000`) or the end of the line.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Cva6;
+
+impl TraceFormat for Cva6 {
+    fn name(&self) -> &str {
+        "cva6"
+    }
+
+    fn find<'a>(&self, line: &'a str) -> Option<TraceInst<'a>> {
+        let (rest, commit) = parser::parse_cva6(line).ok()?;
+        // Register writes are the first field with a colon; the disassembly ends before it.
+        let dasm = match rest.find(':') {
+            Some(colon) => rest[..colon]
+                .trim_end()
+                .trim_end_matches(|c: char| !c.is_whitespace()),
+            None => rest,
+        };
+        let dasm_len = dasm.trim_end().len();
+        Some(TraceInst::after_raw_inst(commit, &rest[dasm_len..], line))
+    }
+}
+
+/// The built-in formats, in the order `detect` tries them.
+pub const BUILTIN_FORMATS: [&dyn TraceFormat; 4] = [&Rocket, &SpikeCommitLog, &Boom, &Cva6];
+
+/// Returns the first built-in format that finds an instruction in any of `lines`.
+pub fn detect<S: AsRef<str>>(lines: &[S]) -> Option<&'static dyn TraceFormat> {
+    lines.iter().find_map(|line| {
+        BUILTIN_FORMATS
+            .iter()
+            .copied()
+            .find(|format| format.find(line.as_ref()).is_some())
+    })
+}
+
+/// Returns the built-in format called `name`.
+pub fn builtin(name: &str) -> Option<&'static dyn TraceFormat> {
+    BUILTIN_FORMATS
+        .iter()
+        .copied()
+        .find(|format| format.name() == name)
+}
+
+#[derive(Debug)]
+pub enum TraceFormatError {
+    Regex(regex::Error),
+    /// The regex doesn't have an `inst` group.
+    MissingInstGroup,
+}
+
+impl fmt::Display for TraceFormatError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Regex(e) => write!(f, "{}", e),
+            Self::MissingInstGroup => f.write_str("trace regex has no `inst` group"),
+        }
+    }
+}
+
+impl Error for TraceFormatError {}
+
+impl From<regex::Error> for TraceFormatError {
+    fn from(e: regex::Error) -> Self {
+        Self::Regex(e)
+    }
+}
+
+/// A user-defined format, matched by a regex with named groups.
+///
+/// The `inst` group holds the instruction in hex, and the optional `pc` group holds its PC, each
+/// with an optional `0x` prefix. The disassembly replaces the text of the optional `dasm` group,
+/// or otherwise is inserted after the whole match, following a space.
+///
+/// # Examples
+///
+/// ```
+/// use spike_dasm_rs::trace::{RegexFormat, TraceFormat};
+/// let format: RegexFormat = r"pc (?P<pc>\w+) (?P<dasm>inst (?P<inst>\w+))".parse().unwrap();
+/// let inst = format.find("pc 80000000 inst 00000297 x5").unwrap();
+/// assert_eq!((inst.prefix, inst.pc, inst.bits, inst.rest), ("pc 80000000 ", Some((0x80000000, 11)), 0x297, " x5"));
+/// ```
+#[derive(Clone, Debug)]
+pub struct RegexFormat {
+    regex: Regex,
+}
+
+impl FromStr for RegexFormat {
+    type Err = TraceFormatError;
+
+    fn from_str(pattern: &str) -> Result<Self, Self::Err> {
+        let regex = Regex::new(pattern)?;
+        if !regex.capture_names().any(|name| name == Some("inst")) {
+            return Err(TraceFormatError::MissingInstGroup);
+        }
+        Ok(Self { regex })
+    }
+}
+
+/// Parses a hex number of up to 64 bits, with an optional `0x` prefix.
+fn parse_hex(s: &str) -> Option<u64> {
+    let s = s
+        .strip_prefix("0x")
+        .or_else(|| s.strip_prefix("0X"))
+        .unwrap_or(s);
+    u64::from_str_radix(s, 16).ok()
+}
+
+impl TraceFormat for RegexFormat {
+    fn name(&self) -> &str {
+        self.regex.as_str()
+    }
+
+    fn find<'a>(&self, line: &'a str) -> Option<TraceInst<'a>> {
+        let captures = self.regex.captures(line)?;
+        let bits = parse_hex(captures.name("inst")?.as_str())?;
+        let (start, end, separator) = match captures.name("dasm") {
+            Some(dasm) => (dasm.start(), dasm.end(), ""),
+            None => {
+                let end = captures.get(0)?.end();
+                (end, end, " ")
+            }
+        };
+        let pc = captures.name("pc").and_then(|pc| {
+            let pc_end = pc.end().min(start);
+            Some((parse_hex(pc.as_str())?, pc_end))
+        });
+        Some(TraceInst {
+            prefix: &line[..start],
+            pc,
+            separator,
+            bits,
+            rest: &line[end..],
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn detect_formats() {
+        let detected = |lines: &[&str]| detect(lines).map(|format| format.name());
+
+        let rocket = [
+            "",
+            "C0: 19 [1] pc=[0000000000010040] inst=[00000517] DASM(00000517)",
+        ];
+        assert_eq!(detected(&rocket), Some("rocket"));
+        let spike = ["core   0: 3 0x0000000080000000 (0x00000297) x5  0x0000000080000000"];
+        assert_eq!(detected(&spike), Some("spike"));
+        let boom = ["3 0x0000000080000000 (0x00000297) x 5 0x0000000080000000"];
+        assert_eq!(detected(&boom), Some("boom"));
+        let cva6 = ["  3000ns  3 M 0000000080000000 0 00000297 auipc t0, 0x0"];
+        assert_eq!(detected(&cva6), Some("cva6"));
+        // CVA6's own disassembly is replaced, up to the register writes if there are any.
+        let inst = Cva6.find(cva6[0]).unwrap();
+        assert_eq!(inst.prefix, "  3000ns  3 M 0000000080000000 0 00000297");
+        assert_eq!((inst.separator, inst.bits, inst.rest), (" ", 0x297, ""));
+        let inst = Cva6
+            .find("  3000ns  3 M 0000000080000000 0 00000297 auipc   t0, 0x0     t0  :0000000080000000")
+            .unwrap();
+        assert_eq!(inst.rest, "     t0  :0000000080000000");
+        assert_eq!(detected(&["nothing to see here"]), None);
+    }
+
+    #[test]
+    fn find_insts() {
+        let inst = Rocket
+            .find("C0: 19 [1] pc=[0000000000010040] inst=[00000517] DASM(00000517) end")
+            .unwrap();
+        assert_eq!(
+            inst.prefix,
+            "C0: 19 [1] pc=[0000000000010040] inst=[00000517] "
+        );
+        assert_eq!(inst.pc, Some((0x10040, 32)));
+        assert_eq!((inst.separator, inst.bits, inst.rest), ("", 0x517, " end"));
+
+        let inst = SpikeCommitLog
+            .find("core   0: 3 0x0000000080000000 (0x00000297) x5  0x0000000080000000")
+            .unwrap();
+        assert_eq!(inst.prefix, "core   0: 3 0x0000000080000000 (0x00000297)");
+        assert_eq!(inst.pc, Some((0x80000000, 30)));
+        assert_eq!(inst.separator, " ");
+        assert_eq!(inst.rest, " x5  0x0000000080000000");
+    }
+
+    #[test]
+    fn regex_formats() {
+        let format: RegexFormat = r"insn=(?P<inst>\w+)".parse().unwrap();
+        let inst = format.find("@12 insn=0x4505 a0").unwrap();
+        assert_eq!(inst.prefix, "@12 insn=0x4505");
+        assert_eq!((inst.pc, inst.separator, inst.bits), (None, " ", 0x4505));
+        assert_eq!(inst.rest, " a0");
+        assert!(format.find("@12 insn=zz").is_none());
+
+        assert!(matches!(
+            "insn=(?P<bits>\\w+)".parse::<RegexFormat>(),
+            Err(TraceFormatError::MissingInstGroup)
+        ));
+        assert!(matches!(
+            "insn=(?P<inst>".parse::<RegexFormat>(),
+            Err(TraceFormatError::Regex(_))
+        ));
+    }
+}